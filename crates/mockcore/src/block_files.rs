@@ -0,0 +1,66 @@
+use {
+  super::*,
+  std::{fs::OpenOptions, io::Write, path::Path},
+};
+
+// small so that even short test chains span several files
+const MAX_BLOCKFILE_SIZE: u64 = 4096;
+
+const XOR_KEY: [u8; 8] = [0x3f, 0x5a, 0x01, 0xc4, 0x77, 0x90, 0x2e, 0xb8];
+
+/// Stand-in for Bitcoin Core's block storage, appending every block it
+/// receives to obfuscated `blk*.dat` files, including blocks that are later
+/// invalidated.
+#[derive(Debug)]
+pub(crate) struct BlockFiles {
+  dir: PathBuf,
+  file: u32,
+  magic: [u8; 4],
+  offset: u64,
+}
+
+impl BlockFiles {
+  pub(crate) fn new(dir: PathBuf, network: Network) -> Self {
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("xor.dat"), XOR_KEY).unwrap();
+
+    Self {
+      dir,
+      file: 0,
+      magic: network.magic().to_bytes(),
+      offset: 0,
+    }
+  }
+
+  pub(crate) fn dir(&self) -> &Path {
+    &self.dir
+  }
+
+  pub(crate) fn write(&mut self, block: &Block) {
+    let block = serialize(block);
+
+    if self.offset > 0 && self.offset + 8 + block.len() as u64 > MAX_BLOCKFILE_SIZE {
+      self.file += 1;
+      self.offset = 0;
+    }
+
+    let mut data = Vec::new();
+    data.extend_from_slice(&self.magic);
+    data.extend_from_slice(&u32::try_from(block.len()).unwrap().to_le_bytes());
+    data.extend_from_slice(&block);
+
+    for (i, byte) in data.iter_mut().enumerate() {
+      *byte ^= XOR_KEY[(self.offset as usize + i) % XOR_KEY.len()];
+    }
+
+    OpenOptions::new()
+      .create(true)
+      .append(true)
+      .open(self.dir.join(format!("blk{:05}.dat", self.file)))
+      .unwrap()
+      .write_all(&data)
+      .unwrap();
+
+    self.offset += data.len() as u64;
+  }
+}
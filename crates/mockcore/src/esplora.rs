@@ -0,0 +1,166 @@
+use {
+  super::*,
+  std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+  },
+};
+
+/// Stand-in for an Esplora REST API server, serving just the endpoints ord
+/// uses to fetch blocks, transactions, and the mempool.
+#[derive(Debug)]
+pub(crate) struct Esplora {
+  port: u16,
+}
+
+impl Esplora {
+  pub(crate) fn spawn(state: Arc<Mutex<State>>) -> Self {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    thread::spawn(move || {
+      for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+          continue;
+        };
+
+        let state = state.clone();
+
+        thread::spawn(move || Self::serve(stream, &state));
+      }
+    });
+
+    Self { port }
+  }
+
+  pub(crate) fn url(&self) -> String {
+    format!("http://127.0.0.1:{}", self.port)
+  }
+
+  fn serve(stream: TcpStream, state: &Mutex<State>) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+    loop {
+      let mut request_line = String::new();
+
+      if reader.read_line(&mut request_line).unwrap_or(0) == 0 {
+        return;
+      }
+
+      loop {
+        let mut header = String::new();
+        if reader.read_line(&mut header).unwrap_or(0) == 0 {
+          return;
+        }
+        if header == "\r\n" {
+          break;
+        }
+      }
+
+      let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+
+      let (status, body) = match Self::route(&state.lock().unwrap(), path) {
+        Some(body) => ("200 OK", body),
+        None => ("404 Not Found", b"not found".to_vec()),
+      };
+
+      let mut response = format!(
+        "HTTP/1.1 {status}\r\ncontent-length: {}\r\n\r\n",
+        body.len()
+      )
+      .into_bytes();
+
+      response.extend(body);
+
+      if (&stream).write_all(&response).is_err() {
+        return;
+      }
+    }
+  }
+
+  fn route(state: &State, path: &str) -> Option<Vec<u8>> {
+    let components = path
+      .trim_start_matches('/')
+      .split('/')
+      .collect::<Vec<&str>>();
+
+    match components.as_slice() {
+      ["blocks", "tip", "height"] => Some((state.hashes.len() - 1).to_string().into_bytes()),
+      ["block-height", height] => Some(
+        state
+          .hashes
+          .get(height.parse::<usize>().ok()?)?
+          .to_string()
+          .into_bytes(),
+      ),
+      ["block", hash] => {
+        let hash = hash.parse::<BlockHash>().ok()?;
+
+        Some(
+          serde_json::to_vec(&serde_json::json!({
+            "id": hash,
+            "height": state.hashes.iter().position(|block_hash| *block_hash == hash)?,
+          }))
+          .unwrap(),
+        )
+      }
+      ["block", hash, "header"] => Some(
+        hex::encode(serialize(
+          &state.blocks.get(&hash.parse::<BlockHash>().ok()?)?.header,
+        ))
+        .into_bytes(),
+      ),
+      ["block", hash, "raw"] => Some(serialize(
+        state.blocks.get(&hash.parse::<BlockHash>().ok()?)?,
+      )),
+      ["mempool", "txids"] => Some(
+        serde_json::to_vec(
+          &state
+            .mempool
+            .iter()
+            .map(|tx| tx.compute_txid())
+            .collect::<Vec<Txid>>(),
+        )
+        .unwrap(),
+      ),
+      ["tx", txid, "outspend", vout] => {
+        let outpoint = OutPoint {
+          txid: txid.parse().ok()?,
+          vout: vout.parse().ok()?,
+        };
+
+        Some(
+          serde_json::to_vec(&serde_json::json!({
+            "spent": !state.utxos.contains_key(&outpoint),
+          }))
+          .unwrap(),
+        )
+      }
+      ["tx", txid, "raw"] => {
+        let txid = txid.parse::<Txid>().ok()?;
+        Some(serialize(state.transactions.get(&txid).or_else(|| {
+          state.mempool.iter().find(|tx| tx.compute_txid() == txid)
+        })?))
+      }
+      ["tx", txid, "status"] => {
+        let txid = txid.parse::<Txid>().ok()?;
+
+        let height = state.txid_to_block_height.get(&txid);
+
+        if height.is_none() && !state.mempool.iter().any(|tx| tx.compute_txid() == txid) {
+          return None;
+        }
+
+        Some(
+          serde_json::to_vec(&serde_json::json!({
+            "confirmed": height.is_some(),
+            "block_height": height,
+            "block_hash": height.map(|height| state.hashes[*height as usize]),
+          }))
+          .unwrap(),
+        )
+      }
+      _ => None,
+    }
+  }
+}
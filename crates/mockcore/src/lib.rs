@@ -30,6 +30,8 @@ use {
     LoadWalletResult, SignRawTransactionInput, SignRawTransactionResult, Timestamp,
     WalletProcessPsbtResult, WalletTxInfo,
   },
+  block_files::BlockFiles,
+  esplora::Esplora,
   jsonrpc_core::{IoHandler, Value},
   jsonrpc_http_server::{CloseHandle, ServerBuilder},
   serde::{Deserialize, Serialize},
@@ -45,14 +47,18 @@ use {
   },
   tempfile::TempDir,
   wallet::Wallet,
+  zmq::Publisher,
 };
 
 const COIN_VALUE: u64 = 100_000_000;
 
 mod api;
+mod block_files;
+mod esplora;
 mod server;
 mod state;
 mod wallet;
+mod zmq;
 
 #[derive(Clone, PartialEq, Eq, Debug, Deserialize, Serialize)]
 pub struct Descriptor {
@@ -72,19 +78,36 @@ pub struct ListDescriptorsResult {
 
 pub fn builder() -> Builder {
   Builder {
+    block_files: false,
+    esplora: false,
     fail_lock_unspent: false,
     network: Network::Bitcoin,
     version: 240000,
+    zmq: false,
   }
 }
 
 pub struct Builder {
+  block_files: bool,
+  esplora: bool,
   fail_lock_unspent: bool,
   network: Network,
   version: usize,
+  zmq: bool,
 }
 
 impl Builder {
+  pub fn block_files(self, block_files: bool) -> Self {
+    Self {
+      block_files,
+      ..self
+    }
+  }
+
+  pub fn esplora(self, esplora: bool) -> Self {
+    Self { esplora, ..self }
+  }
+
   pub fn fail_lock_unspent(self, fail_lock_unspent: bool) -> Self {
     Self {
       fail_lock_unspent,
@@ -100,12 +123,20 @@ impl Builder {
     Self { version, ..self }
   }
 
+  pub fn zmq(self, zmq: bool) -> Self {
+    Self { zmq, ..self }
+  }
+
   pub fn build(self) -> Handle {
     let state = Arc::new(Mutex::new(State::new(
       self.network,
       self.version,
       self.fail_lock_unspent,
     )));
+
+    if self.zmq {
+      state.lock().unwrap().zmq = Some(Arc::new(Publisher::spawn()));
+    }
     let server = Server::new(state.clone());
     let mut io = IoHandler::default();
     io.extend_with(server.to_delegate());
@@ -137,8 +168,30 @@ impl Builder {
 
     fs::write(tempdir.path().join(".cookie"), "username:password").unwrap();
 
+    if self.block_files {
+      let mut state = state.lock().unwrap();
+
+      let dir = match self.network {
+        Network::Bitcoin => tempdir.path().to_owned(),
+        Network::Testnet => tempdir.path().join("testnet3"),
+        network => tempdir.path().join(network.to_string()),
+      }
+      .join("blocks");
+
+      let mut block_files = BlockFiles::new(dir, self.network);
+
+      for hash in &state.hashes {
+        block_files.write(&state.blocks[hash]);
+      }
+
+      state.block_files = Some(block_files);
+    }
+
+    let esplora = self.esplora.then(|| Esplora::spawn(state.clone()));
+
     Handle {
       close_handle: Some(close_handle),
+      esplora,
       tempdir,
       port,
       state,
@@ -216,6 +269,7 @@ impl<'a> Default for TransactionTemplate<'a> {
 
 pub struct Handle {
   close_handle: Option<CloseHandle>,
+  esplora: Option<Esplora>,
   port: u16,
   state: Arc<Mutex<State>>,
   tempdir: TempDir,
@@ -226,6 +280,37 @@ impl Handle {
     format!("http://127.0.0.1:{}", self.port)
   }
 
+  pub fn zmq_url(&self) -> String {
+    self
+      .state()
+      .zmq
+      .as_ref()
+      .expect("mockcore was built without ZMQ publisher")
+      .url()
+  }
+
+  pub fn esplora_url(&self) -> String {
+    self
+      .esplora
+      .as_ref()
+      .expect("mockcore was built without Esplora")
+      .url()
+  }
+
+  pub fn bitcoin_data_dir(&self) -> PathBuf {
+    self.tempdir.path().to_owned()
+  }
+
+  pub fn blocks_dir(&self) -> PathBuf {
+    self
+      .state()
+      .block_files
+      .as_ref()
+      .expect("mockcore was built without block files")
+      .dir()
+      .to_owned()
+  }
+
   pub fn address(&self, output: OutPoint) -> Address {
     let state = self.state();
 
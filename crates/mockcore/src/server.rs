@@ -241,6 +241,17 @@ impl Api for Server {
     )
   }
 
+  fn get_raw_mempool(&self) -> Result<Vec<Txid>, jsonrpc_core::Error> {
+    Ok(
+      self
+        .state()
+        .mempool
+        .iter()
+        .map(|tx| tx.compute_txid())
+        .collect(),
+    )
+  }
+
   fn get_tx_out(
     &self,
     txid: Txid,
@@ -532,7 +543,7 @@ impl Api for Server {
       }
     }
 
-    state.mempool.push(tx.clone());
+    state.add_to_mempool(tx.clone());
 
     Ok(tx.compute_txid().to_string())
   }
@@ -603,7 +614,7 @@ impl Api for Server {
 
     let txid = transaction.compute_txid();
 
-    state.mempool.push(transaction);
+    state.add_to_mempool(transaction);
 
     Ok(txid)
   }
@@ -673,8 +684,13 @@ impl Api for Server {
 
     let blockhash = tx_height.map(|tx_height| state.hashes[usize::try_from(*tx_height).unwrap()]);
 
+    let transaction = state
+      .transactions
+      .get(&txid)
+      .or_else(|| state.mempool.iter().find(|tx| tx.compute_txid() == txid));
+
     if verbose.unwrap_or(false) {
-      match state.transactions.get(&txid) {
+      match transaction {
         Some(transaction) => Ok(
           serde_json::to_value(GetRawTransactionResult {
             in_active_chain: Some(true),
@@ -713,7 +729,7 @@ impl Api for Server {
         None => Err(Self::not_found()),
       }
     } else {
-      match state.transactions.get(&txid) {
+      match transaction {
         Some(tx) => Ok(Value::String(hex::encode(serialize(tx)))),
         None => Err(Self::not_found()),
       }
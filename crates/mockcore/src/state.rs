@@ -2,6 +2,7 @@ use super::*;
 
 #[derive(Debug)]
 pub struct State {
+  pub(crate) block_files: Option<BlockFiles>,
   pub blocks: BTreeMap<BlockHash, Block>,
   pub descriptors: Vec<(String, bitcoincore_rpc::json::Timestamp)>,
   pub fail_lock_unspent: bool,
@@ -19,6 +20,7 @@ pub struct State {
   pub change_addresses: Vec<Address>,
   pub wallets: BTreeSet<String>,
   pub wallet: Wallet,
+  pub(crate) zmq: Option<Arc<Publisher>>,
 }
 
 impl State {
@@ -32,6 +34,7 @@ impl State {
     blocks.insert(genesis_block_hash, genesis_block);
 
     Self {
+      block_files: None,
       blocks,
       change_addresses: Vec::new(),
       descriptors: Vec::new(),
@@ -49,6 +52,7 @@ impl State {
       version,
       wallets: BTreeSet::new(),
       wallet: Wallet::new(network),
+      zmq: None,
     }
   }
 
@@ -70,7 +74,11 @@ impl State {
   }
 
   pub(crate) fn clear(&mut self) {
+    let block_files = self.block_files.take();
+    let zmq = self.zmq.take();
     *self = Self::new(self.network, self.version, self.fail_lock_unspent);
+    self.block_files = block_files;
+    self.zmq = zmq;
   }
 
   #[track_caller]
@@ -180,6 +188,18 @@ impl State {
     self.hashes.push(block.block_hash());
     self.nonce += 1;
 
+    if let Some(block_files) = &mut self.block_files {
+      block_files.write(&block);
+    }
+
+    if let Some(zmq) = &self.zmq {
+      for tx in &block.txdata {
+        zmq.rawtx(tx);
+      }
+
+      zmq.hashblock(block.block_hash());
+    }
+
     block
   }
 
@@ -276,11 +296,19 @@ impl State {
 
     let txid = tx.compute_txid();
 
-    self.mempool.push(tx);
+    self.add_to_mempool(tx);
 
     txid
   }
 
+  pub(crate) fn add_to_mempool(&mut self, tx: Transaction) {
+    if let Some(zmq) = &self.zmq {
+      zmq.rawtx(&tx);
+    }
+
+    self.mempool.push(tx);
+  }
+
   pub(crate) fn mempool(&self) -> &[Transaction] {
     &self.mempool
   }
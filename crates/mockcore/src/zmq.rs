@@ -0,0 +1,177 @@
+use {
+  super::*,
+  std::{
+    io::{self, Read, Write},
+    net::{TcpListener, TcpStream},
+  },
+};
+
+const COMMAND: u8 = 0x04;
+const LONG: u8 = 0x02;
+const MORE: u8 = 0x01;
+
+#[derive(Debug)]
+struct Subscriber {
+  stream: TcpStream,
+  topics: Arc<Mutex<BTreeSet<Vec<u8>>>>,
+}
+
+/// Stand-in for Bitcoin Core's ZMQ publisher, speaking just enough ZMTP 3.0
+/// to serve `hashblock` and `rawtx` notifications.
+#[derive(Debug)]
+pub(crate) struct Publisher {
+  port: u16,
+  sequence: Mutex<u32>,
+  subscribers: Arc<Mutex<Vec<Subscriber>>>,
+}
+
+impl Publisher {
+  pub(crate) fn spawn() -> Self {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let subscribers = Arc::new(Mutex::new(Vec::new()));
+
+    {
+      let subscribers = subscribers.clone();
+      thread::spawn(move || {
+        for stream in listener.incoming() {
+          let Ok(stream) = stream else {
+            continue;
+          };
+
+          let subscribers = subscribers.clone();
+          thread::spawn(move || Self::serve(stream, subscribers).ok());
+        }
+      });
+    }
+
+    Self {
+      port,
+      sequence: Mutex::new(0),
+      subscribers,
+    }
+  }
+
+  pub(crate) fn url(&self) -> String {
+    format!("tcp://127.0.0.1:{}", self.port)
+  }
+
+  pub(crate) fn hashblock(&self, block_hash: BlockHash) {
+    let mut hash = block_hash.to_byte_array();
+    hash.reverse();
+    self.publish(b"hashblock", &hash);
+  }
+
+  pub(crate) fn rawtx(&self, tx: &Transaction) {
+    self.publish(b"rawtx", &serialize(tx));
+  }
+
+  fn publish(&self, topic: &[u8], body: &[u8]) {
+    let mut sequence = self.sequence.lock().unwrap();
+
+    self.subscribers.lock().unwrap().retain_mut(|subscriber| {
+      if !subscriber
+        .topics
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|prefix| topic.starts_with(prefix))
+      {
+        return true;
+      }
+
+      write_frame(&mut subscriber.stream, MORE, topic)
+        .and_then(|()| write_frame(&mut subscriber.stream, MORE, body))
+        .and_then(|()| write_frame(&mut subscriber.stream, 0, &sequence.to_le_bytes()))
+        .is_ok()
+    });
+
+    *sequence += 1;
+  }
+
+  fn serve(mut stream: TcpStream, subscribers: Arc<Mutex<Vec<Subscriber>>>) -> io::Result<()> {
+    let mut greeting = [0; 64];
+    greeting[0] = 0xFF;
+    greeting[9] = 0x7F;
+    greeting[10] = 3;
+    greeting[12..16].copy_from_slice(b"NULL");
+    greeting[32] = 1;
+    stream.write_all(&greeting)?;
+
+    stream.read_exact(&mut greeting)?;
+
+    let mut ready = vec![5];
+    ready.extend_from_slice(b"READY");
+    ready.push(11);
+    ready.extend_from_slice(b"Socket-Type");
+    ready.extend_from_slice(&3u32.to_be_bytes());
+    ready.extend_from_slice(b"PUB");
+    write_frame(&mut stream, COMMAND, &ready)?;
+
+    read_frame(&mut stream)?;
+
+    let topics = Arc::new(Mutex::new(BTreeSet::new()));
+
+    subscribers.lock().unwrap().push(Subscriber {
+      stream: stream.try_clone()?,
+      topics: topics.clone(),
+    });
+
+    loop {
+      let (flags, frame) = read_frame(&mut stream)?;
+
+      if flags & COMMAND != 0 {
+        continue;
+      }
+
+      match frame.split_first() {
+        Some((1, topic)) => {
+          topics.lock().unwrap().insert(topic.to_vec());
+        }
+        Some((0, topic)) => {
+          topics.lock().unwrap().remove(topic);
+        }
+        _ => {}
+      }
+    }
+  }
+}
+
+fn read_frame(stream: &mut TcpStream) -> io::Result<(u8, Vec<u8>)> {
+  let mut flags = [0];
+  stream.read_exact(&mut flags)?;
+
+  let size = if flags[0] & LONG != 0 {
+    let mut size = [0; 8];
+    stream.read_exact(&mut size)?;
+    usize::try_from(u64::from_be_bytes(size)).unwrap()
+  } else {
+    let mut size = [0];
+    stream.read_exact(&mut size)?;
+    size[0].into()
+  };
+
+  let mut frame = vec![0; size];
+  stream.read_exact(&mut frame)?;
+
+  Ok((flags[0], frame))
+}
+
+fn write_frame(stream: &mut TcpStream, flags: u8, body: &[u8]) -> io::Result<()> {
+  let mut frame = Vec::new();
+
+  match u8::try_from(body.len()) {
+    Ok(size) => {
+      frame.push(flags);
+      frame.push(size);
+    }
+    Err(_) => {
+      frame.push(flags | LONG);
+      frame.extend_from_slice(&u64::try_from(body.len()).unwrap().to_be_bytes());
+    }
+  }
+
+  frame.extend_from_slice(body);
+
+  stream.write_all(&frame)
+}
@@ -4,10 +4,14 @@ use {
 };
 
 pub use crate::{
+  index::event::Event,
   subcommand::decode::RawOutput as Decode,
   templates::{
-    BlocksHtml as Blocks, RuneHtml as Rune, RunesHtml as Runes, StatusHtml as Status,
-    TransactionHtml as Transaction,
+    BlocksHtml as Blocks, CollectionHoldersHtml as CollectionHolders,
+    ContentHashHtml as ContentHash, DelegatorsHtml as Delegators, MetaprotocolHtml as Metaprotocol,
+    MetaprotocolsHtml as Metaprotocols, RuneActivityHtml as RuneActivity,
+    RuneHoldersHtml as RuneHolders, RuneHtml as Rune, RunesHtml as Runes, StatusHtml as Status,
+    TextSearchHtml as TextSearch, TransactionHtml as Transaction,
   },
 };
 
@@ -89,6 +93,26 @@ pub struct ChildInscriptions {
   pub page: usize,
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct CollectionHolder {
+  pub address: Option<String>,
+  pub inscriptions: Vec<InscriptionId>,
+  pub script_pubkey: ScriptBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct EventEntry {
+  pub id: u64,
+  pub event: Event,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Events {
+  pub events: Vec<EventEntry>,
+  pub more: bool,
+  pub next: u64,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct Inscription {
   pub address: Option<String>,
@@ -97,12 +121,14 @@ pub struct Inscription {
   pub children: Vec<InscriptionId>,
   pub content_length: Option<usize>,
   pub content_type: Option<String>,
+  pub duplicate_of: Option<InscriptionId>,
   pub effective_content_type: Option<String>,
   pub fee: u64,
   pub height: u32,
   pub id: InscriptionId,
   pub next: Option<InscriptionId>,
   pub number: i32,
+  pub original: Option<bool>,
   pub parents: Vec<InscriptionId>,
   pub previous: Option<InscriptionId>,
   pub rune: Option<SpacedRune>,
@@ -112,6 +138,22 @@ pub struct Inscription {
   pub value: Option<u64>,
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct InscriptionHistory {
+  pub id: InscriptionId,
+  pub number: i32,
+  pub transfers: Vec<InscriptionTransfer>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct InscriptionTransfer {
+  pub address: Option<String>,
+  pub height: u32,
+  pub new_satpoint: SatPoint,
+  pub old_satpoint: Option<SatPoint>,
+  pub txid: Txid,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct InscriptionRecursive {
   pub charms: Vec<Charm>,
@@ -150,12 +192,29 @@ pub struct Inscriptions {
   pub page_index: u32,
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct MempoolInscription {
+  pub id: InscriptionId,
+  pub new: bool,
+  pub satpoint: Option<SatPoint>,
+  pub txid: Txid,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct MempoolRuneOutput {
+  pub address: Option<Address<NetworkUnchecked>>,
+  pub outpoint: OutPoint,
+  pub runes: BTreeMap<SpacedRune, Pile>,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
 pub struct Output {
   pub address: Option<Address<NetworkUnchecked>>,
   pub indexed: bool,
   pub inscriptions: Vec<InscriptionId>,
   pub outpoint: OutPoint,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub pending: Option<PendingOutput>,
   pub runes: BTreeMap<SpacedRune, Pile>,
   pub sat_ranges: Option<Vec<(u64, u64)>>,
   pub script_pubkey: ScriptBuf,
@@ -183,6 +242,7 @@ impl Output {
       indexed,
       inscriptions,
       outpoint,
+      pending: None,
       runes,
       sat_ranges,
       script_pubkey: tx_out.script_pubkey,
@@ -193,6 +253,39 @@ impl Output {
   }
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct PendingAddress {
+  pub inscriptions: Vec<InscriptionId>,
+  pub outputs: Vec<OutPoint>,
+  pub runes_balances: Vec<(SpacedRune, Decimal, Option<char>)>,
+  pub spent: Vec<OutPoint>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct PendingOutput {
+  pub inscriptions: Vec<InscriptionId>,
+  pub runes: BTreeMap<SpacedRune, Pile>,
+  pub spent_by: Option<Txid>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct RuneHolder {
+  pub address: Option<String>,
+  pub amount: u128,
+  pub outpoint: Option<OutPoint>,
+  pub script_pubkey: Option<ScriptBuf>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct RuneSnapshot {
+  pub divisibility: u8,
+  pub height: u32,
+  pub holders: Vec<RuneHolder>,
+  pub id: RuneId,
+  pub rune: SpacedRune,
+  pub symbol: Option<char>,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Sat {
   pub block: u32,
@@ -224,10 +317,37 @@ pub struct SatInscriptions {
   pub page: u64,
 }
 
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct TextSearchResult {
+  pub id: InscriptionId,
+  pub score: f64,
+  pub snippet: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct AddressHistory {
+  pub entries: Vec<AddressHistoryEntry>,
+  pub next: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+pub struct AddressHistoryEntry {
+  pub height: u32,
+  pub inscriptions_received: Vec<InscriptionId>,
+  pub inscriptions_sent: Vec<InscriptionId>,
+  pub received: u64,
+  pub runes_received: Vec<(SpacedRune, Decimal, Option<char>)>,
+  pub runes_sent: Vec<(SpacedRune, Decimal, Option<char>)>,
+  pub sent: u64,
+  pub txid: Txid,
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct AddressInfo {
   pub outputs: Vec<OutPoint>,
   pub inscriptions: Vec<InscriptionId>,
   pub sat_balance: u64,
   pub runes_balances: Vec<(SpacedRune, Decimal, Option<char>)>,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub pending: Option<PendingAddress>,
 }
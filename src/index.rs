@@ -1,26 +1,32 @@
 use {
   self::{
+    address_history::{AddressHistory, AddressHistoryEntry},
+    block_files::BlockFiles,
+    block_source::BlockSource,
+    check::{Checker, Violation},
     entry::{
       Entry, HeaderValue, InscriptionEntry, InscriptionEntryValue, InscriptionIdValue,
-      OutPointValue, RuneEntryValue, RuneIdValue, SatPointValue, SatRange, TxidValue,
+      OutPointValue, RuneActivityEntryValue, RuneEntryValue, RuneIdValue, SatPointValue, SatRange,
+      TransferEntry, TransferEntryValue, TxidValue,
     },
     event::Event,
     lot::Lot,
+    mempool::Mempool,
+    read_only::{ReadOnlyBackend, Snapshot},
     reorg::Reorg,
+    undo_log::UndoLog,
     updater::Updater,
     utxo_entry::{ParsedUtxoEntry, UtxoEntry, UtxoEntryBuf},
   },
   super::*,
   crate::{
+    metrics::{Histogram, Metrics},
     runes::MintError,
     subcommand::{find::FindRangeOutput, server::query},
     templates::StatusHtml,
   },
   bitcoin::block::Header,
-  bitcoincore_rpc::{
-    json::{GetBlockHeaderResult, GetBlockStatsResult},
-    Client,
-  },
+  bitcoincore_rpc::json::{GetBlockHeaderResult, GetBlockStatsResult},
   chrono::SubsecRound,
   indicatif::{ProgressBar, ProgressStyle},
   log::log_enabled,
@@ -32,48 +38,79 @@ use {
   std::{
     collections::HashMap,
     io::{BufWriter, Write},
-    sync::Once,
+    sync::{Once, RwLockReadGuard},
   },
 };
 
-pub use self::entry::RuneEntry;
+pub use self::entry::{RuneActivityEntry, RuneActivityKind, RuneEntry};
+
+pub(crate) use self::read_only::Readers;
 
+mod address_history;
+mod allocation;
+mod block_files;
+pub(crate) mod block_source;
+pub mod check;
 pub(crate) mod entry;
 pub mod event;
 mod fetcher;
+pub(crate) mod full_text;
 mod lot;
+mod mempool;
+mod read_only;
 mod reorg;
 mod rtx;
+mod undo_log;
 mod updater;
 mod utxo_entry;
 
 #[cfg(test)]
 pub(crate) mod testing;
 
-const SCHEMA_VERSION: u64 = 29;
+const SCHEMA_VERSION: u64 = 43;
 
+define_multimap_table! { CONTENT_HASH_TO_SEQUENCE_NUMBER, &[u8; 32], u32 }
+define_multimap_table! { DELEGATE_TO_SEQUENCE_NUMBER, InscriptionIdValue, u32 }
+define_multimap_table! { RUNE_ID_TO_OUTPOINT_BALANCE, RuneIdValue, (u128, OutPointValue) }
+define_multimap_table! { RUNE_ID_TO_SCRIPT_PUBKEY_BALANCE, RuneIdValue, (u128, &[u8]) }
 define_multimap_table! { SAT_TO_SEQUENCE_NUMBER, u64, u32 }
 define_multimap_table! { SEQUENCE_NUMBER_TO_CHILDREN, u32, u32 }
 define_multimap_table! { SCRIPT_PUBKEY_TO_OUTPOINT, &[u8], OutPointValue }
+define_multimap_table! { TERM_TO_SEQUENCE_NUMBER, &str, u32 }
+define_table! { CONTENT_TYPE_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID, (&str, u32), InscriptionIdValue }
+define_table! { CONTENT_TYPE_TO_COUNT, &str, u64 }
+define_table! { EVENT_ID_TO_EVENT, u64, &[u8] }
 define_table! { HEIGHT_TO_BLOCK_HEADER, u32, &HeaderValue }
+define_table! { HEIGHT_TO_LAST_EVENT_ID, u32, u64 }
 define_table! { HEIGHT_TO_LAST_SEQUENCE_NUMBER, u32, u32 }
+define_table! { HEIGHT_TO_UNDO_LOG, u32, &[u8] }
 define_table! { HOME_INSCRIPTIONS, u32, InscriptionIdValue }
 define_table! { INSCRIPTION_ID_TO_SEQUENCE_NUMBER, InscriptionIdValue, u32 }
 define_table! { INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER, i32, u32 }
+define_table! { MEDIA_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID, (&str, u32), InscriptionIdValue }
+define_table! { MEDIA_TO_COUNT, &str, u64 }
+define_table! { METAPROTOCOL_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID, (&str, u32), InscriptionIdValue }
+define_table! { METAPROTOCOL_TO_COUNT, &str, u64 }
 define_table! { OUTPOINT_TO_RUNE_BALANCES, &OutPointValue, &[u8] }
 define_table! { OUTPOINT_TO_UTXO_ENTRY, &OutPointValue, &UtxoEntry }
+define_table! { RUNE_ACTIVITY_ID_TO_RUNE_ACTIVITY_ENTRY, (RuneIdValue, u32, u32, u32), RuneActivityEntryValue }
+define_table! { RUNE_ID_AND_SCRIPT_PUBKEY_TO_BALANCE, (RuneIdValue, &[u8]), u128 }
 define_table! { RUNE_ID_TO_RUNE_ENTRY, RuneIdValue, RuneEntryValue }
 define_table! { RUNE_TO_RUNE_ID, u128, RuneIdValue }
 define_table! { SAT_TO_SATPOINT, u64, &SatPointValue }
+define_table! { SAVEPOINT_TO_HEIGHT, u64, u32 }
+define_table! { SCRIPT_PUBKEY_AND_RUNE_ID_TO_BALANCE, (&[u8], RuneIdValue), u128 }
+define_table! { SCRIPT_PUBKEY_AND_TX_POSITION_TO_HISTORY_ENTRY, (&[u8], u32, u32), &[u8] }
 define_table! { SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY, u32, InscriptionEntryValue }
 define_table! { SEQUENCE_NUMBER_TO_RUNE_ID, u32, RuneIdValue }
 define_table! { SEQUENCE_NUMBER_TO_SATPOINT, u32, &SatPointValue }
 define_table! { STATISTIC_TO_COUNT, u64, u64 }
 define_table! { TRANSACTION_ID_TO_RUNE, &TxidValue, u128 }
 define_table! { TRANSACTION_ID_TO_TRANSACTION, &TxidValue, &[u8] }
+define_table! { TRANSFER_ID_TO_TRANSFER_ENTRY, (u32, u32), TransferEntryValue }
 define_table! { WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP, u32, u128 }
 
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Debug)]
 pub(crate) enum Statistic {
   Schema = 0,
   BlessedInscriptions = 1,
@@ -91,6 +128,19 @@ pub(crate) enum Statistic {
   Runes = 13,
   SatRanges = 14,
   UnboundInscriptions = 16,
+  IndexEvents = 17,
+  Reorgs = 18,
+  IndexUndoLog = 19,
+  IndexInscriptionHistory = 20,
+  IndexRuneActivity = 21,
+  IndexContentTypes = 22,
+  IndexMetaprotocols = 23,
+  IndexContentHashes = 24,
+  IndexFullText = 25,
+  IndexAddressHistory = 26,
+  IndexedBlocks = 27,
+  IndexedTransactions = 28,
+  IndexingTime = 29,
 }
 
 impl Statistic {
@@ -105,6 +155,32 @@ impl From<Statistic> for u64 {
   }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub enum InscriptionFilter {
+  ContentType(String),
+  Media(&'static str),
+}
+
+impl InscriptionFilter {
+  pub(crate) fn query(&self) -> String {
+    match self {
+      Self::ContentType(content_type) => {
+        format!("?content_type={}", urlencoding::encode(content_type))
+      }
+      Self::Media(media) => format!("?media={media}"),
+    }
+  }
+}
+
+impl Display for InscriptionFilter {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::ContentType(content_type) => write!(f, "content type {content_type}"),
+      Self::Media(media) => write!(f, "media {media}"),
+    }
+  }
+}
+
 #[derive(Serialize)]
 pub struct Info {
   blocks_indexed: u32,
@@ -116,15 +192,26 @@ pub struct Info {
   metadata_bytes: u64,
   outputs_traversed: u64,
   page_size: usize,
+  reorgs: u64,
   sat_ranges: u64,
   stored_bytes: u64,
   tables: BTreeMap<String, TableInfo>,
+  throughput: ThroughputInfo,
   total_bytes: u64,
   pub transactions: Vec<TransactionInfo>,
   tree_height: u32,
   utxos_indexed: u64,
 }
 
+#[derive(Serialize)]
+pub(crate) struct ThroughputInfo {
+  blocks: u64,
+  blocks_per_second: f64,
+  seconds: f64,
+  transactions: u64,
+  transactions_per_second: f64,
+}
+
 #[derive(Serialize)]
 pub(crate) struct TableInfo {
   branch_pages: u64,
@@ -191,20 +278,36 @@ impl<T> BitcoinCoreRpcResultExt<T> for Result<T, bitcoincore_rpc::Error> {
 }
 
 pub struct Index {
-  pub(crate) client: Client,
+  block_durations: Mutex<Histogram>,
+  block_source: Arc<dyn BlockSource>,
+  commit_durations: Mutex<Histogram>,
   database: Database,
   durability: redb::Durability,
   event_sender: Option<tokio::sync::mpsc::Sender<Event>>,
   genesis_block_coinbase_transaction: Transaction,
   genesis_block_coinbase_txid: Txid,
   height_limit: Option<u32>,
+  index_address_history: bool,
   index_addresses: bool,
+  index_content_hashes: bool,
+  index_content_types: bool,
+  index_events: bool,
+  index_full_text: bool,
+  index_inscription_history: bool,
   index_inscriptions: bool,
+  index_metaprotocols: bool,
+  index_rune_activity: bool,
   index_runes: bool,
   index_sats: bool,
   index_transactions: bool,
+  index_undo_log: bool,
+  mempool: RwLock<Mempool>,
   path: PathBuf,
+  read_only: bool,
   settings: Settings,
+  // dropped after `database`, so the snapshot is closed before it is
+  // unregistered
+  _snapshot: Option<Snapshot>,
   started: DateTime<Utc>,
   first_index_height: u32,
   unrecoverably_reorged: AtomicBool,
@@ -219,7 +322,22 @@ impl Index {
     settings: &Settings,
     event_sender: Option<tokio::sync::mpsc::Sender<Event>>,
   ) -> Result<Self> {
-    let client = settings.bitcoin_rpc_client(None)?;
+    Index::open_with(settings, event_sender, false)
+  }
+
+  /// Opens an existing index without locking or modifying it, so that it can
+  /// be served while `ord index update --daemon` updates it from another
+  /// process. Fails if the index is currently open for writing.
+  pub fn open_read_only(settings: &Settings) -> Result<Self> {
+    Index::open_with(settings, None, true)
+  }
+
+  fn open_with(
+    settings: &Settings,
+    event_sender: Option<tokio::sync::mpsc::Sender<Event>>,
+    read_only: bool,
+  ) -> Result<Self> {
+    let block_source = block_source::open(settings)?;
 
     let path = settings.index().to_owned();
 
@@ -261,11 +379,28 @@ impl Index {
       }
     };
 
-    let database = match Database::builder()
-      .set_cache_size(index_cache_size)
-      .set_repair_callback(repair_callback)
-      .open(&path)
-    {
+    let snapshot = if read_only {
+      Some(Snapshot::register(&path)?)
+    } else {
+      None
+    };
+
+    let database = if read_only {
+      ReadOnlyBackend::open(&path)
+        .map_err(|error| DatabaseError::Storage(StorageError::Io(error)))
+        .and_then(|backend| {
+          Database::builder()
+            .set_cache_size(index_cache_size)
+            .create_with_backend(backend)
+        })
+    } else {
+      Database::builder()
+        .set_cache_size(index_cache_size)
+        .set_repair_callback(repair_callback)
+        .open(&path)
+    };
+
+    let database = match database {
       Ok(database) => {
         {
           let schema_version = database
@@ -294,7 +429,7 @@ impl Index {
         database
       }
       Err(DatabaseError::Storage(StorageError::Io(error)))
-        if error.kind() == io::ErrorKind::NotFound =>
+        if error.kind() == io::ErrorKind::NotFound && !read_only =>
       {
         let database = Database::builder()
           .set_cache_size(index_cache_size)
@@ -304,40 +439,108 @@ impl Index {
 
         tx.set_durability(durability);
 
+        tx.open_multimap_table(CONTENT_HASH_TO_SEQUENCE_NUMBER)?;
+        tx.open_multimap_table(DELEGATE_TO_SEQUENCE_NUMBER)?;
+        tx.open_multimap_table(RUNE_ID_TO_OUTPOINT_BALANCE)?;
+        tx.open_multimap_table(RUNE_ID_TO_SCRIPT_PUBKEY_BALANCE)?;
         tx.open_multimap_table(SAT_TO_SEQUENCE_NUMBER)?;
         tx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
         tx.open_multimap_table(SEQUENCE_NUMBER_TO_CHILDREN)?;
+        tx.open_multimap_table(TERM_TO_SEQUENCE_NUMBER)?;
+        tx.open_table(CONTENT_TYPE_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID)?;
+        tx.open_table(CONTENT_TYPE_TO_COUNT)?;
+        tx.open_table(EVENT_ID_TO_EVENT)?;
         tx.open_table(HEIGHT_TO_BLOCK_HEADER)?;
+        tx.open_table(HEIGHT_TO_LAST_EVENT_ID)?;
         tx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
+        tx.open_table(HEIGHT_TO_UNDO_LOG)?;
         tx.open_table(HOME_INSCRIPTIONS)?;
         tx.open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
         tx.open_table(INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER)?;
+        tx.open_table(MEDIA_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID)?;
+        tx.open_table(MEDIA_TO_COUNT)?;
+        tx.open_table(METAPROTOCOL_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID)?;
+        tx.open_table(METAPROTOCOL_TO_COUNT)?;
         tx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
         tx.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
+        tx.open_table(RUNE_ACTIVITY_ID_TO_RUNE_ACTIVITY_ENTRY)?;
+        tx.open_table(RUNE_ID_AND_SCRIPT_PUBKEY_TO_BALANCE)?;
         tx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
         tx.open_table(RUNE_TO_RUNE_ID)?;
         tx.open_table(SAT_TO_SATPOINT)?;
+        tx.open_table(SAVEPOINT_TO_HEIGHT)?;
+        tx.open_table(SCRIPT_PUBKEY_AND_RUNE_ID_TO_BALANCE)?;
+        tx.open_table(SCRIPT_PUBKEY_AND_TX_POSITION_TO_HISTORY_ENTRY)?;
         tx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
         tx.open_table(SEQUENCE_NUMBER_TO_RUNE_ID)?;
         tx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
         tx.open_table(TRANSACTION_ID_TO_RUNE)?;
+        tx.open_table(TRANSFER_ID_TO_TRANSFER_ENTRY)?;
         tx.open_table(WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?;
 
         {
           let mut statistics = tx.open_table(STATISTIC_TO_COUNT)?;
 
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexAddressHistory,
+            u64::from(settings.index_address_history_raw()),
+          )?;
+
           Self::set_statistic(
             &mut statistics,
             Statistic::IndexAddresses,
             u64::from(settings.index_addresses_raw()),
           )?;
 
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexContentHashes,
+            u64::from(settings.index_content_hashes_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexContentTypes,
+            u64::from(settings.index_content_types_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexEvents,
+            u64::from(settings.index_events_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexFullText,
+            u64::from(settings.index_full_text_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexInscriptionHistory,
+            u64::from(settings.index_inscription_history_raw()),
+          )?;
+
           Self::set_statistic(
             &mut statistics,
             Statistic::IndexInscriptions,
             u64::from(settings.index_inscriptions_raw()),
           )?;
 
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexMetaprotocols,
+            u64::from(settings.index_metaprotocols_raw()),
+          )?;
+
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexRuneActivity,
+            u64::from(settings.index_rune_activity_raw()),
+          )?;
+
           Self::set_statistic(
             &mut statistics,
             Statistic::IndexRunes,
@@ -356,6 +559,12 @@ impl Index {
             u64::from(settings.index_transactions_raw()),
           )?;
 
+          Self::set_statistic(
+            &mut statistics,
+            Statistic::IndexUndoLog,
+            u64::from(settings.index_undo_log_raw()),
+          )?;
+
           Self::set_statistic(&mut statistics, Statistic::Schema, SCHEMA_VERSION)?;
         }
 
@@ -410,20 +619,39 @@ impl Index {
       Err(error) => bail!("failed to open index: {error}"),
     };
 
+    let index_address_history;
     let index_addresses;
+    let index_content_hashes;
+    let index_content_types;
+    let index_events;
+    let index_full_text;
+    let index_metaprotocols;
+    let index_rune_activity;
     let index_runes;
     let index_sats;
     let index_transactions;
+    let index_undo_log;
+    let index_inscription_history;
     let index_inscriptions;
 
     {
       let tx = database.begin_read()?;
       let statistics = tx.open_table(STATISTIC_TO_COUNT)?;
+      index_address_history = Self::is_statistic_set(&statistics, Statistic::IndexAddressHistory)?;
       index_addresses = Self::is_statistic_set(&statistics, Statistic::IndexAddresses)?;
+      index_content_hashes = Self::is_statistic_set(&statistics, Statistic::IndexContentHashes)?;
+      index_content_types = Self::is_statistic_set(&statistics, Statistic::IndexContentTypes)?;
+      index_events = Self::is_statistic_set(&statistics, Statistic::IndexEvents)?;
+      index_full_text = Self::is_statistic_set(&statistics, Statistic::IndexFullText)?;
+      index_inscription_history =
+        Self::is_statistic_set(&statistics, Statistic::IndexInscriptionHistory)?;
       index_inscriptions = Self::is_statistic_set(&statistics, Statistic::IndexInscriptions)?;
+      index_metaprotocols = Self::is_statistic_set(&statistics, Statistic::IndexMetaprotocols)?;
+      index_rune_activity = Self::is_statistic_set(&statistics, Statistic::IndexRuneActivity)?;
       index_runes = Self::is_statistic_set(&statistics, Statistic::IndexRunes)?;
       index_sats = Self::is_statistic_set(&statistics, Statistic::IndexSats)?;
       index_transactions = Self::is_statistic_set(&statistics, Statistic::IndexTransactions)?;
+      index_undo_log = Self::is_statistic_set(&statistics, Statistic::IndexUndoLog)?;
     }
 
     let genesis_block_coinbase_transaction =
@@ -441,20 +669,34 @@ impl Index {
 
     Ok(Self {
       genesis_block_coinbase_txid: genesis_block_coinbase_transaction.compute_txid(),
-      client,
+      block_durations: Mutex::default(),
+      block_source,
+      commit_durations: Mutex::default(),
       database,
       durability,
       event_sender,
       first_index_height,
       genesis_block_coinbase_transaction,
       height_limit: settings.height_limit(),
+      index_address_history,
       index_addresses,
+      index_content_hashes,
+      index_content_types,
+      index_events,
+      index_full_text,
+      index_metaprotocols,
+      index_rune_activity,
       index_runes,
       index_sats,
       index_transactions,
+      index_undo_log,
+      index_inscription_history,
       index_inscriptions,
+      mempool: RwLock::new(Mempool::default()),
       settings: settings.clone(),
       path,
+      read_only,
+      _snapshot: snapshot,
       started: Utc::now(),
       unrecoverably_reorged: AtomicBool::new(false),
     })
@@ -493,10 +735,42 @@ impl Index {
     self.index_addresses
   }
 
+  pub fn has_address_history_index(&self) -> bool {
+    self.index_address_history && self.index_addresses
+  }
+
+  pub fn has_content_hash_index(&self) -> bool {
+    self.index_content_hashes && self.index_inscriptions
+  }
+
+  pub fn has_content_type_index(&self) -> bool {
+    self.index_content_types && self.index_inscriptions
+  }
+
+  pub fn has_event_index(&self) -> bool {
+    self.index_events
+  }
+
+  pub fn has_full_text_index(&self) -> bool {
+    self.index_full_text && self.index_inscriptions
+  }
+
+  pub fn has_inscription_history_index(&self) -> bool {
+    self.index_inscription_history && self.index_inscriptions
+  }
+
   pub fn has_inscription_index(&self) -> bool {
     self.index_inscriptions
   }
 
+  pub fn has_metaprotocol_index(&self) -> bool {
+    self.index_metaprotocols && self.index_inscriptions
+  }
+
+  pub fn has_rune_activity_index(&self) -> bool {
+    self.index_rune_activity && self.index_runes
+  }
+
   pub fn has_rune_index(&self) -> bool {
     self.index_runes
   }
@@ -532,10 +806,18 @@ impl Index {
     let cursed_inscriptions = statistic(Statistic::CursedInscriptions)?;
     let initial_sync_time = statistic(Statistic::InitialSyncTime)?;
 
+    let (content_types, media) = if self.has_content_type_index() {
+      let (content_types, media) = self.get_inscription_type_counts()?;
+      (Some(content_types), Some(media))
+    } else {
+      (None, None)
+    };
+
     Ok(StatusHtml {
       address_index: self.has_address_index(),
       blessed_inscriptions,
       chain: self.settings.chain(),
+      content_types,
       cursed_inscriptions,
       height,
       initial_sync_time: Duration::from_micros(initial_sync_time),
@@ -543,6 +825,7 @@ impl Index {
       inscriptions: blessed_inscriptions + cursed_inscriptions,
       json_api,
       lost_sats: statistic(Statistic::LostSats)?,
+      media,
       minimum_rune_for_next_block: Rune::minimum_at_height(
         self.settings.chain().network(),
         Height(next_height),
@@ -557,11 +840,7 @@ impl Index {
     })
   }
 
-  pub fn info(&self) -> Result<Info> {
-    let stats = self.database.begin_write()?.stats()?;
-
-    let rtx = self.database.begin_read()?;
-
+  fn table_info(rtx: &redb::ReadTransaction) -> Result<BTreeMap<String, TableInfo>> {
     let mut tables: BTreeMap<String, TableInfo> = BTreeMap::new();
 
     for handle in rtx.list_tables()? {
@@ -587,12 +866,27 @@ impl Index {
     let total_bytes = tables
       .values()
       .map(|table_info| table_info.total_bytes)
-      .sum();
+      .sum::<u64>();
 
     tables.values_mut().for_each(|table_info| {
       table_info.proportion = table_info.total_bytes as f64 / total_bytes as f64
     });
 
+    Ok(tables)
+  }
+
+  pub fn info(&self) -> Result<Info> {
+    let stats = self.database.begin_write()?.stats()?;
+
+    let rtx = self.database.begin_read()?;
+
+    let tables = Self::table_info(&rtx)?;
+
+    let total_bytes = tables
+      .values()
+      .map(|table_info| table_info.total_bytes)
+      .sum();
+
     let info = {
       let statistic_to_count = rtx.open_table(STATISTIC_TO_COUNT)?;
       let sat_ranges = statistic_to_count
@@ -603,6 +897,40 @@ impl Index {
         .get(&Statistic::OutputsTraversed.key())?
         .map(|x| x.value())
         .unwrap_or(0);
+      let reorgs = statistic_to_count
+        .get(&Statistic::Reorgs.key())?
+        .map(|x| x.value())
+        .unwrap_or(0);
+      let throughput = {
+        let statistic = |statistic: Statistic| -> Result<u64> {
+          Ok(
+            statistic_to_count
+              .get(&statistic.key())?
+              .map(|x| x.value())
+              .unwrap_or(0),
+          )
+        };
+
+        let blocks = statistic(Statistic::IndexedBlocks)?;
+        let transactions = statistic(Statistic::IndexedTransactions)?;
+        let seconds = statistic(Statistic::IndexingTime)? as f64 / 1_000_000.0;
+
+        let rate = |n: u64| {
+          if seconds > 0.0 {
+            n as f64 / seconds
+          } else {
+            0.0
+          }
+        };
+
+        ThroughputInfo {
+          blocks,
+          blocks_per_second: rate(blocks),
+          seconds,
+          transactions,
+          transactions_per_second: rate(transactions),
+        }
+      };
       Info {
         index_path: self.path.clone(),
         blocks_indexed: rtx
@@ -620,9 +948,11 @@ impl Index {
         sat_ranges,
         outputs_traversed,
         page_size: stats.page_size(),
+        reorgs,
         stored_bytes: stats.stored_bytes(),
         total_bytes,
         tables,
+        throughput,
         transactions: rtx
           .open_table(WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?
           .range(0..)?
@@ -643,7 +973,195 @@ impl Index {
     Ok(info)
   }
 
+  /// Writes index metrics in the Prometheus text exposition format. Unlike
+  /// `info`, this only opens a read transaction, so it does not wait for the
+  /// updater to commit.
+  pub(crate) fn metrics(&self, metrics: &mut Metrics) -> Result {
+    let rtx = self.database.begin_read()?;
+
+    {
+      let statistic_to_count = rtx.open_table(STATISTIC_TO_COUNT)?;
+
+      let statistic = |statistic: Statistic| -> Result<u64> {
+        Ok(
+          statistic_to_count
+            .get(&statistic.key())?
+            .map(|x| x.value())
+            .unwrap_or(0),
+        )
+      };
+
+      for (name, help, key) in [
+        (
+          "ord_blessed_inscriptions",
+          "Number of blessed inscriptions.",
+          Statistic::BlessedInscriptions,
+        ),
+        (
+          "ord_cursed_inscriptions",
+          "Number of cursed inscriptions.",
+          Statistic::CursedInscriptions,
+        ),
+        (
+          "ord_unbound_inscriptions",
+          "Number of unbound inscriptions.",
+          Statistic::UnboundInscriptions,
+        ),
+        ("ord_runes", "Number of etched runes.", Statistic::Runes),
+        (
+          "ord_reserved_runes",
+          "Number of reserved runes.",
+          Statistic::ReservedRunes,
+        ),
+        ("ord_lost_sats", "Number of lost sats.", Statistic::LostSats),
+        (
+          "ord_sat_ranges",
+          "Number of sat ranges.",
+          Statistic::SatRanges,
+        ),
+      ] {
+        metrics.gauge(name, help, statistic(key)?);
+      }
+
+      for (name, help, key) in [
+        (
+          "ord_outputs_traversed_total",
+          "Number of outputs traversed.",
+          Statistic::OutputsTraversed,
+        ),
+        (
+          "ord_commits_total",
+          "Number of index commits.",
+          Statistic::Commits,
+        ),
+        ("ord_reorgs_total", "Number of reorgs.", Statistic::Reorgs),
+        (
+          "ord_indexed_blocks_total",
+          "Number of blocks indexed.",
+          Statistic::IndexedBlocks,
+        ),
+        (
+          "ord_indexed_transactions_total",
+          "Number of transactions indexed.",
+          Statistic::IndexedTransactions,
+        ),
+      ] {
+        metrics.counter(name, help, statistic(key)?);
+      }
+
+      metrics.counter(
+        "ord_indexing_seconds_total",
+        "Time spent indexing blocks.",
+        statistic(Statistic::IndexingTime)? as f64 / 1_000_000.0,
+      );
+    }
+
+    metrics.gauge(
+      "ord_blocks_indexed",
+      "Number of blocks in the index.",
+      rtx
+        .open_table(HEIGHT_TO_BLOCK_HEADER)?
+        .range(0..)?
+        .next_back()
+        .transpose()?
+        .map(|(height, _header)| height.value() + 1)
+        .unwrap_or(0),
+    );
+
+    match self.block_source.block_count() {
+      Ok(count) => metrics.gauge(
+        "ord_chain_blocks",
+        "Number of blocks in the best chain of the block source.",
+        count + 1,
+      ),
+      Err(err) => log::warn!("failed to fetch block count for metrics: {err}"),
+    }
+
+    metrics.gauge(
+      "ord_utxos_indexed",
+      "Number of unspent outputs in the index.",
+      rtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?.len()?,
+    );
+
+    metrics.gauge(
+      "ord_index_file_size_bytes",
+      "Size of the index file.",
+      fs::metadata(&self.path)?.len(),
+    );
+
+    let tables = Self::table_info(&rtx)?;
+
+    let fields: [(&str, &str, fn(&TableInfo) -> u64); 6] = [
+      (
+        "ord_table_stored_bytes",
+        "Bytes of data stored in table.",
+        |table| table.stored_bytes,
+      ),
+      (
+        "ord_table_metadata_bytes",
+        "Bytes of table metadata.",
+        |table| table.metadata_bytes,
+      ),
+      (
+        "ord_table_fragmented_bytes",
+        "Bytes of table fragmentation.",
+        |table| table.fragmented_bytes,
+      ),
+      (
+        "ord_table_leaf_pages",
+        "Number of table leaf pages.",
+        |table| table.leaf_pages,
+      ),
+      (
+        "ord_table_branch_pages",
+        "Number of table branch pages.",
+        |table| table.branch_pages,
+      ),
+      (
+        "ord_table_tree_height",
+        "Height of table b-tree.",
+        |table| table.tree_height.into(),
+      ),
+    ];
+
+    for (name, help, field) in fields {
+      metrics.header(name, "gauge", help);
+      for (table, info) in &tables {
+        metrics.sample(name, &[("table", table)], field(info));
+      }
+    }
+
+    for (name, help, histogram) in [
+      (
+        "ord_updater_block_duration_seconds",
+        "Time spent indexing each block.",
+        &self.block_durations,
+      ),
+      (
+        "ord_updater_commit_duration_seconds",
+        "Time spent committing each write transaction.",
+        &self.commit_durations,
+      ),
+    ] {
+      metrics.header(name, "histogram", help);
+      metrics.histogram(name, &[], &histogram.lock().unwrap());
+    }
+
+    Ok(())
+  }
+
+  /// Updates the index while holding a read transaction on the state it was
+  /// opened at, so that pages `ord server --read-only` processes may still be
+  /// reading are not reused before the index is closed again. Readers must
+  /// also have reopened the index since the last update, see `Readers`.
+  pub(crate) fn update_holding_snapshot(&self) -> Result {
+    let _snapshot = self.begin_read()?;
+    self.update()
+  }
+
   pub fn update(&self) -> Result {
+    let mut rolled_back = false;
+
     loop {
       let wtx = self.begin_write()?;
 
@@ -659,6 +1177,7 @@ impl Index {
         outputs_cached: 0,
         outputs_traversed: 0,
         sat_ranges_since_flush: 0,
+        transactions_since_commit: 0,
       };
 
       match updater.update_index(wtx) {
@@ -670,11 +1189,24 @@ impl Index {
             Some(&reorg::Error::Recoverable { height, depth }) => {
               Reorg::handle_reorg(self, height, depth)?;
             }
-            Some(&reorg::Error::Unrecoverable) => {
+            Some(&reorg::Error::Unrecoverable { height, depth }) => {
+              // only fall back once, since a reorg deeper than the oldest
+              // savepoint would otherwise be rolled back and re-detected forever
+              if self.settings.rollback_unrecoverable_reorgs()
+                && !rolled_back
+                && !self.unrecoverably_reorged.load(atomic::Ordering::Relaxed)
+                && Reorg::has_savepoints(self)?
+              {
+                log::info!("falling back to oldest savepoint and re-syncing");
+                Reorg::handle_reorg(self, height, depth)?;
+                rolled_back = true;
+                continue;
+              }
+
               self
                 .unrecoverably_reorged
                 .store(true, atomic::Ordering::Relaxed);
-              return Err(anyhow!(reorg::Error::Unrecoverable));
+              return Err(anyhow!(reorg::Error::Unrecoverable { height, depth }));
             }
             _ => return Err(err),
           };
@@ -683,6 +1215,30 @@ impl Index {
     }
   }
 
+  /// Rebuild the mempool overlay from the transactions currently in the
+  /// Bitcoin Core mempool.
+  pub fn update_mempool(&self) -> Result {
+    let mempool = Mempool::load(self, &self.mempool.read().unwrap())?;
+    *self.mempool.write().unwrap() = mempool;
+    Ok(())
+  }
+
+  pub(crate) fn mempool(&self) -> RwLockReadGuard<'_, Mempool> {
+    self.mempool.read().unwrap()
+  }
+
+  /// Roll back the index so that `height` is the last indexed block, using a
+  /// savepoint at that height if there is one, and the undo log otherwise.
+  pub fn rollback(&self, height: u32) -> Result<u32> {
+    Reorg::rollback(self, height)
+  }
+
+  /// Verify that index tables are consistent with each other, returning any
+  /// violations found.
+  pub fn check(&self) -> Result<Vec<Violation>> {
+    Checker::new(self)?.check()
+  }
+
   pub fn export(&self, filename: &String, include_addresses: bool) -> Result {
     let mut writer = BufWriter::new(File::create(filename)?);
     let rtx = self.database.begin_read()?;
@@ -771,6 +1327,7 @@ impl Index {
   }
 
   fn begin_write(&self) -> Result<WriteTransaction> {
+    ensure!(!self.read_only, "index is read-only");
     let mut tx = self.database.begin_write()?;
     tx.set_durability(self.durability);
     Ok(tx)
@@ -993,53 +1550,278 @@ impl Index {
     Ok((entries, more))
   }
 
-  pub fn encode_rune_balance(id: RuneId, balance: u128, buffer: &mut Vec<u8>) {
-    varint::encode_to_vec(id.block.into(), buffer);
-    varint::encode_to_vec(id.tx.into(), buffer);
-    varint::encode_to_vec(balance, buffer);
+  pub fn get_rune_activity_paginated(
+    &self,
+    id: RuneId,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<RuneActivityEntry>, bool)> {
+    let mut entries = self
+      .database
+      .begin_read()?
+      .open_table(RUNE_ACTIVITY_ID_TO_RUNE_ACTIVITY_ENTRY)?
+      .range((id.store(), 0, 0, 0)..=(id.store(), u32::MAX, u32::MAX, u32::MAX))?
+      .rev()
+      .skip(page_index.saturating_mul(page_size))
+      .take(page_size.saturating_add(1))
+      .map(|result| RuneActivityEntry::load(result?.1.value()))
+      .collect::<Result<Vec<RuneActivityEntry>>>()?;
+
+    let more = entries.len() > page_size;
+
+    if more {
+      entries.pop();
+    }
+
+    Ok((entries, more))
   }
 
-  pub fn decode_rune_balance(buffer: &[u8]) -> Result<((RuneId, u128), usize)> {
-    let mut len = 0;
-    let (block, block_len) = varint::decode(&buffer[len..])?;
-    len += block_len;
-    let (tx, tx_len) = varint::decode(&buffer[len..])?;
-    len += tx_len;
-    let id = RuneId {
-      block: block.try_into()?,
-      tx: tx.try_into()?,
+  pub fn get_rune_holder_count(&self, id: RuneId) -> Result<u64> {
+    let rtx = self.database.begin_read()?;
+
+    let count = if self.index_addresses {
+      rtx
+        .open_multimap_table(RUNE_ID_TO_SCRIPT_PUBKEY_BALANCE)?
+        .get(id.store())?
+        .len()
+    } else {
+      rtx
+        .open_multimap_table(RUNE_ID_TO_OUTPOINT_BALANCE)?
+        .get(id.store())?
+        .len()
     };
-    let (balance, balance_len) = varint::decode(&buffer[len..])?;
-    len += balance_len;
-    Ok(((id, balance), len))
+
+    Ok(count)
   }
 
-  pub fn get_rune_balances_for_output(
+  pub fn get_rune_holders_paginated(
     &self,
-    outpoint: OutPoint,
-  ) -> Result<BTreeMap<SpacedRune, Pile>> {
+    id: RuneId,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<api::RuneHolder>, bool)> {
     let rtx = self.database.begin_read()?;
 
-    let outpoint_to_balances = rtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
+    let mut holders = if self.index_addresses {
+      rtx
+        .open_multimap_table(RUNE_ID_TO_SCRIPT_PUBKEY_BALANCE)?
+        .get(id.store())?
+        .rev()
+        .skip(page_index.saturating_mul(page_size))
+        .take(page_size.saturating_add(1))
+        .map(|result| {
+          let guard = result?;
+          let (amount, script_pubkey) = guard.value();
+          let script_pubkey = ScriptBuf::from_bytes(script_pubkey.to_vec());
+
+          Ok(api::RuneHolder {
+            address: self
+              .settings
+              .chain()
+              .address_from_script(&script_pubkey)
+              .ok()
+              .map(|address| address.to_string()),
+            amount,
+            outpoint: None,
+            script_pubkey: Some(script_pubkey),
+          })
+        })
+        .collect::<Result<Vec<api::RuneHolder>>>()?
+    } else {
+      rtx
+        .open_multimap_table(RUNE_ID_TO_OUTPOINT_BALANCE)?
+        .get(id.store())?
+        .rev()
+        .skip(page_index.saturating_mul(page_size))
+        .take(page_size.saturating_add(1))
+        .map(|result| {
+          let (amount, outpoint) = result?.value();
+
+          Ok(api::RuneHolder {
+            address: None,
+            amount,
+            outpoint: Some(OutPoint::load(outpoint)),
+            script_pubkey: None,
+          })
+        })
+        .collect::<Result<Vec<api::RuneHolder>>>()?
+    };
 
-    let id_to_rune_entries = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+    let more = holders.len() > page_size;
 
-    let Some(balances) = outpoint_to_balances.get(&outpoint.store())? else {
-      return Ok(BTreeMap::new());
-    };
+    if more {
+      holders.pop();
+    }
 
-    let balances_buffer = balances.value();
+    Ok((holders, more))
+  }
 
-    let mut balances = BTreeMap::new();
-    let mut i = 0;
-    while i < balances_buffer.len() {
-      let ((id, amount), length) = Index::decode_rune_balance(&balances_buffer[i..]).unwrap();
-      i += length;
+  /// Reconstructs the holders of a rune as of `height` by replaying its
+  /// activity ledger from the etching forward. Each transfer credits its
+  /// output, and each entry debits the outputs it spent.
+  pub fn get_rune_snapshot(
+    &self,
+    id: RuneId,
+    height: u32,
+    by_address: bool,
+  ) -> Result<Vec<api::RuneHolder>> {
+    let mut balances = HashMap::<OutPoint, u128>::new();
 
-      let entry = RuneEntry::load(id_to_rune_entries.get(id.store())?.unwrap().value());
+    for result in self
+      .database
+      .begin_read()?
+      .open_table(RUNE_ACTIVITY_ID_TO_RUNE_ACTIVITY_ENTRY)?
+      .range((id.store(), 0, 0, 0)..=(id.store(), u32::MAX, u32::MAX, u32::MAX))?
+    {
+      let entry = RuneActivityEntry::load(result?.1.value())?;
 
-      balances.insert(
-        entry.spaced_rune,
+      if entry.height > height {
+        break;
+      }
+
+      for input in &entry.inputs {
+        balances.remove(input);
+      }
+
+      if let (RuneActivityKind::Transfer, Some(output)) = (entry.kind, entry.output) {
+        *balances.entry(output).or_default() += entry.amount;
+      }
+    }
+
+    let mut holders = if by_address {
+      let mut script_pubkey_balances = HashMap::<ScriptBuf, u128>::new();
+
+      for (outpoint, amount) in balances {
+        let tx = self
+          .get_transaction(outpoint.txid)?
+          .ok_or_else(|| anyhow!("transaction {} not found", outpoint.txid))?;
+
+        let script_pubkey = tx
+          .output
+          .into_iter()
+          .nth(outpoint.vout.into_usize())
+          .ok_or_else(|| anyhow!("output {outpoint} not found"))?
+          .script_pubkey;
+
+        *script_pubkey_balances.entry(script_pubkey).or_default() += amount;
+      }
+
+      script_pubkey_balances
+        .into_iter()
+        .map(|(script_pubkey, amount)| api::RuneHolder {
+          address: self
+            .settings
+            .chain()
+            .address_from_script(&script_pubkey)
+            .ok()
+            .map(|address| address.to_string()),
+          amount,
+          outpoint: None,
+          script_pubkey: Some(script_pubkey),
+        })
+        .collect::<Vec<api::RuneHolder>>()
+    } else {
+      balances
+        .into_iter()
+        .map(|(outpoint, amount)| api::RuneHolder {
+          address: None,
+          amount,
+          outpoint: Some(outpoint),
+          script_pubkey: None,
+        })
+        .collect()
+    };
+
+    holders.sort_by(|a, b| {
+      b.amount
+        .cmp(&a.amount)
+        .then_with(|| a.outpoint.cmp(&b.outpoint))
+        .then_with(|| a.script_pubkey.cmp(&b.script_pubkey))
+    });
+
+    Ok(holders)
+  }
+
+  pub fn encode_rune_balance(id: RuneId, balance: u128, buffer: &mut Vec<u8>) {
+    varint::encode_to_vec(id.block.into(), buffer);
+    varint::encode_to_vec(id.tx.into(), buffer);
+    varint::encode_to_vec(balance, buffer);
+  }
+
+  pub fn decode_rune_balance(buffer: &[u8]) -> Result<((RuneId, u128), usize)> {
+    let mut len = 0;
+    let (block, block_len) = varint::decode(&buffer[len..])?;
+    len += block_len;
+    let (tx, tx_len) = varint::decode(&buffer[len..])?;
+    len += tx_len;
+    let id = RuneId {
+      block: block.try_into()?,
+      tx: tx.try_into()?,
+    };
+    let (balance, balance_len) = varint::decode(&buffer[len..])?;
+    len += balance_len;
+    Ok(((id, balance), len))
+  }
+
+  pub(crate) fn get_rune_balances_for_script_pubkey(
+    &self,
+    script_pubkey: &Script,
+  ) -> Result<Vec<(SpacedRune, Decimal, Option<char>)>> {
+    let rtx = self.database.begin_read()?;
+
+    let id_to_rune_entries = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+
+    let mut balances = rtx
+      .open_table(SCRIPT_PUBKEY_AND_RUNE_ID_TO_BALANCE)?
+      .range((script_pubkey.as_bytes(), (0, 0))..=(script_pubkey.as_bytes(), (u64::MAX, u32::MAX)))?
+      .map(|result| {
+        let (key, balance) = result?;
+        let (_script_pubkey, id) = key.value();
+
+        let entry = RuneEntry::load(id_to_rune_entries.get(id)?.unwrap().value());
+
+        Ok((
+          entry.spaced_rune,
+          Decimal {
+            value: balance.value(),
+            scale: entry.divisibility,
+          },
+          entry.symbol,
+        ))
+      })
+      .collect::<Result<Vec<(SpacedRune, Decimal, Option<char>)>>>()?;
+
+    balances.sort_by_key(|(spaced_rune, _decimal, _symbol)| *spaced_rune);
+
+    Ok(balances)
+  }
+
+  pub fn get_rune_balances_for_output(
+    &self,
+    outpoint: OutPoint,
+  ) -> Result<BTreeMap<SpacedRune, Pile>> {
+    let rtx = self.database.begin_read()?;
+
+    let outpoint_to_balances = rtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
+
+    let id_to_rune_entries = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+
+    let Some(balances) = outpoint_to_balances.get(&outpoint.store())? else {
+      return Ok(BTreeMap::new());
+    };
+
+    let balances_buffer = balances.value();
+
+    let mut balances = BTreeMap::new();
+    let mut i = 0;
+    while i < balances_buffer.len() {
+      let ((id, amount), length) = Index::decode_rune_balance(&balances_buffer[i..]).unwrap();
+      i += length;
+
+      let entry = RuneEntry::load(id_to_rune_entries.get(id.store())?.unwrap().value());
+
+      balances.insert(
+        entry.spaced_rune,
         Pile {
           amount,
           divisibility: entry.divisibility,
@@ -1134,30 +1916,36 @@ impl Index {
   }
 
   pub fn block_header(&self, hash: BlockHash) -> Result<Option<Header>> {
-    self.client.get_block_header(&hash).into_option()
+    self.block_source.block_header(hash)
   }
 
   pub fn block_header_info(&self, hash: BlockHash) -> Result<Option<GetBlockHeaderResult>> {
-    self.client.get_block_header_info(&hash).into_option()
+    self.block_source.block_header_info(hash)
+  }
+
+  pub fn get_block_height_by_hash(&self, hash: BlockHash) -> Result<Option<u32>> {
+    self.block_source.block_height(hash)
   }
 
-  pub fn block_stats(&self, height: u64) -> Result<Option<GetBlockStatsResult>> {
-    self.client.get_block_stats(height).into_option()
+  pub fn block_stats(&self, height: u32) -> Result<Option<GetBlockStatsResult>> {
+    self.block_source.block_stats(height)
   }
 
   pub fn get_block_by_height(&self, height: u32) -> Result<Option<Block>> {
-    Ok(
-      self
-        .client
-        .get_block_hash(height.into())
-        .into_option()?
-        .map(|hash| self.client.get_block(&hash))
-        .transpose()?,
-    )
+    self
+      .block_source
+      .block_hash(height)?
+      .map(|hash| self.block_source.block(hash))
+      .transpose()
+      .map(Option::flatten)
   }
 
   pub fn get_block_by_hash(&self, hash: BlockHash) -> Result<Option<Block>> {
-    self.client.get_block(&hash).into_option()
+    self.block_source.block(hash)
+  }
+
+  pub(crate) fn mempool_txids(&self) -> Result<Vec<Txid>> {
+    self.block_source.mempool()
   }
 
   pub fn get_collections_paginated(
@@ -1293,6 +2081,142 @@ impl Index {
     Ok((children, more))
   }
 
+  /// Groups the children of `parent` by the script pubkey that holds them,
+  /// either now or, with `height`, at the end of that block. Children that
+  /// are unbound, lost, or not yet inscribed are left out.
+  pub fn get_collection_holders(
+    &self,
+    parent: InscriptionId,
+    height: Option<u32>,
+  ) -> Result<Option<Vec<api::CollectionHolder>>> {
+    Ok(
+      self
+        .get_collection_holders_paginated(parent, height, usize::MAX, 0)?
+        .map(|(holders, _more)| holders),
+    )
+  }
+
+  /// Like `get_collection_holders`, but only returns page `page_index` of
+  /// holders, and whether there are more. Every child is still grouped, since
+  /// holders are ordered by how many children they hold, but inscription
+  /// entries and addresses are only looked up for holders on the page.
+  pub fn get_collection_holders_paginated(
+    &self,
+    parent: InscriptionId,
+    height: Option<u32>,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<Option<(Vec<api::CollectionHolder>, bool)>> {
+    let rtx = self.database.begin_read()?;
+
+    let Some(parent) = rtx
+      .open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?
+      .get(&parent.store())?
+      .map(|sequence_number| sequence_number.value())
+    else {
+      return Ok(None);
+    };
+
+    let outpoint_to_utxo_entry = rtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
+    let sequence_number_to_entry = rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+    let sequence_number_to_satpoint = rtx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
+    let transfer_id_to_transfer_entry = rtx.open_table(TRANSFER_ID_TO_TRANSFER_ENTRY)?;
+
+    let mut holders = HashMap::<ScriptBuf, Vec<u32>>::new();
+
+    for result in rtx
+      .open_multimap_table(SEQUENCE_NUMBER_TO_CHILDREN)?
+      .get(parent)?
+    {
+      let sequence_number = result?.value();
+
+      let script_pubkey = if let Some(height) = height {
+        let mut script_pubkey = None;
+
+        for result in transfer_id_to_transfer_entry
+          .range((sequence_number, 0)..=(sequence_number, u32::MAX))?
+          .rev()
+        {
+          let transfer = TransferEntry::load(result?.1.value());
+
+          if transfer.height <= height {
+            script_pubkey = transfer.script_pubkey;
+            break;
+          }
+        }
+
+        script_pubkey
+      } else {
+        let satpoint = SatPoint::load(
+          *sequence_number_to_satpoint
+            .get(sequence_number)?
+            .unwrap()
+            .value(),
+        );
+
+        if satpoint.outpoint == unbound_outpoint() || satpoint.outpoint == OutPoint::null() {
+          None
+        } else {
+          outpoint_to_utxo_entry
+            .get(&satpoint.outpoint.store())?
+            .map(|entry| ScriptBuf::from_bytes(entry.value().parse(self).script_pubkey().to_vec()))
+        }
+      };
+
+      // children burned to OP_RETURN outputs have no holder
+      let Some(script_pubkey) = script_pubkey.filter(|script_pubkey| !script_pubkey.is_op_return())
+      else {
+        continue;
+      };
+
+      holders
+        .entry(script_pubkey)
+        .or_default()
+        .push(sequence_number);
+    }
+
+    let mut holders = holders.into_iter().collect::<Vec<(ScriptBuf, Vec<u32>)>>();
+
+    holders.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then_with(|| a.0.cmp(&b.0)));
+
+    let start = page_index.saturating_mul(page_size);
+
+    let more = holders.len() > start.saturating_add(page_size);
+
+    let holders = holders
+      .into_iter()
+      .skip(start)
+      .take(page_size)
+      .map(|(script_pubkey, sequence_numbers)| {
+        Ok(api::CollectionHolder {
+          address: self
+            .settings
+            .chain()
+            .address_from_script(&script_pubkey)
+            .ok()
+            .map(|address| address.to_string()),
+          inscriptions: sequence_numbers
+            .into_iter()
+            .map(|sequence_number| {
+              Ok(
+                InscriptionEntry::load(
+                  sequence_number_to_entry
+                    .get(sequence_number)?
+                    .unwrap()
+                    .value(),
+                )
+                .id,
+              )
+            })
+            .collect::<Result<Vec<InscriptionId>>>()?,
+          script_pubkey,
+        })
+      })
+      .collect::<Result<Vec<api::CollectionHolder>>>()?;
+
+    Ok(Some((holders, more)))
+  }
+
   pub fn get_parents_by_sequence_number_paginated(
     &self,
     parent_sequence_numbers: Vec<u32>,
@@ -1324,6 +2248,37 @@ impl Index {
     Ok((parents, more_parents))
   }
 
+  pub(crate) fn get_inscription_history(
+    &self,
+    query: query::Inscription,
+  ) -> Result<Option<(InscriptionEntry, Vec<TransferEntry>)>> {
+    let rtx = self.database.begin_read()?;
+
+    let Some(sequence_number) = Self::inscription_query_sequence_number(&rtx, query)? else {
+      return Ok(None);
+    };
+
+    let entry = InscriptionEntry::load(
+      rtx
+        .open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?
+        .get(sequence_number)?
+        .unwrap()
+        .value(),
+    );
+
+    let transfers = rtx
+      .open_table(TRANSFER_ID_TO_TRANSFER_ENTRY)?
+      .range((sequence_number, 0)..=(sequence_number, u32::MAX))?
+      .map(|result| {
+        result
+          .map(|(_id, entry)| TransferEntry::load(entry.value()))
+          .map_err(|err| err.into())
+      })
+      .collect::<Result<Vec<TransferEntry>>>()?;
+
+    Ok(Some((entry, transfers)))
+  }
+
   pub fn get_etching(&self, txid: Txid) -> Result<Option<SpacedRune>> {
     let rtx = self.database.begin_read()?;
 
@@ -1587,7 +2542,7 @@ impl Index {
       }
     }
 
-    self.client.get_raw_transaction(&txid, None).into_option()
+    self.block_source.transaction(txid)
   }
 
   pub fn find(&self, sat: Sat) -> Result<Option<SatPoint>> {
@@ -1709,10 +2664,7 @@ impl Index {
             .get(&outpoint.store())?
             .is_none()
         } else {
-          self
-            .client
-            .get_tx_out(&outpoint.txid, outpoint.vout, Some(true))?
-            .is_none()
+          !self.block_source.is_unspent(outpoint)?
         },
     )
   }
@@ -1726,23 +2678,19 @@ impl Index {
       return Ok(true);
     }
 
-    let Some(info) = self
-      .client
-      .get_raw_transaction_info(&outpoint.txid, None)
-      .into_option()?
-    else {
+    let Some(transaction) = self.block_source.transaction(outpoint.txid)? else {
       return Ok(false);
     };
 
-    if info.blockhash.is_none() {
-      return Ok(false);
-    }
-
-    if outpoint.vout.into_usize() >= info.vout.len() {
+    if self
+      .block_source
+      .transaction_height(outpoint.txid)?
+      .is_none()
+    {
       return Ok(false);
     }
 
-    Ok(true)
+    Ok(outpoint.vout.into_usize() < transaction.output.len())
   }
 
   pub fn block_time(&self, height: Height) -> Result<Blocktime> {
@@ -1815,69 +2763,410 @@ impl Index {
     Ok((inscriptions, more))
   }
 
-  pub fn get_inscriptions_in_block(&self, block_height: u32) -> Result<Vec<InscriptionId>> {
+  pub fn get_filtered_inscriptions_paginated(
+    &self,
+    filter: &InscriptionFilter,
+    block_height: Option<u32>,
+    page_size: u32,
+    page_index: u32,
+  ) -> Result<(Vec<InscriptionId>, bool)> {
     let rtx = self.database.begin_read()?;
 
-    let height_to_last_sequence_number = rtx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
-    let sequence_number_to_inscription_entry =
-      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
-
-    let Some(newest_sequence_number) = height_to_last_sequence_number
-      .get(&block_height)?
-      .map(|ag| ag.value())
-    else {
-      return Ok(Vec::new());
+    let (table, key) = match filter {
+      InscriptionFilter::ContentType(content_type) => (
+        rtx.open_table(CONTENT_TYPE_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID)?,
+        content_type.as_str(),
+      ),
+      InscriptionFilter::Media(media) => (
+        rtx.open_table(MEDIA_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID)?,
+        *media,
+      ),
     };
 
-    let oldest_sequence_number = height_to_last_sequence_number
-      .get(block_height.saturating_sub(1))?
-      .map(|ag| ag.value())
-      .unwrap_or(0);
-
-    (oldest_sequence_number..newest_sequence_number)
-      .map(|num| match sequence_number_to_inscription_entry.get(&num) {
-        Ok(Some(inscription_id)) => Ok(InscriptionEntry::load(inscription_id.value()).id),
-        Ok(None) => Err(anyhow!(
-          "could not find inscription for inscription number {num}"
-        )),
-        Err(err) => Err(anyhow!(err)),
-      })
-      .collect::<Result<Vec<InscriptionId>>>()
-  }
+    let (start, end) = match block_height {
+      Some(block_height) => {
+        let height_to_last_sequence_number = rtx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
 
-  pub fn get_runes_in_block(&self, block_height: u64) -> Result<Vec<SpacedRune>> {
-    let rtx = self.database.begin_read()?;
+        let Some(end) = height_to_last_sequence_number
+          .get(block_height)?
+          .map(|end| end.value())
+        else {
+          return Ok((Vec::new(), false));
+        };
 
-    let rune_id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+        let start = match block_height.checked_sub(1) {
+          Some(previous) => height_to_last_sequence_number
+            .get(previous)?
+            .map(|start| start.value())
+            .unwrap_or_default(),
+          None => 0,
+        };
 
-    let min_id = RuneId {
-      block: block_height,
-      tx: 0,
+        (start, end)
+      }
+      None => (0, u32::MAX),
     };
 
-    let max_id = RuneId {
-      block: block_height,
-      tx: u32::MAX,
+    let range = table.range((key, start)..(key, end))?;
+
+    let skip = usize::try_from(page_size.saturating_mul(page_index)).unwrap();
+    let take = usize::try_from(page_size).unwrap().saturating_add(1);
+
+    // All inscriptions of a type are listed newest first, like `/inscriptions`,
+    // while inscriptions in a block are listed in block order.
+    let mut inscriptions = if block_height.is_some() {
+      range
+        .skip(skip)
+        .take(take)
+        .map(|result| result.map(|(_key, id)| InscriptionId::load(id.value())))
+        .collect::<Result<Vec<InscriptionId>, StorageError>>()?
+    } else {
+      range
+        .rev()
+        .skip(skip)
+        .take(take)
+        .map(|result| result.map(|(_key, id)| InscriptionId::load(id.value())))
+        .collect::<Result<Vec<InscriptionId>, StorageError>>()?
     };
 
-    let runes = rune_id_to_rune_entry
-      .range(min_id.store()..=max_id.store())?
-      .map(|result| result.map(|(_, entry)| RuneEntry::load(entry.value()).spaced_rune))
-      .collect::<Result<Vec<SpacedRune>, StorageError>>()?;
+    let more = inscriptions.len() == take;
 
-    Ok(runes)
+    if more {
+      inscriptions.pop();
+    }
+
+    Ok((inscriptions, more))
   }
 
-  pub fn get_highest_paying_inscriptions_in_block(
+  pub fn get_inscription_type_counts(
     &self,
-    block_height: u32,
-    n: usize,
-  ) -> Result<(Vec<InscriptionId>, usize)> {
-    let inscription_ids = self.get_inscriptions_in_block(block_height)?;
+  ) -> Result<(BTreeMap<String, u64>, BTreeMap<String, u64>)> {
+    let rtx = self.database.begin_read()?;
 
-    let mut inscription_to_fee: Vec<(InscriptionId, u64)> = Vec::new();
-    for id in &inscription_ids {
-      inscription_to_fee.push((
+    let counts = |definition: TableDefinition<&str, u64>| -> Result<BTreeMap<String, u64>> {
+      rtx
+        .open_table(definition)?
+        .iter()?
+        .map(|result| {
+          result
+            .map(|(key, count)| (key.value().to_string(), count.value()))
+            .map_err(|err| err.into())
+        })
+        .collect()
+    };
+
+    Ok((counts(CONTENT_TYPE_TO_COUNT)?, counts(MEDIA_TO_COUNT)?))
+  }
+
+  pub fn get_inscriptions_by_content_hash_paginated(
+    &self,
+    hash: sha256::Hash,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<InscriptionId>, bool)> {
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let mut inscriptions = rtx
+      .open_multimap_table(CONTENT_HASH_TO_SEQUENCE_NUMBER)?
+      .get(hash.as_byte_array())?
+      .skip(page_index.saturating_mul(page_size))
+      .take(page_size.saturating_add(1))
+      .map(|result| {
+        let sequence_number = result?.value();
+        let entry = sequence_number_to_inscription_entry
+          .get(sequence_number)?
+          .unwrap();
+        Ok(InscriptionEntry::load(entry.value()).id)
+      })
+      .collect::<Result<Vec<InscriptionId>>>()?;
+
+    let more = inscriptions.len() > page_size;
+
+    if more {
+      inscriptions.pop();
+    }
+
+    Ok((inscriptions, more))
+  }
+
+  pub fn get_delegators_paginated(
+    &self,
+    delegate: InscriptionId,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<InscriptionId>, bool)> {
+    let rtx = self.database.begin_read()?;
+
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let mut delegators = rtx
+      .open_multimap_table(DELEGATE_TO_SEQUENCE_NUMBER)?
+      .get(&delegate.store())?
+      .skip(page_index.saturating_mul(page_size))
+      .take(page_size.saturating_add(1))
+      .map(|result| {
+        let sequence_number = result?.value();
+        let entry = sequence_number_to_inscription_entry
+          .get(sequence_number)?
+          .unwrap();
+        Ok(InscriptionEntry::load(entry.value()).id)
+      })
+      .collect::<Result<Vec<InscriptionId>>>()?;
+
+    let more = delegators.len() > page_size;
+
+    if more {
+      delegators.pop();
+    }
+
+    Ok((delegators, more))
+  }
+
+  /// Ranks inscriptions containing any of `terms` by the summed inverse
+  /// document frequency of the terms they contain, breaking ties in favor of
+  /// newer inscriptions. Only the newest `MAX_POSTINGS_PER_TERM` inscriptions
+  /// containing each term are considered.
+  pub fn search_full_text(
+    &self,
+    terms: &BTreeSet<String>,
+    page_size: usize,
+    page_index: usize,
+  ) -> Result<(Vec<(InscriptionId, f64)>, bool)> {
+    let rtx = self.database.begin_read()?;
+
+    let term_to_sequence_number = rtx.open_multimap_table(TERM_TO_SEQUENCE_NUMBER)?;
+
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let total = sequence_number_to_inscription_entry.len()? as f64;
+
+    let mut scores = HashMap::<u32, f64>::new();
+
+    for term in terms {
+      let postings = term_to_sequence_number.get(term.as_str())?;
+
+      if postings.is_empty() {
+        continue;
+      }
+
+      let idf = (total / postings.len() as f64).ln() + 1.0;
+
+      for result in postings.rev().take(full_text::MAX_POSTINGS_PER_TERM) {
+        *scores.entry(result?.value()).or_default() += idf;
+      }
+    }
+
+    let mut scores = scores.into_iter().collect::<Vec<(u32, f64)>>();
+
+    scores.sort_by(
+      |(a_sequence_number, a_score), (b_sequence_number, b_score)| {
+        b_score
+          .total_cmp(a_score)
+          .then(b_sequence_number.cmp(a_sequence_number))
+      },
+    );
+
+    let start = page_index.saturating_mul(page_size);
+
+    let more = scores.len() > start.saturating_add(page_size);
+
+    let results = scores
+      .into_iter()
+      .skip(start)
+      .take(page_size)
+      .map(|(sequence_number, score)| {
+        let entry = sequence_number_to_inscription_entry
+          .get(sequence_number)?
+          .unwrap();
+        Ok((InscriptionEntry::load(entry.value()).id, score))
+      })
+      .collect::<Result<Vec<(InscriptionId, f64)>>>()?;
+
+    Ok((results, more))
+  }
+
+  pub fn get_metaprotocol_inscriptions_paginated(
+    &self,
+    metaprotocol: &str,
+    page_size: u32,
+    page_index: u32,
+  ) -> Result<(Vec<InscriptionId>, bool)> {
+    let mut inscriptions = self
+      .database
+      .begin_read()?
+      .open_table(METAPROTOCOL_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID)?
+      .range((metaprotocol, 0)..=(metaprotocol, u32::MAX))?
+      .rev()
+      .skip(usize::try_from(page_size.saturating_mul(page_index)).unwrap())
+      .take(usize::try_from(page_size).unwrap().saturating_add(1))
+      .map(|result| result.map(|(_key, id)| InscriptionId::load(id.value())))
+      .collect::<Result<Vec<InscriptionId>, StorageError>>()?;
+
+    let more = inscriptions.len() > usize::try_from(page_size).unwrap();
+
+    if more {
+      inscriptions.pop();
+    }
+
+    Ok((inscriptions, more))
+  }
+
+  pub fn get_metaprotocols(&self) -> Result<BTreeMap<String, u64>> {
+    self
+      .database
+      .begin_read()?
+      .open_table(METAPROTOCOL_TO_COUNT)?
+      .iter()?
+      .map(|result| {
+        result
+          .map(|(metaprotocol, count)| (metaprotocol.value().to_string(), count.value()))
+          .map_err(|err| err.into())
+      })
+      .collect()
+  }
+
+  pub fn get_inscriptions_in_block(&self, block_height: u32) -> Result<Vec<InscriptionId>> {
+    let rtx = self.database.begin_read()?;
+
+    let height_to_last_sequence_number = rtx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
+
+    let Some(newest_sequence_number) = height_to_last_sequence_number
+      .get(&block_height)?
+      .map(|ag| ag.value())
+    else {
+      return Ok(Vec::new());
+    };
+
+    let oldest_sequence_number = height_to_last_sequence_number
+      .get(block_height.saturating_sub(1))?
+      .map(|ag| ag.value())
+      .unwrap_or(0);
+
+    (oldest_sequence_number..newest_sequence_number)
+      .map(|num| match sequence_number_to_inscription_entry.get(&num) {
+        Ok(Some(inscription_id)) => Ok(InscriptionEntry::load(inscription_id.value()).id),
+        Ok(None) => Err(anyhow!(
+          "could not find inscription for inscription number {num}"
+        )),
+        Err(err) => Err(anyhow!(err)),
+      })
+      .collect::<Result<Vec<InscriptionId>>>()
+  }
+
+  /// Events logged while indexing the block at `block_height`, including
+  /// reorg and undo events logged when the block before it became the chain
+  /// tip after a reorg.
+  pub fn get_events_in_block(&self, block_height: u32) -> Result<Vec<(u64, Event)>> {
+    let rtx = self.database.begin_read()?;
+
+    let height_to_last_event_id = rtx.open_table(HEIGHT_TO_LAST_EVENT_ID)?;
+
+    let Some(end) = height_to_last_event_id
+      .get(block_height)?
+      .map(|end| end.value())
+    else {
+      return Ok(Vec::new());
+    };
+
+    let start = match block_height.checked_sub(1) {
+      Some(previous) => height_to_last_event_id
+        .get(previous)?
+        .map(|start| start.value())
+        .unwrap_or_default(),
+      None => 0,
+    };
+
+    rtx
+      .open_table(EVENT_ID_TO_EVENT)?
+      .range(start..end)?
+      .map(|result| {
+        result
+          .map_err(|err| err.into())
+          .and_then(|(id, event)| Ok((id.value(), serde_json::from_slice(event.value())?)))
+      })
+      .collect()
+  }
+
+  /// Events in the order they were logged, starting at id `from`. Ids keep
+  /// increasing across reorgs, so reorg and undo events are logged after every
+  /// event they compensate for, and a consumer resuming from a cursor sees
+  /// them.
+  pub fn get_events_paginated(
+    &self,
+    from: u64,
+    page_size: usize,
+  ) -> Result<(Vec<(u64, Event)>, Option<u64>)> {
+    let mut events = self
+      .database
+      .begin_read()?
+      .open_table(EVENT_ID_TO_EVENT)?
+      .range(from..)?
+      .take(page_size.saturating_add(1))
+      .map(|result| {
+        result
+          .map_err(|err| err.into())
+          .and_then(|(id, event)| Ok((id.value(), serde_json::from_slice(event.value())?)))
+      })
+      .collect::<Result<Vec<(u64, Event)>>>()?;
+
+    let next = if events.len() > page_size {
+      events.pop().map(|(id, _event)| id)
+    } else {
+      None
+    };
+
+    Ok((events, next))
+  }
+
+  fn next_event_id(event_id_to_event: &impl ReadableTable<u64, &'static [u8]>) -> Result<u64> {
+    Ok(
+      event_id_to_event
+        .last()?
+        .map(|(id, _event)| id.value() + 1)
+        .unwrap_or_default(),
+    )
+  }
+
+  pub fn get_runes_in_block(&self, block_height: u64) -> Result<Vec<SpacedRune>> {
+    let rtx = self.database.begin_read()?;
+
+    let rune_id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+
+    let min_id = RuneId {
+      block: block_height,
+      tx: 0,
+    };
+
+    let max_id = RuneId {
+      block: block_height,
+      tx: u32::MAX,
+    };
+
+    let runes = rune_id_to_rune_entry
+      .range(min_id.store()..=max_id.store())?
+      .map(|result| result.map(|(_, entry)| RuneEntry::load(entry.value()).spaced_rune))
+      .collect::<Result<Vec<SpacedRune>, StorageError>>()?;
+
+    Ok(runes)
+  }
+
+  pub fn get_highest_paying_inscriptions_in_block(
+    &self,
+    block_height: u32,
+    n: usize,
+  ) -> Result<(Vec<InscriptionId>, usize)> {
+    let inscription_ids = self.get_inscriptions_in_block(block_height)?;
+
+    let mut inscription_to_fee: Vec<(InscriptionId, u64)> = Vec::new();
+    for id in &inscription_ids {
+      inscription_to_fee.push((
         *id,
         self
           .get_inscription_entry(*id)?
@@ -1928,14 +3217,11 @@ impl Index {
     )
   }
 
-  pub(crate) fn inscription_info(
-    &self,
+  fn inscription_query_sequence_number(
+    rtx: &redb::ReadTransaction,
     query: query::Inscription,
-    child: Option<usize>,
-  ) -> Result<Option<(api::Inscription, Option<TxOut>, Inscription)>> {
-    let rtx = self.database.begin_read()?;
-
-    let sequence_number = match query {
+  ) -> Result<Option<u32>> {
+    Ok(match query {
       query::Inscription::Id(id) => rtx
         .open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?
         .get(&id.store())?
@@ -1950,9 +3236,17 @@ impl Index {
         .next()
         .transpose()?
         .map(|guard| guard.value()),
-    };
+    })
+  }
+
+  pub(crate) fn inscription_info(
+    &self,
+    query: query::Inscription,
+    child: Option<usize>,
+  ) -> Result<Option<(api::Inscription, Option<TxOut>, Inscription)>> {
+    let rtx = self.database.begin_read()?;
 
-    let Some(sequence_number) = sequence_number else {
+    let Some(sequence_number) = Self::inscription_query_sequence_number(&rtx, query)? else {
       return Ok(None);
     };
 
@@ -2087,6 +3381,26 @@ impl Index {
       Charm::Lost.set(&mut charms);
     }
 
+    let (original, duplicate_of) = match inscription.content_hash() {
+      Some(content_hash) if self.has_content_hash_index() => {
+        let first = rtx
+          .open_multimap_table(CONTENT_HASH_TO_SEQUENCE_NUMBER)?
+          .get(&content_hash)?
+          .next()
+          .transpose()?
+          .map(|first| first.value())
+          .unwrap_or(sequence_number);
+
+        if first == sequence_number {
+          (Some(true), None)
+        } else {
+          let entry = sequence_number_to_inscription_entry.get(first)?.unwrap();
+          (Some(false), Some(InscriptionEntry::load(entry.value()).id))
+        }
+      }
+      _ => (None, None),
+    };
+
     let effective_mime_type = if let Some(delegate_id) = inscription.delegate() {
       let delegate_result = self.get_inscription_by_id(delegate_id);
       if let Ok(Some(delegate)) = delegate_result {
@@ -2115,12 +3429,14 @@ impl Index {
         children,
         content_length: inscription.content_length(),
         content_type: inscription.content_type().map(|s| s.to_string()),
+        duplicate_of,
         effective_content_type: effective_mime_type,
         fee: entry.fee,
         height: entry.height,
         id: entry.id,
         next,
         number: entry.inscription_number,
+        original,
         parents,
         previous,
         rune,
@@ -2282,39 +3598,95 @@ impl Index {
       .collect()
   }
 
-  pub(crate) fn get_aggregated_rune_balances_for_outputs(
+  /// Returns up to `limit` history entries for `script_pubkey`, newest first,
+  /// starting before the `(height, transaction index)` position `before`, along
+  /// with the position to continue from if there are more.
+  pub fn get_address_history(
     &self,
-    outputs: &Vec<OutPoint>,
-  ) -> Result<Vec<(SpacedRune, Decimal, Option<char>)>> {
-    let mut runes = BTreeMap::new();
+    script_pubkey: &Script,
+    before: Option<(u32, u32)>,
+    limit: usize,
+  ) -> Result<(Vec<api::AddressHistoryEntry>, Option<(u32, u32)>)> {
+    let rtx = self.database.begin_read()?;
 
-    for output in outputs {
-      let rune_balances = self.get_rune_balances_for_output(*output)?;
-
-      for (spaced_rune, pile) in rune_balances {
-        runes
-          .entry(spaced_rune)
-          .and_modify(|(decimal, _symbol): &mut (Decimal, Option<char>)| {
-            assert_eq!(decimal.scale, pile.divisibility);
-            decimal.value += pile.amount;
-          })
-          .or_insert((
-            Decimal {
-              value: pile.amount,
-              scale: pile.divisibility,
-            },
-            pile.symbol,
-          ));
-      }
-    }
+    let script_pubkey_and_tx_position_to_history_entry =
+      rtx.open_table(SCRIPT_PUBKEY_AND_TX_POSITION_TO_HISTORY_ENTRY)?;
+    let rune_id_to_rune_entry = rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+    let sequence_number_to_inscription_entry =
+      rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
 
-    Ok(
-      runes
-        .into_iter()
-        .map(|(spaced_rune, (decimal, symbol))| (spaced_rune, decimal, symbol))
-        .collect(),
-    )
-  }
+    let script_pubkey = script_pubkey.as_bytes();
+
+    let (height, tx_index) = before.unwrap_or((u32::MAX, u32::MAX));
+
+    let mut entries = script_pubkey_and_tx_position_to_history_entry
+      .range((script_pubkey, 0, 0)..(script_pubkey, height, tx_index))?
+      .rev()
+      .take(limit.saturating_add(1))
+      .map(|result| {
+        let (key, value) = result?;
+        let (_script_pubkey, height, tx_index) = key.value();
+        Ok((
+          (height, tx_index),
+          serde_json::from_slice::<AddressHistoryEntry>(value.value())?,
+        ))
+      })
+      .collect::<Result<Vec<((u32, u32), AddressHistoryEntry)>>>()?;
+
+    let next = if entries.len() > limit {
+      entries.pop();
+      entries.last().map(|(position, _entry)| *position)
+    } else {
+      None
+    };
+
+    let inscription_ids = |sequence_numbers: Vec<u32>| {
+      sequence_numbers
+        .into_iter()
+        .map(|sequence_number| {
+          let entry = sequence_number_to_inscription_entry
+            .get(sequence_number)?
+            .unwrap();
+          Ok(InscriptionEntry::load(entry.value()).id)
+        })
+        .collect::<Result<Vec<InscriptionId>>>()
+    };
+
+    let rune_amounts = |amounts: BTreeMap<RuneId, u128>| {
+      amounts
+        .into_iter()
+        .map(|(id, amount)| {
+          let entry = RuneEntry::load(rune_id_to_rune_entry.get(id.store())?.unwrap().value());
+          Ok((
+            entry.spaced_rune,
+            Decimal {
+              value: amount,
+              scale: entry.divisibility,
+            },
+            entry.symbol,
+          ))
+        })
+        .collect::<Result<Vec<(SpacedRune, Decimal, Option<char>)>>>()
+    };
+
+    let entries = entries
+      .into_iter()
+      .map(|((height, _tx_index), entry)| {
+        Ok(api::AddressHistoryEntry {
+          height,
+          inscriptions_received: inscription_ids(entry.inscriptions_received)?,
+          inscriptions_sent: inscription_ids(entry.inscriptions_sent)?,
+          received: entry.received,
+          runes_received: rune_amounts(entry.runes_received)?,
+          runes_sent: rune_amounts(entry.runes_sent)?,
+          sent: entry.sent,
+          txid: entry.txid,
+        })
+      })
+      .collect::<Result<Vec<api::AddressHistoryEntry>>>()?;
+
+    Ok((entries, next))
+  }
 
   pub(crate) fn get_sat_balances_for_outputs(&self, outputs: &Vec<OutPoint>) -> Result<u64> {
     let outpoint_to_utxo_entry = self
@@ -6674,6 +8046,1129 @@ mod tests {
     );
   }
 
+  #[test]
+  fn event_log() {
+    let context = Context::builder().arg("--index-events").build();
+
+    context.mine_blocks(1);
+
+    let create_txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      fee: 0,
+      outputs: 1,
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let transfer_txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Default::default())],
+      fee: 0,
+      outputs: 1,
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let inscription_id = InscriptionId {
+      txid: create_txid,
+      index: 0,
+    };
+
+    let created = (
+      0,
+      Event::InscriptionCreated {
+        block_height: 2,
+        charms: 0,
+        inscription_id,
+        location: Some(SatPoint {
+          outpoint: OutPoint {
+            txid: create_txid,
+            vout: 0,
+          },
+          offset: 0,
+        }),
+        parent_inscription_ids: Vec::new(),
+        sequence_number: 0,
+      },
+    );
+
+    let transferred = (
+      1,
+      Event::InscriptionTransferred {
+        block_height: 3,
+        inscription_id,
+        new_location: SatPoint {
+          outpoint: OutPoint {
+            txid: transfer_txid,
+            vout: 0,
+          },
+          offset: 0,
+        },
+        old_location: SatPoint {
+          outpoint: OutPoint {
+            txid: create_txid,
+            vout: 0,
+          },
+          offset: 0,
+        },
+        sequence_number: 0,
+      },
+    );
+
+    assert_eq!(
+      context.index.get_events_in_block(2).unwrap(),
+      vec![created.clone()]
+    );
+
+    assert_eq!(
+      context.index.get_events_in_block(3).unwrap(),
+      vec![transferred.clone()]
+    );
+
+    assert_eq!(
+      context.index.get_events_paginated(0, 1).unwrap(),
+      (vec![created.clone()], Some(transferred.0)),
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_events_paginated(transferred.0, 1)
+        .unwrap(),
+      (vec![transferred], None),
+    );
+  }
+
+  #[test]
+  fn event_log_is_not_written_without_index_events() {
+    let context = Context::builder().build();
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    assert!(!context.index.has_event_index());
+    assert_eq!(context.index.get_events_in_block(2).unwrap(), []);
+  }
+
+  #[test]
+  fn unrecoverable_reorg_is_an_error_by_default() {
+    let mut context = Context::builder().arg("--max-savepoints=1").build();
+
+    context.index.set_durability(redb::Durability::Immediate);
+
+    context.mine_blocks(11);
+
+    for _ in 0..3 {
+      context.core.invalidate_tip();
+    }
+
+    context.mine_blocks_with_update(5, false);
+
+    assert!(matches!(
+      context.index.update().unwrap_err().downcast_ref(),
+      Some(reorg::Error::Unrecoverable { .. })
+    ));
+
+    assert!(context.index.status(false).unwrap().unrecoverably_reorged);
+  }
+
+  #[test]
+  fn unrecoverable_reorg_rolls_back_to_oldest_savepoint() {
+    let mut context = Context::builder()
+      .arg("--max-savepoints=1")
+      .arg("--rollback-unrecoverable-reorgs")
+      .build();
+
+    context.index.set_durability(redb::Durability::Immediate);
+
+    context.mine_blocks(1);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(10);
+
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    assert!(context.index.inscription_exists(inscription_id).unwrap());
+
+    for _ in 0..10 {
+      context.core.invalidate_tip();
+    }
+
+    context.mine_blocks(12);
+
+    assert!(!context.index.inscription_exists(inscription_id).unwrap());
+    assert_eq!(context.index.block_count().unwrap(), 14);
+    assert_eq!(context.index.statistic(Statistic::Reorgs), 1);
+    assert!(!context.index.status(false).unwrap().unrecoverably_reorged);
+  }
+
+  #[test]
+  fn reorg_emits_reorg_and_undo_events() {
+    let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(1024);
+    let mut context = Context::builder()
+      .arg("--index-events")
+      .event_sender(event_sender)
+      .build();
+
+    context.index.set_durability(redb::Durability::Immediate);
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let created = event_receiver.blocking_recv().unwrap();
+    assert_eq!(created.kind(), "inscription_created");
+
+    let old_hash = context.index.block_hash(Some(2)).unwrap().unwrap();
+
+    assert_eq!(context.index.statistic(Statistic::Reorgs), 0);
+
+    context.core.invalidate_tip();
+    context.mine_blocks(2);
+
+    let Event::Reorg {
+      depth,
+      height,
+      old_hashes,
+    } = event_receiver.blocking_recv().unwrap()
+    else {
+      panic!("expected reorg event");
+    };
+
+    assert_eq!(height, 3);
+    assert_eq!(depth, 2);
+    assert!(old_hashes.contains(&old_hash));
+
+    assert_eq!(
+      event_receiver.blocking_recv().unwrap(),
+      Event::Undo(Box::new(created.clone()))
+    );
+
+    assert_eq!(context.index.statistic(Statistic::Reorgs), 1);
+
+    // the rolled back event had id 0, and ids are not reused, so cursors
+    // past it see the reorg and undo events
+    let (logged, _next) = context.index.get_events_paginated(0, 100).unwrap();
+
+    assert_eq!((logged[0].0, logged[0].1.kind()), (1, "reorg"));
+    assert_eq!(logged[1], (2, Event::Undo(Box::new(created))));
+
+    assert_eq!(
+      context.index.get_events_in_block(2).unwrap()[..2],
+      logged[..2]
+    );
+  }
+
+  #[test]
+  fn reorg_event_is_sent_without_index_events() {
+    let (event_sender, mut event_receiver) = tokio::sync::mpsc::channel(1024);
+    let mut context = Context::builder().event_sender(event_sender).build();
+
+    context.index.set_durability(redb::Durability::Immediate);
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      event_receiver.blocking_recv().unwrap().kind(),
+      "inscription_created"
+    );
+
+    context.core.invalidate_tip();
+    context.mine_blocks(2);
+
+    assert_eq!(context.index.statistic(Statistic::Reorgs), 1);
+
+    assert_eq!(event_receiver.blocking_recv().unwrap().kind(), "reorg");
+
+    while let Ok(event) = event_receiver.try_recv() {
+      assert_eq!(event.kind(), "inscription_created");
+    }
+  }
+
+  fn dump_tables(index: &Index) -> BTreeMap<String, Vec<(Vec<u8>, Vec<u8>)>> {
+    fn dump<K: redb::Key + 'static, V: redb::Value + 'static>(
+      rtx: &redb::ReadTransaction,
+      definition: TableDefinition<K, V>,
+    ) -> Vec<(Vec<u8>, Vec<u8>)> {
+      rtx
+        .open_table(definition)
+        .unwrap()
+        .iter()
+        .unwrap()
+        .map(|result| {
+          let (key, value) = result.unwrap();
+          let key = K::as_bytes(&key.value()).as_ref().to_vec();
+          let value = V::as_bytes(&value.value()).as_ref().to_vec();
+          (key, value)
+        })
+        .collect()
+    }
+
+    fn dump_multimap<K: redb::Key + 'static, V: redb::Key + 'static>(
+      rtx: &redb::ReadTransaction,
+      definition: MultimapTableDefinition<K, V>,
+    ) -> Vec<(Vec<u8>, Vec<u8>)> {
+      let mut entries = Vec::new();
+
+      for result in rtx.open_multimap_table(definition).unwrap().iter().unwrap() {
+        let (key, values) = result.unwrap();
+        for value in values {
+          entries.push((
+            K::as_bytes(&key.value()).as_ref().to_vec(),
+            V::as_bytes(&value.unwrap().value()).as_ref().to_vec(),
+          ));
+        }
+      }
+
+      entries
+    }
+
+    let rtx = index.database.begin_read().unwrap();
+
+    let mut tables = BTreeMap::new();
+
+    macro_rules! dump {
+      ($($table:ident),*) => {
+        $(tables.insert($table.name().to_string(), dump(&rtx, $table));)*
+      };
+    }
+
+    macro_rules! dump_multimap {
+      ($($table:ident),*) => {
+        $(tables.insert($table.name().to_string(), dump_multimap(&rtx, $table));)*
+      };
+    }
+
+    dump!(
+      EVENT_ID_TO_EVENT,
+      HEIGHT_TO_BLOCK_HEADER,
+      HEIGHT_TO_LAST_EVENT_ID,
+      HEIGHT_TO_LAST_SEQUENCE_NUMBER,
+      HOME_INSCRIPTIONS,
+      INSCRIPTION_ID_TO_SEQUENCE_NUMBER,
+      INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER,
+      OUTPOINT_TO_RUNE_BALANCES,
+      OUTPOINT_TO_UTXO_ENTRY,
+      RUNE_ID_TO_RUNE_ENTRY,
+      RUNE_TO_RUNE_ID,
+      SAT_TO_SATPOINT,
+      SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY,
+      SEQUENCE_NUMBER_TO_RUNE_ID,
+      SEQUENCE_NUMBER_TO_SATPOINT,
+      STATISTIC_TO_COUNT,
+      TRANSACTION_ID_TO_RUNE,
+      TRANSACTION_ID_TO_TRANSACTION
+    );
+
+    dump_multimap!(
+      SAT_TO_SEQUENCE_NUMBER,
+      SCRIPT_PUBKEY_TO_OUTPOINT,
+      SEQUENCE_NUMBER_TO_CHILDREN
+    );
+
+    // the commit count and throughput statistics always go up
+    tables
+      .get_mut(STATISTIC_TO_COUNT.name())
+      .unwrap()
+      .retain(|(key, _)| {
+        ![
+          Statistic::Commits,
+          Statistic::IndexedBlocks,
+          Statistic::IndexedTransactions,
+          Statistic::IndexingTime,
+        ]
+        .iter()
+        .any(|statistic| *key == statistic.key().to_le_bytes())
+      });
+
+    tables
+  }
+
+  #[test]
+  fn rollback_with_undo_log_restores_earlier_state() {
+    const RUNE: u128 = 99246114928149462;
+
+    let context = Context::builder()
+      .args([
+        "--index-addresses",
+        "--index-events",
+        "--index-runes",
+        "--index-sats",
+        "--index-transactions",
+        "--index-undo-log",
+      ])
+      .build();
+
+    context.mine_blocks(1);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    // rolling back appends undo events to the event log instead of restoring it
+    let tables = |index: &Index| {
+      let mut tables = dump_tables(index);
+      tables.remove("EVENT_ID_TO_EVENT").unwrap();
+      tables
+    };
+
+    let before = tables(&context.index);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Witness::new())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let (_txid, id) = context.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          terms: Some(Terms {
+            amount: Some(1000),
+            cap: Some(100),
+            ..default()
+          }),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(6, 0, 0, Witness::new())],
+      op_return: Some(
+        Runestone {
+          mint: Some(id),
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let mut after = tables(&context.index);
+
+    assert_eq!(context.index.block_count().unwrap(), 13);
+    assert!(context.index.get_rune_by_id(id).unwrap().is_some());
+
+    assert_eq!(context.index.rollback(2).unwrap(), 10);
+
+    assert_eq!(context.index.block_count().unwrap(), 3);
+    assert!(context.index.inscription_exists(inscription_id).unwrap());
+    assert!(context.index.get_rune_by_id(id).unwrap().is_none());
+    assert_eq!(tables(&context.index), before);
+
+    let (logged, _next) = context.index.get_events_paginated(0, 100).unwrap();
+
+    assert_eq!(
+      logged
+        .iter()
+        .map(|(id, event)| (*id, event.kind()))
+        .collect::<Vec<(u64, &str)>>(),
+      [
+        (0, "inscription_created"),
+        (5, "undo"),
+        (6, "undo"),
+        (7, "undo"),
+        (8, "undo"),
+      ],
+    );
+
+    context.index.update().unwrap();
+
+    // replayed events are logged under new ids
+    let mut replayed = tables(&context.index);
+    after.remove("HEIGHT_TO_LAST_EVENT_ID").unwrap();
+    replayed.remove("HEIGHT_TO_LAST_EVENT_ID").unwrap();
+
+    assert_eq!(context.index.block_count().unwrap(), 13);
+    assert_eq!(replayed, after);
+    assert_eq!(
+      context.index.get_events_in_block(3).unwrap()[..4]
+        .iter()
+        .map(|(id, _event)| *id)
+        .collect::<Vec<u64>>(),
+      [5, 6, 7, 8],
+    );
+  }
+
+  #[test]
+  fn rollback_uses_savepoint_at_height() {
+    let mut context = Context::builder().arg("--index-sats").build();
+
+    context.index.set_durability(redb::Durability::Immediate);
+
+    context.mine_blocks(1);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+    context.mine_blocks(1);
+
+    assert_eq!(context.index.block_count().unwrap(), 4);
+
+    assert_eq!(context.index.rollback(2).unwrap(), 1);
+
+    assert_eq!(context.index.block_count().unwrap(), 3);
+    assert!(context
+      .index
+      .inscription_exists(InscriptionId { txid, index: 0 })
+      .unwrap());
+
+    assert_eq!(
+      context.index.rollback(1).unwrap_err().to_string(),
+      "no savepoint at height 1, and index was not built with `--index-undo-log`",
+    );
+
+    context.index.update().unwrap();
+
+    assert_eq!(context.index.block_count().unwrap(), 4);
+  }
+
+  #[test]
+  fn rollback_to_current_height_is_an_error() {
+    let context = Context::builder().arg("--index-undo-log").build();
+
+    context.mine_blocks(2);
+
+    assert_eq!(
+      context.index.rollback(2).unwrap_err().to_string(),
+      "cannot roll back to height 2: index has 3 blocks, so it is already at or below that height",
+    );
+
+    assert_eq!(context.index.rollback(1).unwrap(), 1);
+    assert_eq!(context.index.block_count().unwrap(), 2);
+  }
+
+  #[test]
+  fn undo_log_depth_limits_rollback() {
+    let context = Context::builder()
+      .args(["--index-undo-log", "--undo-log-depth", "2"])
+      .build();
+
+    context.mine_blocks(5);
+
+    assert_eq!(
+      context
+        .index
+        .database
+        .begin_read()
+        .unwrap()
+        .open_table(HEIGHT_TO_UNDO_LOG)
+        .unwrap()
+        .iter()
+        .unwrap()
+        .map(|result| result.unwrap().0.value())
+        .collect::<Vec<u32>>(),
+      [4, 5],
+    );
+
+    assert_eq!(
+      context.index.rollback(1).unwrap_err().to_string(),
+      "undo log for block 3 is missing, it may have been pruned by `--undo-log-depth`",
+    );
+
+    assert_eq!(context.index.block_count().unwrap(), 6);
+
+    assert_eq!(context.index.rollback(3).unwrap(), 2);
+    assert_eq!(context.index.block_count().unwrap(), 4);
+  }
+
+  #[test]
+  fn check_finds_no_violations_in_consistent_index() {
+    const RUNE: u128 = 99246114928149462;
+
+    for context in [
+      Context::builder().build(),
+      Context::builder()
+        .args(["--index-addresses", "--index-runes", "--index-sats"])
+        .build(),
+    ] {
+      context.mine_blocks(1);
+
+      let txid = context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+        ..default()
+      });
+
+      context.mine_blocks(1);
+
+      context.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 1, 0, Witness::new())],
+        fee: 50 * COIN_VALUE,
+        ..default()
+      });
+
+      context.mine_blocks_with_subsidy(1, 0);
+
+      context.index.assert_inscription_location(
+        InscriptionId { txid, index: 0 },
+        SatPoint {
+          outpoint: OutPoint::null(),
+          offset: 0,
+        },
+        Some(50 * COIN_VALUE),
+      );
+
+      if context.index.index_runes {
+        let (_txid, id) = context.etch(
+          Runestone {
+            etching: Some(Etching {
+              premine: Some(1000),
+              rune: Some(Rune(RUNE)),
+              terms: Some(Terms {
+                amount: Some(1000),
+                cap: Some(100),
+                ..default()
+              }),
+              ..default()
+            }),
+            ..default()
+          },
+          1,
+        );
+
+        context.core.broadcast_tx(TransactionTemplate {
+          inputs: &[(5, 0, 0, Witness::new())],
+          op_return: Some(
+            Runestone {
+              mint: Some(id),
+              pointer: Some(1),
+              ..default()
+            }
+            .encipher(),
+          ),
+          ..default()
+        });
+
+        context.mine_blocks(1);
+
+        assert_eq!(
+          context.index.get_rune_by_id(id).unwrap().unwrap(),
+          Rune(RUNE)
+        );
+      }
+
+      assert_eq!(context.index.check().unwrap(), Vec::new());
+    }
+  }
+
+  #[test]
+  fn check_reports_violations() {
+    let context = Context::builder().arg("--index-sats").build();
+
+    context.mine_blocks(1);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    assert_eq!(context.index.check().unwrap(), Vec::new());
+
+    let outpoint = OutPoint { txid, vout: 0 };
+
+    {
+      let wtx = context.index.database.begin_write().unwrap();
+
+      wtx
+        .open_table(OUTPOINT_TO_UTXO_ENTRY)
+        .unwrap()
+        .remove(&outpoint.store())
+        .unwrap();
+
+      Index::increment_statistic(&wtx, Statistic::BlessedInscriptions, 1).unwrap();
+
+      wtx.commit().unwrap();
+    }
+
+    let satpoint = SatPoint {
+      outpoint,
+      offset: 0,
+    };
+
+    assert_eq!(
+      context.index.check().unwrap(),
+      [
+        Violation::SatSupplyMismatch {
+          sats: 100 * COIN_VALUE,
+          supply: 150 * COIN_VALUE,
+        },
+        Violation::InscriptionOutputSpent {
+          satpoint,
+          sequence_number: 0,
+        },
+        Violation::StatisticMismatch {
+          count: 1,
+          statistic: "BlessedInscriptions".into(),
+          table: "INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER".into(),
+          value: 2,
+        },
+      ],
+    );
+  }
+
+  #[test]
+  fn check_sorts_sat_ranges_across_runs() {
+    let ranges = [(7, 8), (0, 2), (5, 6), (1, 3), (9, 10), (4, 5), (2, 4)];
+
+    for run_len in [1, 2, 3, ranges.len(), ranges.len() + 1] {
+      let mut sorted = check::SortedSatRanges::new(run_len);
+
+      for range in ranges {
+        sorted.push(range).unwrap();
+      }
+
+      let mut merged = Vec::new();
+
+      sorted.for_each(|range| merged.push(range)).unwrap();
+
+      let mut expected = ranges.to_vec();
+      expected.sort_unstable();
+
+      assert_eq!(merged, expected, "run length {run_len}");
+    }
+  }
+
+  #[test]
+  fn event_log_is_rolled_back_on_reorg() {
+    let mut context = Context::builder().arg("--index-events").build();
+
+    context.index.set_durability(redb::Durability::Immediate);
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    assert_eq!(context.index.get_events_in_block(2).unwrap().len(), 1);
+
+    context.core.invalidate_tip();
+    context.mine_blocks(2);
+
+    assert_eq!(
+      context
+        .index
+        .get_events_in_block(2)
+        .unwrap()
+        .iter()
+        .map(|(_id, event)| event.kind())
+        .collect::<Vec<&str>>(),
+      ["reorg", "undo"],
+    );
+  }
+
+  #[test]
+  fn read_only_index_follows_writer() {
+    let Context {
+      index,
+      core,
+      tempdir: _tempdir,
+    } = Context::builder().build();
+
+    let settings = index.settings.clone();
+
+    core.mine_blocks(1);
+    index.update().unwrap();
+
+    assert!(Index::open_read_only(&settings)
+      .err()
+      .unwrap()
+      .to_string()
+      .contains("index is open for writing by another process"));
+
+    drop(index);
+
+    let reader = Index::open_read_only(&settings).unwrap();
+    assert_eq!(reader.block_count().unwrap(), 2);
+
+    let writer = Index::open(&settings).unwrap();
+    core.mine_blocks(1);
+    writer.update_holding_snapshot().unwrap();
+
+    assert_eq!(reader.block_count().unwrap(), 2);
+
+    drop(writer);
+
+    assert_eq!(
+      Index::open_read_only(&settings)
+        .unwrap()
+        .block_count()
+        .unwrap(),
+      3
+    );
+
+    assert_eq!(
+      reader.update().unwrap_err().to_string(),
+      "index is read-only"
+    );
+  }
+
+  #[test]
+  fn index_daemon_waits_for_read_only_indexes_to_reopen() {
+    let Context {
+      index,
+      core,
+      tempdir: _tempdir,
+    } = Context::builder().build();
+
+    let settings = index.settings.clone();
+
+    core.mine_blocks(1);
+    index.update().unwrap();
+    drop(index);
+
+    let readers = Readers::new(settings.index());
+
+    assert_eq!(readers.generation().unwrap(), 0);
+
+    let old = Index::open_read_only(&settings).unwrap();
+
+    assert!(readers.lagging(0).unwrap().is_empty());
+
+    readers.set_generation(1).unwrap();
+
+    assert_eq!(readers.lagging(1).unwrap(), [process::id()]);
+
+    let new = Index::open_read_only(&settings).unwrap();
+
+    assert_eq!(readers.lagging(1).unwrap(), [process::id()]);
+
+    drop(old);
+
+    assert!(readers.lagging(1).unwrap().is_empty());
+    assert!(readers.wait(1, Duration::MAX).unwrap());
+
+    drop(new);
+
+    assert!(readers.lagging(2).unwrap().is_empty());
+
+    let exited = settings
+      .index()
+      .with_extension("redb.readers")
+      .join("4294967295");
+
+    fs::write(&exited, "0").unwrap();
+
+    assert!(readers.lagging(2).unwrap().is_empty());
+    assert!(!exited.exists());
+  }
+
+  #[test]
+  fn index_daemon_evicts_read_only_indexes_that_do_not_reopen() {
+    let Context {
+      index,
+      core,
+      tempdir: _tempdir,
+    } = Context::builder().build();
+
+    let settings = index.settings.clone();
+
+    core.mine_blocks(1);
+    index.update().unwrap();
+    drop(index);
+
+    let readers = Readers::new(settings.index());
+
+    let _reader = Index::open_read_only(&settings).unwrap();
+
+    readers.set_generation(1).unwrap();
+
+    assert_eq!(readers.lagging(1).unwrap(), [process::id()]);
+    assert!(readers.wait(1, Duration::ZERO).unwrap());
+    assert!(readers.lagging(1).unwrap().is_empty());
+  }
+
+  #[test]
+  fn throughput_statistics_are_recorded() {
+    let context = Context::builder().arg("--index-runes").build();
+
+    context.mine_blocks(1);
+
+    context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    context.mine_blocks(2);
+
+    assert_eq!(context.index.statistic(Statistic::IndexedBlocks), 4);
+    assert_eq!(context.index.statistic(Statistic::IndexedTransactions), 5);
+    assert!(context.index.statistic(Statistic::IndexingTime) > 0);
+
+    let info = context.index.info().unwrap();
+    assert_eq!(info.throughput.blocks, 4);
+    assert_eq!(info.throughput.transactions, 5);
+    assert!(info.throughput.blocks_per_second > 0.0);
+  }
+
+  #[test]
+  fn index_from_block_files() {
+    let context = Context::builder()
+      .block_files()
+      .args(["--chain-tip-distance", "2"])
+      .build();
+
+    context.mine_blocks(1);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    context.mine_blocks(10);
+
+    assert_eq!(context.index.block_count().unwrap(), 12);
+
+    for (height, hash) in context.core.state().hashes.iter().enumerate() {
+      assert_eq!(
+        context
+          .index
+          .block_hash(Some(height.try_into().unwrap()))
+          .unwrap(),
+        Some(*hash),
+      );
+    }
+
+    context.index.assert_inscription_location(
+      inscription_id,
+      SatPoint {
+        outpoint: OutPoint { txid, vout: 0 },
+        offset: 0,
+      },
+      Some(50 * COIN_VALUE),
+    );
+  }
+
+  #[test]
+  fn index_falls_back_to_rpc_without_block_files() {
+    let context = Context::builder()
+      .block_files()
+      .args(["--chain-tip-distance", "2"])
+      .build();
+
+    context.mine_blocks_with_update(10, false);
+
+    fs::remove_file(context.core.blocks_dir().join("blk00000.dat")).unwrap();
+
+    context.index.update().unwrap();
+
+    assert_eq!(context.index.block_count().unwrap(), 11);
+    assert_eq!(
+      context.index.block_hash(None).unwrap(),
+      context.core.state().hashes.last().copied(),
+    );
+  }
+
+  #[test]
+  fn index_from_esplora() {
+    const RUNE: u128 = 99246114928149462;
+
+    let context = Context::builder().esplora().arg("--index-runes").build();
+
+    context.mine_blocks(1);
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    context.mine_blocks(1);
+
+    context.index.assert_inscription_location(
+      inscription_id,
+      SatPoint {
+        outpoint: OutPoint { txid, vout: 0 },
+        offset: 0,
+      },
+      Some(50 * COIN_VALUE),
+    );
+
+    let (txid, id) = context.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          premine: Some(u128::MAX),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    context.assert_runes(
+      [(
+        id,
+        RuneEntry {
+          block: id.block,
+          etching: txid,
+          spaced_rune: SpacedRune {
+            rune: Rune(RUNE),
+            spacers: 0,
+          },
+          premine: u128::MAX,
+          timestamp: id.block,
+          ..default()
+        },
+      )],
+      [(OutPoint { txid, vout: 0 }, vec![(id, u128::MAX)])],
+    );
+
+    let hashes = context.core.state().hashes.clone();
+
+    for (height, hash) in hashes.iter().enumerate() {
+      assert_eq!(
+        context
+          .index
+          .block_hash(Some(height.try_into().unwrap()))
+          .unwrap(),
+        Some(*hash),
+      );
+
+      assert_eq!(
+        context.index.get_block_height_by_hash(*hash).unwrap(),
+        Some(height.try_into().unwrap()),
+      );
+
+      assert_eq!(
+        context
+          .index
+          .get_block_by_height(height.try_into().unwrap())
+          .unwrap()
+          .unwrap()
+          .block_hash(),
+        *hash,
+      );
+    }
+
+    assert_eq!(
+      context
+        .index
+        .get_transaction(txid)
+        .unwrap()
+        .unwrap()
+        .compute_txid(),
+      txid,
+    );
+
+    assert!(!context
+      .index
+      .is_output_spent(OutPoint { txid, vout: 0 })
+      .unwrap());
+
+    assert!(context
+      .index
+      .is_output_in_active_chain(OutPoint { txid, vout: 0 })
+      .unwrap());
+
+    let txid = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(3, 0, 0, Witness::new())],
+      ..default()
+    });
+
+    assert_eq!(context.index.mempool_txids().unwrap(), [txid]);
+
+    assert!(!context
+      .index
+      .is_output_in_active_chain(OutPoint { txid, vout: 0 })
+      .unwrap());
+
+    assert!(context.index.block_stats(0).is_err());
+  }
+
+  #[test]
+  fn esplora_must_be_on_same_chain() {
+    let core = mockcore::builder()
+      .network(Network::Signet)
+      .esplora(true)
+      .build();
+
+    let tempdir = TempDir::new().unwrap();
+
+    let settings = Settings::from_options(
+      Options::try_parse_from([
+        "ord".into(),
+        "--regtest".into(),
+        "--datadir".into(),
+        tempdir.path().as_os_str().to_owned(),
+        "--esplora-url".into(),
+        core.esplora_url().into(),
+      ])
+      .unwrap(),
+    )
+    .or_defaults()
+    .unwrap();
+
+    assert_eq!(
+      Index::open(&settings).err().unwrap().to_string(),
+      format!("Esplora at `{}` is not on regtest", core.esplora_url()),
+    );
+  }
+
+  #[test]
+  fn read_only_index_must_exist() {
+    let tempdir = TempDir::new().unwrap();
+
+    let settings = Settings::from_options(
+      Options::try_parse_from([
+        "ord".into(),
+        "--regtest".into(),
+        "--datadir".into(),
+        tempdir.path().as_os_str().to_owned(),
+      ])
+      .unwrap(),
+    )
+    .or_defaults()
+    .unwrap();
+
+    assert!(Index::open_read_only(&settings).is_err());
+    assert!(!settings.index().exists());
+  }
+
   #[test]
   fn assert_schema_statistic_key_is_zero() {
     // other schema statistic keys may chenge when the schema changes, but for
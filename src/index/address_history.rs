@@ -0,0 +1,51 @@
+use super::*;
+
+/// Everything a single transaction did to a single script pubkey, stored as
+/// JSON under the script pubkey and the transaction's height and index in its
+/// block. Inscriptions are stored as sequence numbers and resolved to ids when
+/// the history is read.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct AddressHistoryEntry {
+  pub(crate) inscriptions_received: Vec<u32>,
+  pub(crate) inscriptions_sent: Vec<u32>,
+  pub(crate) received: u64,
+  pub(crate) runes_received: BTreeMap<RuneId, u128>,
+  pub(crate) runes_sent: BTreeMap<RuneId, u128>,
+  pub(crate) sent: u64,
+  pub(crate) txid: Txid,
+}
+
+impl AddressHistoryEntry {
+  pub(crate) fn new(txid: Txid) -> Self {
+    Self {
+      inscriptions_received: Vec::new(),
+      inscriptions_sent: Vec::new(),
+      received: 0,
+      runes_received: BTreeMap::new(),
+      runes_sent: BTreeMap::new(),
+      sent: 0,
+      txid,
+    }
+  }
+}
+
+/// Address history entries accumulated while indexing a block, keyed by
+/// transaction and script pubkey, and written out once the block is done.
+#[derive(Default)]
+pub(crate) struct AddressHistory(HashMap<(Txid, ScriptBuf), AddressHistoryEntry>);
+
+impl AddressHistory {
+  pub(crate) fn entry(&mut self, txid: Txid, script_pubkey: &[u8]) -> &mut AddressHistoryEntry {
+    self
+      .0
+      .entry((txid, ScriptBuf::from_bytes(script_pubkey.to_vec())))
+      .or_insert_with(|| AddressHistoryEntry::new(txid))
+  }
+
+  pub(crate) fn into_entries(self) -> impl Iterator<Item = (Txid, ScriptBuf, AddressHistoryEntry)> {
+    self
+      .0
+      .into_iter()
+      .map(|((txid, script_pubkey), entry)| (txid, script_pubkey, entry))
+  }
+}
@@ -0,0 +1,98 @@
+use super::*;
+
+/// Moves rune balances from `unallocated` to the per-output balances in
+/// `allocated` as directed by `edicts`. Edicts for rune ID `0:0` refer to the
+/// rune etched by the transaction, if any.
+pub(super) fn apply_edicts(
+  tx: &Transaction,
+  edicts: &[Edict],
+  etched: Option<RuneId>,
+  unallocated: &mut HashMap<RuneId, Lot>,
+  allocated: &mut [HashMap<RuneId, Lot>],
+) {
+  for Edict { id, amount, output } in edicts.iter().copied() {
+    let amount = Lot(amount);
+
+    // edicts with output values greater than the number of outputs
+    // should never be produced by the edict parser
+    let output = usize::try_from(output).unwrap();
+    assert!(output <= tx.output.len());
+
+    let id = if id == RuneId::default() {
+      let Some(id) = etched else {
+        continue;
+      };
+
+      id
+    } else {
+      id
+    };
+
+    let Some(balance) = unallocated.get_mut(&id) else {
+      continue;
+    };
+
+    let mut allocate = |balance: &mut Lot, amount: Lot, output: usize| {
+      if amount > 0 {
+        *balance -= amount;
+        *allocated[output].entry(id).or_default() += amount;
+      }
+    };
+
+    if output == tx.output.len() {
+      // find non-OP_RETURN outputs
+      let destinations = tx
+        .output
+        .iter()
+        .enumerate()
+        .filter_map(|(output, tx_out)| (!tx_out.script_pubkey.is_op_return()).then_some(output))
+        .collect::<Vec<usize>>();
+
+      if !destinations.is_empty() {
+        if amount == 0 {
+          // if amount is zero, divide balance between eligible outputs
+          let amount = *balance / destinations.len() as u128;
+          let remainder = usize::try_from(*balance % destinations.len() as u128).unwrap();
+
+          for (i, output) in destinations.iter().enumerate() {
+            allocate(
+              balance,
+              if i < remainder { amount + 1 } else { amount },
+              *output,
+            );
+          }
+        } else {
+          // if amount is non-zero, distribute amount to eligible outputs
+          for output in destinations {
+            allocate(balance, amount.min(*balance), output);
+          }
+        }
+      }
+    } else {
+      // Get the allocatable amount
+      let amount = if amount == 0 {
+        *balance
+      } else {
+        amount.min(*balance)
+      };
+
+      allocate(balance, amount, output);
+    }
+  }
+}
+
+/// Returns the output that receives runes not allocated by edicts: the
+/// runestone's pointer, or the first non-OP_RETURN output if there is no
+/// pointer. Returns `None` if the runes should be burned.
+pub(super) fn default_output(tx: &Transaction, pointer: Option<u32>) -> Option<usize> {
+  pointer
+    .map(|pointer| pointer.into_usize())
+    .inspect(|&pointer| assert!(pointer < tx.output.len()))
+    .or_else(|| {
+      tx.output
+        .iter()
+        .enumerate()
+        .find(|(_vout, tx_out)| !tx_out.script_pubkey.is_op_return())
+        .map(|(vout, _tx_out)| vout)
+    })
+}
@@ -0,0 +1,298 @@
+use {
+  super::*,
+  std::io::{Seek, SeekFrom},
+};
+
+// blocks read at multiples of this height are checked against Bitcoin Core
+const CHECKPOINT_INTERVAL: u32 = 1000;
+
+#[derive(Clone, Copy, Debug)]
+struct Location {
+  file: u32,
+  len: u32,
+  offset: u64,
+}
+
+/// Reads blocks directly from Bitcoin Core's `blk*.dat` files, which is much
+/// faster than fetching them over JSON-RPC during initial sync. The files are
+/// scanned once for block headers, and the active chain is found by following
+/// previous block hashes back from the block Bitcoin Core reports at the end
+/// height to the one it reports at the start height.
+pub(crate) struct BlockFiles {
+  blocks: Vec<(BlockHash, Location)>,
+  dir: PathBuf,
+  file: Option<(u32, File)>,
+  key: [u8; 8],
+  start: u32,
+}
+
+impl BlockFiles {
+  /// Locates blocks `start..=end` of the active chain in the block files in
+  /// `dir`. Returns `None` if any of them are missing, for example because
+  /// Bitcoin Core is pruned.
+  pub(crate) fn open(
+    dir: &Path,
+    network: Network,
+    block_source: &dyn BlockSource,
+    start: u32,
+    end: u32,
+  ) -> Result<Option<Self>> {
+    let (Some(start_hash), Some(end_hash)) = (
+      block_source.block_hash(start)?,
+      block_source.block_hash(end)?,
+    ) else {
+      return Ok(None);
+    };
+
+    // blocks are obfuscated since Bitcoin Core 28.0
+    let key = match fs::read(dir.join("xor.dat")) {
+      Ok(key) => key
+        .try_into()
+        .map_err(|key: Vec<u8>| anyhow!("xor.dat is {} bytes, expected 8", key.len()))?,
+      Err(err) if err.kind() == io::ErrorKind::NotFound => [0; 8],
+      Err(err) => return Err(err.into()),
+    };
+
+    let magic = network.magic().to_bytes();
+
+    let mut locations = HashMap::new();
+
+    for file in 0.. {
+      let path = Self::path(dir, file);
+
+      let mut reader = match File::open(&path) {
+        Ok(file) => BufReader::new(file),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => break,
+        Err(err) => return Err(err.into()),
+      };
+
+      let mut offset = 0;
+
+      loop {
+        let mut prefix = [0; 88];
+
+        match reader.read_exact(&mut prefix) {
+          Ok(()) => {}
+          Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => break,
+          Err(err) => return Err(err.into()),
+        }
+
+        Self::deobfuscate(key, offset, &mut prefix);
+
+        // Bitcoin Core preallocates block files with zeros
+        if prefix[..4] != magic {
+          if prefix[..4] != [0; 4] {
+            log::warn!("unexpected data in {} at offset {offset}", path.display());
+          }
+          break;
+        }
+
+        let len = u32::from_le_bytes(prefix[4..8].try_into().unwrap());
+
+        if len < 80 {
+          log::warn!(
+            "invalid block size in {} at offset {offset}",
+            path.display()
+          );
+          break;
+        }
+
+        let header = consensus::deserialize::<Header>(&prefix[8..])?;
+
+        locations.insert(
+          header.block_hash(),
+          (
+            header.prev_blockhash,
+            Location {
+              file,
+              len,
+              offset: offset + 8,
+            },
+          ),
+        );
+
+        reader.seek_relative(i64::from(len) - 80)?;
+
+        offset += 8 + u64::from(len);
+      }
+    }
+
+    let mut blocks = Vec::new();
+    let mut hash = end_hash;
+
+    loop {
+      let Some((prev_blockhash, location)) = locations.get(&hash) else {
+        return Ok(None);
+      };
+
+      blocks.push((hash, *location));
+
+      if blocks.len() > usize::try_from(end - start).unwrap() {
+        break;
+      }
+
+      hash = *prev_blockhash;
+    }
+
+    if hash != start_hash {
+      return Ok(None);
+    }
+
+    blocks.reverse();
+
+    Ok(Some(Self {
+      blocks,
+      dir: dir.into(),
+      file: None,
+      key,
+      start,
+    }))
+  }
+
+  /// Returns the block at `height`, or `None` if it is not covered by these
+  /// block files.
+  pub(crate) fn block(
+    &mut self,
+    block_source: &dyn BlockSource,
+    height: u32,
+  ) -> Result<Option<Block>> {
+    let Some((hash, location)) = height
+      .checked_sub(self.start)
+      .and_then(|i| self.blocks.get(usize::try_from(i).unwrap()))
+      .copied()
+    else {
+      return Ok(None);
+    };
+
+    if height % CHECKPOINT_INTERVAL == 0 {
+      ensure!(
+        block_source.block_hash(height)? == Some(hash),
+        "block {hash} at height {height} is no longer in the active chain",
+      );
+    }
+
+    let file = match &mut self.file {
+      Some((file, handle)) if *file == location.file => handle,
+      file => {
+        &mut file
+          .insert((
+            location.file,
+            File::open(Self::path(&self.dir, location.file))?,
+          ))
+          .1
+      }
+    };
+
+    let mut buffer = vec![0; usize::try_from(location.len).unwrap()];
+
+    file.seek(SeekFrom::Start(location.offset))?;
+    file.read_exact(&mut buffer)?;
+
+    Self::deobfuscate(self.key, location.offset, &mut buffer);
+
+    let block = consensus::deserialize::<Block>(&buffer)?;
+
+    ensure!(
+      block.block_hash() == hash,
+      "block at height {height} in {} does not have hash {hash}",
+      Self::path(&self.dir, location.file).display(),
+    );
+
+    Ok(Some(block))
+  }
+
+  fn deobfuscate(key: [u8; 8], offset: u64, data: &mut [u8]) {
+    if key == [0; 8] {
+      return;
+    }
+
+    for (i, byte) in data.iter_mut().enumerate() {
+      *byte ^= key[usize::try_from((offset + u64::try_from(i).unwrap()) % 8).unwrap()];
+    }
+  }
+
+  fn path(dir: &Path, file: u32) -> PathBuf {
+    dir.join(format!("blk{file:05}.dat"))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn core() -> mockcore::Handle {
+    mockcore::builder()
+      .network(Network::Regtest)
+      .block_files(true)
+      .build()
+  }
+
+  #[test]
+  fn reads_active_chain() {
+    let core = core();
+
+    let blocks = core.mine_blocks(30);
+
+    let client = Client::new(&core.url(), bitcoincore_rpc::Auth::None).unwrap();
+
+    let mut block_files = BlockFiles::open(&core.blocks_dir(), Network::Regtest, &client, 5, 25)
+      .unwrap()
+      .unwrap();
+
+    assert!(fs::metadata(BlockFiles::path(&core.blocks_dir(), 1)).is_ok());
+
+    assert_eq!(block_files.block(&client, 4).unwrap(), None);
+
+    for height in 5..=25 {
+      assert_eq!(
+        block_files.block(&client, height).unwrap().unwrap(),
+        blocks[usize::try_from(height).unwrap() - 1],
+      );
+    }
+
+    assert_eq!(block_files.block(&client, 26).unwrap(), None);
+  }
+
+  #[test]
+  fn skips_stale_blocks() {
+    let core = core();
+
+    core.mine_blocks(5);
+    core.invalidate_tip();
+    core.invalidate_tip();
+    let blocks = core.mine_blocks(3);
+
+    let client = Client::new(&core.url(), bitcoincore_rpc::Auth::None).unwrap();
+
+    let mut block_files = BlockFiles::open(&core.blocks_dir(), Network::Regtest, &client, 0, 6)
+      .unwrap()
+      .unwrap();
+
+    for (i, block) in blocks.iter().enumerate() {
+      assert_eq!(
+        &block_files
+          .block(&client, u32::try_from(i).unwrap() + 4)
+          .unwrap()
+          .unwrap(),
+        block,
+      );
+    }
+  }
+
+  #[test]
+  fn missing_blocks() {
+    let core = core();
+
+    core.mine_blocks(10);
+
+    let client = Client::new(&core.url(), bitcoincore_rpc::Auth::None).unwrap();
+
+    fs::remove_file(BlockFiles::path(&core.blocks_dir(), 0)).unwrap();
+
+    assert!(
+      BlockFiles::open(&core.blocks_dir(), Network::Regtest, &client, 0, 10)
+        .unwrap()
+        .is_none()
+    );
+  }
+}
@@ -0,0 +1,289 @@
+use {super::*, reqwest::StatusCode, serde::de::DeserializeOwned};
+
+/// Where the index gets blocks, transactions, and the mempool from. Bitcoin
+/// Core's JSON-RPC interface is used by default, and an Esplora instance can
+/// be used instead with `--esplora-url`.
+pub(crate) trait BlockSource: Send + Sync {
+  fn block(&self, hash: BlockHash) -> Result<Option<Block>>;
+
+  /// Height of the chain tip.
+  fn block_count(&self) -> Result<u32>;
+
+  fn block_hash(&self, height: u32) -> Result<Option<BlockHash>>;
+
+  fn block_header(&self, hash: BlockHash) -> Result<Option<Header>>;
+
+  /// Only available from Bitcoin Core, since Esplora doesn't serve chainwork.
+  fn block_header_info(&self, hash: BlockHash) -> Result<Option<GetBlockHeaderResult>>;
+
+  fn block_height(&self, hash: BlockHash) -> Result<Option<u32>>;
+
+  /// Only available from Bitcoin Core.
+  fn block_stats(&self, height: u32) -> Result<Option<GetBlockStatsResult>>;
+
+  /// Height of the best known header, which may be ahead of `block_count`
+  /// while the source is still syncing.
+  fn header_count(&self) -> Result<u32>;
+
+  /// Whether `outpoint` exists and is not spent by a confirmed or mempool
+  /// transaction.
+  fn is_unspent(&self, outpoint: OutPoint) -> Result<bool>;
+
+  fn mempool(&self) -> Result<Vec<Txid>>;
+
+  fn transaction(&self, txid: Txid) -> Result<Option<Transaction>>;
+
+  /// Height of the block containing `txid`, or `None` if it is unconfirmed or
+  /// unknown.
+  fn transaction_height(&self, txid: Txid) -> Result<Option<u32>>;
+}
+
+pub(crate) fn open(settings: &Settings) -> Result<Arc<dyn BlockSource>> {
+  match settings.esplora_url() {
+    Some(url) => {
+      let esplora = Esplora::new(url)?;
+
+      let genesis_block_hash = settings.chain().genesis_block().block_hash();
+
+      let esplora_genesis_block_hash = esplora
+        .block_hash(0)
+        .with_context(|| format!("failed to connect to Esplora at `{url}`"))?;
+
+      ensure!(
+        esplora_genesis_block_hash == Some(genesis_block_hash),
+        "Esplora at `{url}` is not on {}",
+        settings.chain(),
+      );
+
+      Ok(Arc::new(esplora))
+    }
+    None => Ok(Arc::new(settings.bitcoin_rpc_client(None)?)),
+  }
+}
+
+impl BlockSource for Client {
+  fn block(&self, hash: BlockHash) -> Result<Option<Block>> {
+    self.get_block(&hash).into_option()
+  }
+
+  fn block_count(&self) -> Result<u32> {
+    Ok(self.get_block_count()?.try_into()?)
+  }
+
+  fn block_hash(&self, height: u32) -> Result<Option<BlockHash>> {
+    self.get_block_hash(height.into()).into_option()
+  }
+
+  fn block_header(&self, hash: BlockHash) -> Result<Option<Header>> {
+    self.get_block_header(&hash).into_option()
+  }
+
+  fn block_header_info(&self, hash: BlockHash) -> Result<Option<GetBlockHeaderResult>> {
+    self.get_block_header_info(&hash).into_option()
+  }
+
+  fn block_height(&self, hash: BlockHash) -> Result<Option<u32>> {
+    Ok(
+      self
+        .block_header_info(hash)?
+        .map(|info| info.height.try_into().unwrap()),
+    )
+  }
+
+  fn block_stats(&self, height: u32) -> Result<Option<GetBlockStatsResult>> {
+    self.get_block_stats(height.into()).into_option()
+  }
+
+  fn header_count(&self) -> Result<u32> {
+    Ok(self.get_blockchain_info()?.headers.try_into()?)
+  }
+
+  fn is_unspent(&self, outpoint: OutPoint) -> Result<bool> {
+    Ok(
+      self
+        .get_tx_out(&outpoint.txid, outpoint.vout, Some(true))?
+        .is_some(),
+    )
+  }
+
+  fn mempool(&self) -> Result<Vec<Txid>> {
+    Ok(self.get_raw_mempool()?)
+  }
+
+  fn transaction(&self, txid: Txid) -> Result<Option<Transaction>> {
+    self.get_raw_transaction(&txid, None).into_option()
+  }
+
+  fn transaction_height(&self, txid: Txid) -> Result<Option<u32>> {
+    let Some(blockhash) = self
+      .get_raw_transaction_info(&txid, None)
+      .into_option()?
+      .and_then(|info| info.blockhash)
+    else {
+      return Ok(None);
+    };
+
+    self.block_height(blockhash)
+  }
+}
+
+/// Client for the Esplora REST API, as served by Blockstream's electrs fork.
+pub(crate) struct Esplora {
+  client: reqwest::blocking::Client,
+  url: String,
+}
+
+#[derive(Deserialize)]
+struct BlockSummary {
+  height: u32,
+}
+
+#[derive(Deserialize)]
+struct OutputSpend {
+  spent: bool,
+}
+
+#[derive(Deserialize)]
+struct TransactionStatus {
+  block_height: Option<u32>,
+}
+
+impl Esplora {
+  pub(crate) fn new(url: &str) -> Result<Self> {
+    let url = if url.starts_with("http://") || url.starts_with("https://") {
+      url.trim_end_matches('/').into()
+    } else {
+      format!("http://{}", url.trim_end_matches('/'))
+    };
+
+    Ok(Self {
+      client: reqwest::blocking::Client::builder()
+        .timeout(Duration::from_secs(60))
+        .build()?,
+      url,
+    })
+  }
+
+  fn get(&self, path: &str) -> Result<Option<reqwest::blocking::Response>> {
+    let response = self.client.get(format!("{}{path}", self.url)).send()?;
+
+    if response.status() == StatusCode::NOT_FOUND {
+      return Ok(None);
+    }
+
+    Ok(Some(response.error_for_status()?))
+  }
+
+  fn bytes(&self, path: &str) -> Result<Option<Vec<u8>>> {
+    self
+      .get(path)?
+      .map(|response| Ok(response.bytes()?.to_vec()))
+      .transpose()
+  }
+
+  fn json<T: DeserializeOwned>(&self, path: &str) -> Result<Option<T>> {
+    self
+      .get(path)?
+      .map(|response| Ok(response.json()?))
+      .transpose()
+  }
+
+  fn text(&self, path: &str) -> Result<Option<String>> {
+    self
+      .get(path)?
+      .map(|response| Ok(response.text()?.trim().to_string()))
+      .transpose()
+  }
+}
+
+impl BlockSource for Esplora {
+  fn block(&self, hash: BlockHash) -> Result<Option<Block>> {
+    self
+      .bytes(&format!("/block/{hash}/raw"))?
+      .map(|bytes| Ok(consensus::deserialize(&bytes)?))
+      .transpose()
+  }
+
+  fn block_count(&self) -> Result<u32> {
+    self
+      .text("/blocks/tip/height")?
+      .ok_or_else(|| anyhow!("Esplora did not return chain tip"))?
+      .parse()
+      .context("invalid chain tip height from Esplora")
+  }
+
+  fn block_hash(&self, height: u32) -> Result<Option<BlockHash>> {
+    self
+      .text(&format!("/block-height/{height}"))?
+      .map(|hash| hash.parse().context("invalid block hash from Esplora"))
+      .transpose()
+  }
+
+  fn block_header(&self, hash: BlockHash) -> Result<Option<Header>> {
+    self
+      .text(&format!("/block/{hash}/header"))?
+      .map(|header| Ok(consensus::deserialize(&hex::decode(header)?)?))
+      .transpose()
+  }
+
+  fn block_header_info(&self, _hash: BlockHash) -> Result<Option<GetBlockHeaderResult>> {
+    bail!("block header info is not available from Esplora")
+  }
+
+  fn block_height(&self, hash: BlockHash) -> Result<Option<u32>> {
+    Ok(
+      self
+        .json::<BlockSummary>(&format!("/block/{hash}"))?
+        .map(|block| block.height),
+    )
+  }
+
+  fn block_stats(&self, _height: u32) -> Result<Option<GetBlockStatsResult>> {
+    bail!("block stats are not available from Esplora")
+  }
+
+  // Esplora only serves blocks it has fully indexed, so its tip is the best
+  // header it knows of.
+  fn header_count(&self) -> Result<u32> {
+    self.block_count()
+  }
+
+  // Esplora reports outputs of unknown transactions as unspent, so check that
+  // the output exists first
+  fn is_unspent(&self, outpoint: OutPoint) -> Result<bool> {
+    let Some(transaction) = self.transaction(outpoint.txid)? else {
+      return Ok(false);
+    };
+
+    if outpoint.vout.into_usize() >= transaction.output.len() {
+      return Ok(false);
+    }
+
+    Ok(
+      self
+        .json::<OutputSpend>(&format!("/tx/{}/outspend/{}", outpoint.txid, outpoint.vout))?
+        .is_some_and(|output| !output.spent),
+    )
+  }
+
+  fn mempool(&self) -> Result<Vec<Txid>> {
+    self
+      .json("/mempool/txids")?
+      .ok_or_else(|| anyhow!("Esplora did not return mempool"))
+  }
+
+  fn transaction(&self, txid: Txid) -> Result<Option<Transaction>> {
+    self
+      .bytes(&format!("/tx/{txid}/raw"))?
+      .map(|bytes| Ok(consensus::deserialize(&bytes)?))
+      .transpose()
+  }
+
+  fn transaction_height(&self, txid: Txid) -> Result<Option<u32>> {
+    Ok(
+      self
+        .json::<TransactionStatus>(&format!("/tx/{txid}/status"))?
+        .and_then(|status| status.block_height),
+    )
+  }
+}
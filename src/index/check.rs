@@ -0,0 +1,546 @@
+use {
+  super::*,
+  std::{
+    cmp::Reverse,
+    collections::BinaryHeap,
+    io::{BufReader, Seek},
+  },
+};
+
+// sat ranges are sorted in runs of this many, which are written to temporary
+// files and merged, so that checking them for overlaps uses bounded memory
+const SAT_RANGE_RUN_LEN: usize = 1 << 20;
+
+/// An inconsistency between index tables found by `ord index check`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Violation {
+  BrokenHeaderChain {
+    height: u32,
+  },
+  InscriptionLocationMismatch {
+    satpoint: Option<SatPoint>,
+    sequence_number: u32,
+    utxo_satpoint: Option<SatPoint>,
+  },
+  InscriptionOutputSpent {
+    satpoint: SatPoint,
+    sequence_number: u32,
+  },
+  LostSatsMismatch {
+    sats: u64,
+    statistic: u64,
+  },
+  OverlappingSatRanges {
+    first: (u64, u64),
+    second: (u64, u64),
+  },
+  RuneSupplyMismatch {
+    balances: u128,
+    burned: u128,
+    rune_id: RuneId,
+    supply: u128,
+  },
+  SatSupplyMismatch {
+    sats: u64,
+    supply: u64,
+  },
+  StatisticMismatch {
+    count: u64,
+    statistic: String,
+    table: String,
+    value: u64,
+  },
+  TableLengthMismatch {
+    first: String,
+    first_len: u64,
+    second: String,
+    second_len: u64,
+  },
+  UnknownRuneBalance {
+    outpoint: OutPoint,
+    rune_id: RuneId,
+  },
+}
+
+pub(super) struct Checker<'a> {
+  block_count: u32,
+  index: &'a Index,
+  rtx: redb::ReadTransaction,
+  violations: Vec<Violation>,
+}
+
+impl<'a> Checker<'a> {
+  pub(super) fn new(index: &'a Index) -> Result<Self> {
+    let rtx = index.begin_read()?;
+
+    Ok(Self {
+      block_count: rtx.block_count()?,
+      rtx: rtx.0,
+      index,
+      violations: Vec::new(),
+    })
+  }
+
+  pub(super) fn check(mut self) -> Result<Vec<Violation>> {
+    self.check_header_chain()?;
+
+    if self.index.index_sats || self.index.index_inscriptions {
+      self.check_utxo_entries()?;
+    }
+
+    if self.index.index_inscriptions {
+      self.check_inscription_tables()?;
+    }
+
+    if self.index.index_runes {
+      self.check_runes()?;
+    }
+
+    Ok(self.violations)
+  }
+
+  fn check_header_chain(&mut self) -> Result {
+    let mut previous: Option<(u32, BlockHash)> = None;
+
+    for result in self.rtx.open_table(HEIGHT_TO_BLOCK_HEADER)?.iter()? {
+      let (height, header) = result?;
+      let height = height.value();
+      let header = Header::load(*header.value());
+
+      let linked = match previous {
+        Some((previous_height, previous_hash)) => {
+          previous_height.checked_add(1) == Some(height) && header.prev_blockhash == previous_hash
+        }
+        None => height == 0,
+      };
+
+      if !linked {
+        self
+          .violations
+          .push(Violation::BrokenHeaderChain { height });
+      }
+
+      previous = Some((height, header.block_hash()));
+    }
+
+    Ok(())
+  }
+
+  fn check_utxo_entries(&mut self) -> Result {
+    let mut sat_ranges = SortedSatRanges::new(SAT_RANGE_RUN_LEN);
+    let mut sats = 0u64;
+    let mut lost_sats = 0u64;
+    let mut inscriptions = HashMap::new();
+    let mut unbound_inscriptions = 0u64;
+
+    for result in self.rtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?.iter()? {
+      let (outpoint, utxo_entry) = result?;
+      let outpoint = OutPoint::load(*outpoint.value());
+      let utxo_entry = utxo_entry.value().parse(self.index);
+
+      if self.index.index_sats {
+        for chunk in utxo_entry.sat_ranges().chunks_exact(11) {
+          let (start, end) = SatRange::load(chunk.try_into().unwrap());
+
+          sat_ranges.push((start, end))?;
+
+          if outpoint == OutPoint::null() {
+            lost_sats += end - start;
+          } else {
+            sats += end - start;
+          }
+        }
+      }
+
+      if self.index.index_inscriptions {
+        for (sequence_number, offset) in utxo_entry.parse_inscriptions() {
+          inscriptions.insert(sequence_number, SatPoint { outpoint, offset });
+
+          if outpoint == unbound_outpoint() {
+            unbound_inscriptions += 1;
+          }
+        }
+      }
+    }
+
+    if self.index.index_sats {
+      let mut previous: Option<(u64, u64)> = None;
+
+      sat_ranges.for_each(|range| {
+        if let Some(previous) = previous {
+          if range.0 < previous.1 {
+            self.violations.push(Violation::OverlappingSatRanges {
+              first: previous,
+              second: range,
+            });
+          }
+        }
+
+        previous = Some(range);
+      })?;
+
+      let lost_sats_statistic = self.statistic(Statistic::LostSats)?;
+
+      if lost_sats != lost_sats_statistic {
+        self.violations.push(Violation::LostSatsMismatch {
+          sats: lost_sats,
+          statistic: lost_sats_statistic,
+        });
+      }
+
+      let supply = Height(self.block_count)
+        .starting_sat()
+        .n()
+        .saturating_sub(lost_sats_statistic);
+
+      if sats != supply {
+        self
+          .violations
+          .push(Violation::SatSupplyMismatch { sats, supply });
+      }
+    }
+
+    if self.index.index_inscriptions {
+      for result in self.rtx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?.iter()? {
+        let (sequence_number, satpoint) = result?;
+        let sequence_number = sequence_number.value();
+        let satpoint = SatPoint::load(*satpoint.value());
+
+        let utxo_satpoint = inscriptions.remove(&sequence_number);
+
+        if utxo_satpoint == Some(satpoint) {
+          continue;
+        }
+
+        let spent = self
+          .rtx
+          .open_table(OUTPOINT_TO_UTXO_ENTRY)?
+          .get(&satpoint.outpoint.store())?
+          .is_none();
+
+        if spent {
+          self.violations.push(Violation::InscriptionOutputSpent {
+            satpoint,
+            sequence_number,
+          });
+        }
+
+        if !spent || utxo_satpoint.is_some() {
+          self
+            .violations
+            .push(Violation::InscriptionLocationMismatch {
+              satpoint: Some(satpoint),
+              sequence_number,
+              utxo_satpoint,
+            });
+        }
+      }
+
+      let mut inscriptions = inscriptions.into_iter().collect::<Vec<(u32, SatPoint)>>();
+
+      inscriptions.sort_unstable_by_key(|(sequence_number, _)| *sequence_number);
+
+      for (sequence_number, utxo_satpoint) in inscriptions {
+        self
+          .violations
+          .push(Violation::InscriptionLocationMismatch {
+            satpoint: None,
+            sequence_number,
+            utxo_satpoint: Some(utxo_satpoint),
+          });
+      }
+
+      self.check_statistic(
+        Statistic::UnboundInscriptions,
+        OUTPOINT_TO_UTXO_ENTRY.name(),
+        unbound_inscriptions,
+      )?;
+    }
+
+    Ok(())
+  }
+
+  fn check_inscription_tables(&mut self) -> Result {
+    let inscription_number_to_sequence_number =
+      self.rtx.open_table(INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER)?;
+
+    let blessed = u64::try_from(inscription_number_to_sequence_number.range(0..)?.count())?;
+    let cursed = u64::try_from(inscription_number_to_sequence_number.range(..0)?.count())?;
+
+    self.check_statistic(
+      Statistic::BlessedInscriptions,
+      INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER.name(),
+      blessed,
+    )?;
+
+    self.check_statistic(
+      Statistic::CursedInscriptions,
+      INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER.name(),
+      cursed,
+    )?;
+
+    let entries = (
+      SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY.name(),
+      self
+        .rtx
+        .open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?
+        .len()?,
+    );
+
+    for other in [
+      (
+        INSCRIPTION_ID_TO_SEQUENCE_NUMBER.name(),
+        self
+          .rtx
+          .open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?
+          .len()?,
+      ),
+      (
+        INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER.name(),
+        inscription_number_to_sequence_number.len()?,
+      ),
+      (
+        SEQUENCE_NUMBER_TO_SATPOINT.name(),
+        self.rtx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?.len()?,
+      ),
+    ] {
+      self.check_table_lengths(entries, other);
+    }
+
+    if self.index.index_content_types {
+      self.check_table_lengths(
+        entries,
+        (
+          MEDIA_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID.name(),
+          self
+            .rtx
+            .open_table(MEDIA_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID)?
+            .len()?,
+        ),
+      );
+    }
+
+    Ok(())
+  }
+
+  fn check_runes(&mut self) -> Result {
+    let mut balances = HashMap::<RuneId, u128>::new();
+    let mut holdings = 0u64;
+
+    let rune_id_to_rune_entry = self.rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+
+    for result in self.rtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?.iter()? {
+      let (outpoint, buffer) = result?;
+      let outpoint = OutPoint::load(*outpoint.value());
+      let buffer = buffer.value();
+
+      let mut i = 0;
+      while i < buffer.len() {
+        let ((rune_id, amount), length) = Index::decode_rune_balance(&buffer[i..])?;
+        i += length;
+
+        if rune_id_to_rune_entry.get(&rune_id.store())?.is_none() {
+          self
+            .violations
+            .push(Violation::UnknownRuneBalance { outpoint, rune_id });
+          continue;
+        }
+
+        let balance = balances.entry(rune_id).or_default();
+        *balance = balance.saturating_add(amount);
+        holdings += 1;
+      }
+    }
+
+    for result in rune_id_to_rune_entry.iter()? {
+      let (rune_id, entry) = result?;
+      let rune_id = RuneId::load(rune_id.value());
+      let entry = RuneEntry::load(entry.value());
+
+      let balances = balances.get(&rune_id).copied().unwrap_or_default();
+
+      if balances.checked_add(entry.burned) != Some(entry.supply()) {
+        self.violations.push(Violation::RuneSupplyMismatch {
+          balances,
+          burned: entry.burned,
+          rune_id,
+          supply: entry.supply(),
+        });
+      }
+    }
+
+    let runes = rune_id_to_rune_entry.len()?;
+
+    self.check_statistic(Statistic::Runes, RUNE_ID_TO_RUNE_ENTRY.name(), runes)?;
+
+    self.check_table_lengths(
+      (RUNE_ID_TO_RUNE_ENTRY.name(), runes),
+      (
+        RUNE_TO_RUNE_ID.name(),
+        self.rtx.open_table(RUNE_TO_RUNE_ID)?.len()?,
+      ),
+    );
+
+    self.check_table_lengths(
+      (OUTPOINT_TO_RUNE_BALANCES.name(), holdings),
+      (
+        RUNE_ID_TO_OUTPOINT_BALANCE.name(),
+        self
+          .rtx
+          .open_multimap_table(RUNE_ID_TO_OUTPOINT_BALANCE)?
+          .len()?,
+      ),
+    );
+
+    if self.index.index_addresses {
+      self.check_table_lengths(
+        (
+          RUNE_ID_AND_SCRIPT_PUBKEY_TO_BALANCE.name(),
+          self
+            .rtx
+            .open_table(RUNE_ID_AND_SCRIPT_PUBKEY_TO_BALANCE)?
+            .len()?,
+        ),
+        (
+          SCRIPT_PUBKEY_AND_RUNE_ID_TO_BALANCE.name(),
+          self
+            .rtx
+            .open_table(SCRIPT_PUBKEY_AND_RUNE_ID_TO_BALANCE)?
+            .len()?,
+        ),
+      );
+    }
+
+    Ok(())
+  }
+
+  fn check_statistic(&mut self, statistic: Statistic, table: &str, count: u64) -> Result {
+    let value = self.statistic(statistic)?;
+
+    if value != count {
+      self.violations.push(Violation::StatisticMismatch {
+        count,
+        statistic: format!("{statistic:?}"),
+        table: table.into(),
+        value,
+      });
+    }
+
+    Ok(())
+  }
+
+  fn check_table_lengths(
+    &mut self,
+    (first, first_len): (&str, u64),
+    (second, second_len): (&str, u64),
+  ) {
+    if first_len != second_len {
+      self.violations.push(Violation::TableLengthMismatch {
+        first: first.into(),
+        first_len,
+        second: second.into(),
+        second_len,
+      });
+    }
+  }
+
+  fn statistic(&self, statistic: Statistic) -> Result<u64> {
+    Ok(
+      self
+        .rtx
+        .open_table(STATISTIC_TO_COUNT)?
+        .get(&statistic.key())?
+        .map(|x| x.value())
+        .unwrap_or_default(),
+    )
+  }
+}
+
+/// Sat ranges sorted with an external merge sort.
+pub(super) struct SortedSatRanges {
+  buffer: Vec<(u64, u64)>,
+  run_len: usize,
+  runs: Vec<BufReader<File>>,
+}
+
+impl SortedSatRanges {
+  pub(super) fn new(run_len: usize) -> Self {
+    Self {
+      buffer: Vec::new(),
+      run_len,
+      runs: Vec::new(),
+    }
+  }
+
+  pub(super) fn push(&mut self, range: (u64, u64)) -> Result {
+    self.buffer.push(range);
+
+    if self.buffer.len() >= self.run_len {
+      self.spill()?;
+    }
+
+    Ok(())
+  }
+
+  /// Calls `f` with every range pushed, in ascending order.
+  pub(super) fn for_each(mut self, mut f: impl FnMut((u64, u64))) -> Result {
+    if self.runs.is_empty() {
+      self.buffer.sort_unstable();
+      self.buffer.into_iter().for_each(f);
+      return Ok(());
+    }
+
+    self.spill()?;
+
+    let mut heap = BinaryHeap::new();
+
+    for (i, run) in self.runs.iter_mut().enumerate() {
+      if let Some(range) = Self::read(run)? {
+        heap.push(Reverse((range, i)));
+      }
+    }
+
+    while let Some(Reverse((range, i))) = heap.pop() {
+      f(range);
+
+      if let Some(range) = Self::read(&mut self.runs[i])? {
+        heap.push(Reverse((range, i)));
+      }
+    }
+
+    Ok(())
+  }
+
+  fn read(run: &mut BufReader<File>) -> Result<Option<(u64, u64)>> {
+    let mut buffer = [0; 16];
+
+    match run.read_exact(&mut buffer) {
+      Ok(()) => Ok(Some((
+        u64::from_le_bytes(buffer[..8].try_into().unwrap()),
+        u64::from_le_bytes(buffer[8..].try_into().unwrap()),
+      ))),
+      Err(err) if err.kind() == io::ErrorKind::UnexpectedEof => Ok(None),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  fn spill(&mut self) -> Result {
+    self.buffer.sort_unstable();
+
+    let mut writer = BufWriter::new(tempfile::tempfile()?);
+
+    for (start, end) in self.buffer.drain(..) {
+      writer.write_all(&start.to_le_bytes())?;
+      writer.write_all(&end.to_le_bytes())?;
+    }
+
+    let mut file = writer.into_inner()?;
+
+    file.rewind()?;
+
+    self.runs.push(BufReader::new(file));
+
+    Ok(())
+  }
+}
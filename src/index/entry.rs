@@ -273,6 +273,97 @@ impl Entry for RuneEntry {
   }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuneActivityKind {
+  Burn,
+  Mint,
+  Transfer,
+}
+
+impl TryFrom<u8> for RuneActivityKind {
+  type Error = anyhow::Error;
+
+  fn try_from(kind: u8) -> Result<Self> {
+    match kind {
+      0 => Ok(Self::Burn),
+      1 => Ok(Self::Mint),
+      2 => Ok(Self::Transfer),
+      _ => Err(anyhow!("invalid rune activity kind {kind}")),
+    }
+  }
+}
+
+impl From<RuneActivityKind> for u8 {
+  fn from(kind: RuneActivityKind) -> Self {
+    match kind {
+      RuneActivityKind::Burn => 0,
+      RuneActivityKind::Mint => 1,
+      RuneActivityKind::Transfer => 2,
+    }
+  }
+}
+
+impl Display for RuneActivityKind {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Self::Burn => "burn",
+        Self::Mint => "mint",
+        Self::Transfer => "transfer",
+      }
+    )
+  }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RuneActivityEntry {
+  pub amount: u128,
+  pub height: u32,
+  pub inputs: Vec<OutPoint>,
+  pub kind: RuneActivityKind,
+  pub output: Option<OutPoint>,
+  pub txid: Txid,
+}
+
+pub(crate) type RuneActivityEntryValue = (
+  u128,                  // amount
+  u32,                   // height
+  Vec<OutPointValue>,    // inputs
+  u8,                    // kind
+  Option<OutPointValue>, // output
+  TxidValue,             // txid
+);
+
+// Not an `Entry`, since loading fails on an unknown kind rather than panicking
+impl RuneActivityEntry {
+  pub(crate) fn load(
+    (amount, height, inputs, kind, output, txid): RuneActivityEntryValue,
+  ) -> Result<Self> {
+    Ok(Self {
+      amount,
+      height,
+      inputs: inputs.into_iter().map(OutPoint::load).collect(),
+      kind: kind.try_into()?,
+      output: output.map(OutPoint::load),
+      txid: Txid::load(txid),
+    })
+  }
+
+  pub(crate) fn store(self) -> RuneActivityEntryValue {
+    (
+      self.amount,
+      self.height,
+      self.inputs.into_iter().map(OutPoint::store).collect(),
+      self.kind.into(),
+      self.output.map(OutPoint::store),
+      self.txid.store(),
+    )
+  }
+}
+
 pub(super) type RuneIdValue = (u64, u32);
 
 impl Entry for RuneId {
@@ -474,6 +565,47 @@ impl Entry for SatRange {
   }
 }
 
+#[derive(Debug, Eq, PartialEq, Clone)]
+pub struct TransferEntry {
+  pub height: u32,
+  pub new_satpoint: SatPoint,
+  pub old_satpoint: Option<SatPoint>,
+  pub script_pubkey: Option<ScriptBuf>,
+  pub txid: Txid,
+}
+
+pub(crate) type TransferEntryValue = (
+  u32,                   // height
+  SatPointValue,         // new satpoint
+  Option<SatPointValue>, // old satpoint
+  Option<Vec<u8>>,       // script pubkey
+  TxidValue,             // txid
+);
+
+impl Entry for TransferEntry {
+  type Value = TransferEntryValue;
+
+  fn load((height, new_satpoint, old_satpoint, script_pubkey, txid): TransferEntryValue) -> Self {
+    Self {
+      height,
+      new_satpoint: SatPoint::load(new_satpoint),
+      old_satpoint: old_satpoint.map(SatPoint::load),
+      script_pubkey: script_pubkey.map(ScriptBuf::from_bytes),
+      txid: Txid::load(txid),
+    }
+  }
+
+  fn store(self) -> Self::Value {
+    (
+      self.height,
+      self.new_satpoint.store(),
+      self.old_satpoint.map(SatPoint::store),
+      self.script_pubkey.map(ScriptBuf::into_bytes),
+      self.txid.store(),
+    )
+  }
+}
+
 pub(super) type TxidValue = [u8; 32];
 
 impl Entry for Txid {
@@ -516,6 +648,75 @@ mod tests {
     assert_eq!(InscriptionEntry::load(value), entry);
   }
 
+  #[test]
+  fn rune_activity_entry() {
+    let entry = RuneActivityEntry {
+      amount: 1,
+      height: 2,
+      inputs: vec![outpoint(3), outpoint(4)],
+      kind: RuneActivityKind::Transfer,
+      output: Some(outpoint(5)),
+      txid: txid(6),
+    };
+
+    let value = (
+      1,
+      2,
+      vec![outpoint(3).store(), outpoint(4).store()],
+      2,
+      Some(outpoint(5).store()),
+      txid(6).store(),
+    );
+
+    assert_eq!(entry.clone().store(), value);
+    assert_eq!(RuneActivityEntry::load(value).unwrap(), entry);
+
+    for kind in [
+      RuneActivityKind::Burn,
+      RuneActivityKind::Mint,
+      RuneActivityKind::Transfer,
+    ] {
+      let entry = RuneActivityEntry {
+        kind,
+        ..entry.clone()
+      };
+
+      assert_eq!(
+        RuneActivityEntry::load(entry.clone().store()).unwrap(),
+        entry
+      );
+    }
+
+    assert_eq!(
+      RuneActivityEntry::load((1, 2, Vec::new(), 3, None, txid(6).store()))
+        .unwrap_err()
+        .to_string(),
+      "invalid rune activity kind 3",
+    );
+  }
+
+  #[test]
+  fn transfer_entry() {
+    let entry = TransferEntry {
+      height: 1,
+      new_satpoint: satpoint(2, 3),
+      old_satpoint: Some(satpoint(4, 5)),
+      script_pubkey: Some(ScriptBuf::from_bytes(vec![6, 7])),
+      txid: txid(8),
+    };
+
+    let value = (
+      1,
+      satpoint(2, 3).store(),
+      Some(satpoint(4, 5).store()),
+      Some(vec![6, 7]),
+      txid(8).store(),
+    );
+
+    assert_eq!(entry.clone().store(), value);
+    assert_eq!(TransferEntry::load(value), entry);
+  }
+
   #[test]
   fn inscription_id_entry() {
     let inscription_id = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdefi0"
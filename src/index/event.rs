@@ -1,6 +1,7 @@
 use super::*;
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Event {
   InscriptionCreated {
     block_height: u32,
@@ -17,6 +18,11 @@ pub enum Event {
     old_location: SatPoint,
     sequence_number: u32,
   },
+  Reorg {
+    depth: u32,
+    height: u32,
+    old_hashes: Vec<BlockHash>,
+  },
   RuneBurned {
     amount: u128,
     block_height: u32,
@@ -41,4 +47,80 @@ pub enum Event {
     rune_id: RuneId,
     txid: Txid,
   },
+  /// Compensates for an event in a block that was rolled back by a reorg.
+  Undo(Box<Event>),
+}
+
+impl Event {
+  pub fn kind(&self) -> &'static str {
+    match self {
+      Self::InscriptionCreated { .. } => "inscription_created",
+      Self::InscriptionTransferred { .. } => "inscription_transferred",
+      Self::Reorg { .. } => "reorg",
+      Self::RuneBurned { .. } => "rune_burned",
+      Self::RuneEtched { .. } => "rune_etched",
+      Self::RuneMinted { .. } => "rune_minted",
+      Self::RuneTransferred { .. } => "rune_transferred",
+      Self::Undo(_) => "undo",
+    }
+  }
+
+  pub fn inscription_id(&self) -> Option<InscriptionId> {
+    match self {
+      Self::InscriptionCreated { inscription_id, .. }
+      | Self::InscriptionTransferred { inscription_id, .. } => Some(*inscription_id),
+      Self::Undo(event) => event.inscription_id(),
+      _ => None,
+    }
+  }
+
+  pub fn rune_id(&self) -> Option<RuneId> {
+    match self {
+      Self::RuneBurned { rune_id, .. }
+      | Self::RuneEtched { rune_id, .. }
+      | Self::RuneMinted { rune_id, .. }
+      | Self::RuneTransferred { rune_id, .. } => Some(*rune_id),
+      Self::Undo(event) => event.rune_id(),
+      _ => None,
+    }
+  }
+
+  /// The output this event moved an inscription or runes into, if any.
+  pub fn outpoint(&self) -> Option<OutPoint> {
+    match self {
+      Self::InscriptionCreated { location, .. } => location.map(|location| location.outpoint),
+      Self::InscriptionTransferred { new_location, .. } => Some(new_location.outpoint),
+      Self::RuneTransferred { outpoint, .. } => Some(*outpoint),
+      Self::Undo(event) => event.outpoint(),
+      _ => None,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn serialization_is_tagged_with_kind() {
+    let event = Event::RuneMinted {
+      amount: u128::MAX,
+      block_height: 1,
+      rune_id: RuneId { block: 1, tx: 2 },
+      txid: txid(1),
+    };
+
+    let json = serde_json::to_string(&event).unwrap();
+
+    assert_eq!(
+      json,
+      format!(
+        r#"{{"rune_minted":{{"amount":{},"block_height":1,"rune_id":"1:2","txid":"{}"}}}}"#,
+        u128::MAX,
+        txid(1),
+      )
+    );
+
+    assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event);
+  }
 }
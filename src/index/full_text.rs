@@ -0,0 +1,225 @@
+use {super::*, std::iter};
+
+pub(crate) const MAX_BODY_SIZE: usize = 64 * 1024;
+pub(crate) const MAX_POSTINGS_PER_TERM: usize = 10_000;
+pub(crate) const MAX_QUERY_TERMS: usize = 8;
+const MAX_TERM_LENGTH: usize = 64;
+const SNIPPET_CONTEXT: usize = 48;
+
+/// Returns the searchable text of an inscription, if it has an uncompressed
+/// text, markdown, HTML, or JSON body no larger than `MAX_BODY_SIZE`. Tags are
+/// stripped from HTML bodies.
+pub(crate) fn text(inscription: &Inscription) -> Option<String> {
+  if inscription.content_encoding().is_some() {
+    return None;
+  }
+
+  let content_type = Media::normalize_content_type(inscription.content_type()?);
+
+  if !(content_type.starts_with("text/") || content_type == "application/json") {
+    return None;
+  }
+
+  let body = inscription
+    .body()
+    .filter(|body| body.len() <= MAX_BODY_SIZE)?;
+
+  let text = std::str::from_utf8(body).ok()?;
+
+  Some(if content_type == "text/html" {
+    strip_tags(text)
+  } else {
+    text.into()
+  })
+}
+
+/// Splits text into distinct lowercase alphanumeric terms.
+pub(crate) fn terms(text: &str) -> BTreeSet<String> {
+  tokens(text)
+    .into_iter()
+    .map(|(start, end)| text[start..end].to_lowercase())
+    .collect()
+}
+
+/// Returns up to `SNIPPET_CONTEXT` characters on either side of the first
+/// occurrence of any of `terms`, with whitespace collapsed.
+pub(crate) fn snippet(text: &str, terms: &BTreeSet<String>) -> String {
+  let (start, end) = tokens(text)
+    .into_iter()
+    .find(|(start, end)| terms.contains(&text[*start..*end].to_lowercase()))
+    .unwrap_or((0, 0));
+
+  let before = text[..start]
+    .char_indices()
+    .rev()
+    .nth(SNIPPET_CONTEXT - 1)
+    .map(|(i, _)| i)
+    .filter(|&i| i > 0);
+
+  let after = text[end..]
+    .char_indices()
+    .nth(SNIPPET_CONTEXT)
+    .map(|(i, _)| end + i);
+
+  let mut snippet = String::new();
+
+  if before.is_some() {
+    snippet.push('…');
+  }
+
+  snippet.push_str(
+    &text[before.unwrap_or(0)..after.unwrap_or(text.len())]
+      .split_whitespace()
+      .collect::<Vec<&str>>()
+      .join(" "),
+  );
+
+  if after.is_some() {
+    snippet.push('…');
+  }
+
+  snippet
+}
+
+fn tokens(text: &str) -> Vec<(usize, usize)> {
+  let mut tokens = Vec::new();
+  let mut start = None;
+
+  for (i, c) in text.char_indices().chain(iter::once((text.len(), ' '))) {
+    match (start, c.is_alphanumeric()) {
+      (None, true) => start = Some(i),
+      (Some(s), false) => {
+        if text[s..i].chars().count() <= MAX_TERM_LENGTH {
+          tokens.push((s, i));
+        }
+        start = None;
+      }
+      _ => {}
+    }
+  }
+
+  tokens
+}
+
+fn strip_tags(html: &str) -> String {
+  let mut text = String::with_capacity(html.len());
+  let mut in_tag = false;
+
+  for c in html.chars() {
+    match c {
+      '<' => in_tag = true,
+      '>' if in_tag => {
+        in_tag = false;
+        text.push(' ');
+      }
+      _ if !in_tag => text.push(c),
+      _ => {}
+    }
+  }
+
+  text
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn text_of(content_type: &str, body: &str) -> Option<String> {
+    text(&Inscription {
+      content_type: Some(content_type.into()),
+      body: Some(body.into()),
+      ..default()
+    })
+  }
+
+  #[test]
+  fn indexed_content_types() {
+    assert_eq!(text_of("text/plain", "foo"), Some("foo".into()));
+    assert_eq!(
+      text_of("text/plain;charset=utf-8", "foo"),
+      Some("foo".into())
+    );
+    assert_eq!(text_of("text/markdown", "# foo"), Some("# foo".into()));
+    assert_eq!(text_of("application/json", "{}"), Some("{}".into()));
+    assert_eq!(text_of("image/png", "foo"), None);
+    assert_eq!(text_of("application/octet-stream", "foo"), None);
+  }
+
+  #[test]
+  fn html_tags_are_stripped() {
+    assert_eq!(
+      text_of("text/html", "<p class=x>foo</p>bar")
+        .unwrap()
+        .trim(),
+      "foo bar",
+    );
+  }
+
+  #[test]
+  fn large_and_encoded_bodies_are_not_indexed() {
+    assert_eq!(
+      text_of("text/plain", &"a".repeat(MAX_BODY_SIZE))
+        .unwrap()
+        .len(),
+      MAX_BODY_SIZE
+    );
+    assert_eq!(text_of("text/plain", &"a".repeat(MAX_BODY_SIZE + 1)), None);
+
+    assert_eq!(
+      text(&Inscription {
+        content_type: Some("text/plain".into()),
+        content_encoding: Some("br".into()),
+        body: Some("foo".into()),
+        ..default()
+      }),
+      None,
+    );
+
+    assert_eq!(
+      text(&Inscription {
+        content_type: Some("text/plain".into()),
+        body: Some(vec![0xff, 0xfe]),
+        ..default()
+      }),
+      None,
+    );
+  }
+
+  #[test]
+  fn terms_are_lowercase_and_distinct() {
+    assert_eq!(
+      terms("Hello, hello WORLD! ünïcode_42"),
+      ["42", "hello", "ünïcode", "world"]
+        .into_iter()
+        .map(str::to_string)
+        .collect(),
+    );
+  }
+
+  #[test]
+  fn long_terms_are_skipped() {
+    assert_eq!(
+      terms(&format!("{} foo", "a".repeat(MAX_TERM_LENGTH + 1))),
+      ["foo".to_string()].into(),
+    );
+  }
+
+  #[test]
+  fn snippet_surrounds_first_match() {
+    let terms = ["needle".to_string()].into();
+
+    assert_eq!(
+      snippet("a needle  in\na haystack", &terms),
+      "a needle in a haystack"
+    );
+
+    let text = format!("{} Needle {}", "x ".repeat(100), "y ".repeat(100));
+
+    let snippet = snippet(&text, &terms);
+
+    assert!(snippet.starts_with('…'));
+    assert!(snippet.ends_with('…'));
+    assert!(snippet.contains("Needle"));
+    assert!(snippet.chars().count() < 2 * SNIPPET_CONTEXT + 10);
+  }
+}
@@ -0,0 +1,584 @@
+use super::*;
+
+/// An output created by a mempool transaction, with the inscriptions and runes
+/// it will hold if the transaction confirms.
+#[derive(Clone, Debug, Default)]
+struct UnconfirmedOutput {
+  inscriptions: Vec<(InscriptionId, u64)>,
+  runes: HashMap<RuneId, Lot>,
+  script_pubkey: ScriptBuf,
+  value: u64,
+}
+
+/// Inscriptions and runes moved by transactions in the Bitcoin Core mempool.
+///
+/// Unconfirmed transactions are run through the same envelope parsing and
+/// rune allocation as confirmed ones, with inputs resolved from the index or
+/// from the outputs of other mempool transactions. Nothing is written to the
+/// database; the overlay is rebuilt from scratch on every update. Runes etched
+/// by unconfirmed transactions are not tracked, since their IDs are not known
+/// until they confirm.
+#[derive(Default)]
+pub(crate) struct Mempool {
+  inscriptions: Vec<api::MempoolInscription>,
+  outputs: BTreeMap<OutPoint, UnconfirmedOutput>,
+  spent: HashMap<OutPoint, Txid>,
+  transactions: HashMap<Txid, Transaction>,
+}
+
+struct Context<'a> {
+  height: u32,
+  index: &'a Index,
+  mints: HashMap<RuneId, u128>,
+  outpoint_to_rune_balances: ReadOnlyTable<&'static OutPointValue, &'static [u8]>,
+  outpoint_to_utxo_entry: ReadOnlyTable<&'static OutPointValue, &'static UtxoEntry>,
+  rune_id_to_rune_entry: ReadOnlyTable<RuneIdValue, RuneEntryValue>,
+  sequence_number_to_inscription_entry: ReadOnlyTable<u32, InscriptionEntryValue>,
+}
+
+impl Mempool {
+  /// Loads the current mempool from the block source, reusing transactions
+  /// already fetched by `previous`.
+  pub(crate) fn load(index: &Index, previous: &Mempool) -> Result<Self> {
+    let mut transactions = HashMap::new();
+
+    for txid in index.mempool_txids()? {
+      let transaction = match previous.transactions.get(&txid) {
+        Some(transaction) => transaction.clone(),
+        // the transaction may have been mined or evicted since the mempool was listed
+        None => match index.block_source.transaction(txid)? {
+          Some(transaction) => transaction,
+          None => continue,
+        },
+      };
+
+      transactions.insert(txid, transaction);
+    }
+
+    let rtx = index.database.begin_read()?;
+
+    let mut context = Context {
+      height: index.block_count()?,
+      index,
+      mints: HashMap::new(),
+      outpoint_to_rune_balances: rtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?,
+      outpoint_to_utxo_entry: rtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?,
+      rune_id_to_rune_entry: rtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?,
+      sequence_number_to_inscription_entry: rtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?,
+    };
+
+    let mut mempool = Self::default();
+
+    for txid in Self::parents_first(&transactions) {
+      mempool.add(&mut context, txid, &transactions[&txid])?;
+    }
+
+    mempool.transactions = transactions;
+
+    Ok(mempool)
+  }
+
+  pub(crate) fn inscriptions(&self) -> Vec<api::MempoolInscription> {
+    self.inscriptions.clone()
+  }
+
+  pub(crate) fn runes(&self, index: &Index) -> Result<Vec<api::MempoolRuneOutput>> {
+    let chain = index.settings.chain();
+
+    self
+      .outputs
+      .iter()
+      .filter(|(_outpoint, output)| !output.runes.is_empty())
+      .map(|(outpoint, output)| {
+        Ok(api::MempoolRuneOutput {
+          address: chain
+            .address_from_script(&output.script_pubkey)
+            .ok()
+            .map(|address| uncheck(&address)),
+          outpoint: *outpoint,
+          runes: Self::piles(index, &output.runes)?,
+        })
+      })
+      .collect()
+  }
+
+  /// Returns pending changes to `outpoint`, if it was created or is being
+  /// spent by a mempool transaction.
+  pub(crate) fn output(
+    &self,
+    index: &Index,
+    outpoint: OutPoint,
+  ) -> Result<Option<api::PendingOutput>> {
+    let spent_by = self.spent.get(&outpoint).copied();
+
+    let Some(output) = self.outputs.get(&outpoint) else {
+      return Ok(spent_by.map(|txid| api::PendingOutput {
+        inscriptions: Vec::new(),
+        runes: BTreeMap::new(),
+        spent_by: Some(txid),
+      }));
+    };
+
+    Ok(Some(api::PendingOutput {
+      inscriptions: output
+        .inscriptions
+        .iter()
+        .map(|(id, _offset)| *id)
+        .collect(),
+      runes: Self::piles(index, &output.runes)?,
+      spent_by,
+    }))
+  }
+
+  /// Returns unconfirmed outputs paying to `script_pubkey`, and which of the
+  /// address's confirmed `outputs` are being spent.
+  pub(crate) fn address(
+    &self,
+    index: &Index,
+    script_pubkey: &Script,
+    outputs: &[OutPoint],
+  ) -> Result<Option<api::PendingAddress>> {
+    let mut inscriptions = Vec::new();
+    let mut pending = Vec::new();
+    let mut runes = HashMap::<RuneId, Lot>::new();
+
+    for (outpoint, output) in &self.outputs {
+      if output.script_pubkey.as_script() != script_pubkey || self.spent.contains_key(outpoint) {
+        continue;
+      }
+
+      pending.push(*outpoint);
+
+      inscriptions.extend(output.inscriptions.iter().map(|(id, _offset)| *id));
+
+      for (id, balance) in &output.runes {
+        *runes.entry(*id).or_default() += *balance;
+      }
+    }
+
+    let spent = outputs
+      .iter()
+      .filter(|outpoint| self.spent.contains_key(outpoint))
+      .copied()
+      .collect::<Vec<OutPoint>>();
+
+    if pending.is_empty() && spent.is_empty() {
+      return Ok(None);
+    }
+
+    Ok(Some(api::PendingAddress {
+      inscriptions,
+      outputs: pending,
+      runes_balances: Self::piles(index, &runes)?
+        .into_iter()
+        .map(|(spaced_rune, pile)| {
+          (
+            spaced_rune,
+            Decimal {
+              value: pile.amount,
+              scale: pile.divisibility,
+            },
+            pile.symbol,
+          )
+        })
+        .collect(),
+      spent,
+    }))
+  }
+
+  fn add(&mut self, context: &mut Context, txid: Txid, tx: &Transaction) -> Result {
+    let mut inputs = Vec::new();
+
+    for txin in &tx.input {
+      let output = match self.outputs.get(&txin.previous_output) {
+        Some(output) => output.clone(),
+        None => match context.resolve(txin.previous_output)? {
+          Some(output) => output,
+          None => return Ok(()),
+        },
+      };
+
+      inputs.push(output);
+    }
+
+    for txin in &tx.input {
+      self.spent.insert(txin.previous_output, txid);
+    }
+
+    let mut outputs = tx
+      .output
+      .iter()
+      .map(|tx_out| UnconfirmedOutput {
+        inscriptions: Vec::new(),
+        runes: HashMap::new(),
+        script_pubkey: tx_out.script_pubkey.clone(),
+        value: tx_out.value.to_sat(),
+      })
+      .collect::<Vec<UnconfirmedOutput>>();
+
+    if context.index.index_inscriptions {
+      self.allocate_inscriptions(txid, tx, &inputs, &mut outputs);
+    }
+
+    if context.index.index_runes {
+      Self::allocate_runes(context, tx, &inputs, &mut outputs)?;
+    }
+
+    for (vout, output) in outputs.into_iter().enumerate() {
+      self.outputs.insert(
+        OutPoint {
+          txid,
+          vout: vout.try_into().unwrap(),
+        },
+        output,
+      );
+    }
+
+    Ok(())
+  }
+
+  fn allocate_inscriptions(
+    &mut self,
+    txid: Txid,
+    tx: &Transaction,
+    inputs: &[UnconfirmedOutput],
+    outputs: &mut [UnconfirmedOutput],
+  ) {
+    let total_output_value = outputs.iter().map(|output| output.value).sum::<u64>();
+
+    let mut envelopes = ParsedEnvelope::from_transaction(tx).into_iter().peekable();
+    let mut floating = Vec::new();
+    let mut id_counter = 0;
+    let mut total_input_value = 0;
+
+    for (input_index, input) in inputs.iter().enumerate() {
+      for (id, offset) in &input.inscriptions {
+        floating.push((total_input_value + offset, *id, false));
+      }
+
+      let offset = total_input_value;
+
+      total_input_value += input.value;
+
+      while let Some(envelope) = envelopes.peek() {
+        if envelope.input != u32::try_from(input_index).unwrap() {
+          break;
+        }
+
+        let offset = envelope
+          .payload
+          .pointer()
+          .filter(|&pointer| pointer < total_output_value)
+          .unwrap_or(offset);
+
+        floating.push((
+          offset,
+          InscriptionId {
+            txid,
+            index: id_counter,
+          },
+          true,
+        ));
+
+        envelopes.next();
+        id_counter += 1;
+      }
+    }
+
+    floating.sort_by_key(|(offset, _id, _new)| *offset);
+
+    let mut floating = floating.into_iter().peekable();
+    let mut start = 0;
+
+    for (vout, output) in outputs.iter_mut().enumerate() {
+      let end = start + output.value;
+
+      while let Some((offset, id, new)) = floating.next_if(|(offset, ..)| *offset < end) {
+        output.inscriptions.push((id, offset - start));
+
+        self.inscriptions.push(api::MempoolInscription {
+          id,
+          new,
+          satpoint: Some(SatPoint {
+            outpoint: OutPoint {
+              txid,
+              vout: vout.try_into().unwrap(),
+            },
+            offset: offset - start,
+          }),
+          txid,
+        });
+      }
+
+      start = end;
+    }
+
+    // inscriptions on sats paid as fees
+    for (_offset, id, new) in floating {
+      self.inscriptions.push(api::MempoolInscription {
+        id,
+        new,
+        satpoint: None,
+        txid,
+      });
+    }
+  }
+
+  fn allocate_runes(
+    context: &mut Context,
+    tx: &Transaction,
+    inputs: &[UnconfirmedOutput],
+    outputs: &mut [UnconfirmedOutput],
+  ) -> Result {
+    let mut unallocated = HashMap::<RuneId, Lot>::new();
+
+    for input in inputs {
+      for (id, balance) in &input.runes {
+        *unallocated.entry(*id).or_default() += *balance;
+      }
+    }
+
+    let mut allocated = vec![HashMap::new(); tx.output.len()];
+
+    let artifact = Runestone::decipher(tx);
+
+    if let Some(artifact) = &artifact {
+      if let Some(id) = artifact.mint() {
+        if let Some(amount) = context.mint(id)? {
+          *unallocated.entry(id).or_default() += amount;
+        }
+      }
+
+      if let Artifact::Runestone(runestone) = artifact {
+        allocation::apply_edicts(
+          tx,
+          &runestone.edicts,
+          None,
+          &mut unallocated,
+          &mut allocated,
+        );
+      }
+    }
+
+    if !matches!(artifact, Some(Artifact::Cenotaph(_))) {
+      let pointer = match &artifact {
+        Some(Artifact::Runestone(runestone)) => runestone.pointer,
+        _ => None,
+      };
+
+      if let Some(vout) = allocation::default_output(tx, pointer) {
+        for (id, balance) in unallocated {
+          if balance > 0 {
+            *allocated[vout].entry(id).or_default() += balance;
+          }
+        }
+      }
+    }
+
+    for (output, runes) in outputs.iter_mut().zip(allocated) {
+      // runes sent to OP_RETURN outputs are burned
+      if !output.script_pubkey.is_op_return() {
+        output.runes = runes;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Orders transactions so that each comes after any of its parents that are
+  /// also in the mempool.
+  fn parents_first(transactions: &HashMap<Txid, Transaction>) -> Vec<Txid> {
+    let mut txids = transactions.keys().copied().collect::<Vec<Txid>>();
+    txids.sort();
+
+    let mut ordered = Vec::new();
+    let mut visited = HashSet::new();
+
+    for txid in txids {
+      let mut stack = vec![(txid, false)];
+
+      while let Some((txid, expanded)) = stack.pop() {
+        if expanded {
+          ordered.push(txid);
+          continue;
+        }
+
+        if !visited.insert(txid) {
+          continue;
+        }
+
+        stack.push((txid, true));
+
+        for txin in &transactions[&txid].input {
+          let parent = txin.previous_output.txid;
+
+          if transactions.contains_key(&parent) && !visited.contains(&parent) {
+            stack.push((parent, false));
+          }
+        }
+      }
+    }
+
+    ordered
+  }
+
+  fn piles(index: &Index, runes: &HashMap<RuneId, Lot>) -> Result<BTreeMap<SpacedRune, Pile>> {
+    let rune_id_to_rune_entry = index
+      .database
+      .begin_read()?
+      .open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+
+    let mut piles = BTreeMap::new();
+
+    for (id, balance) in runes {
+      let Some(entry) = rune_id_to_rune_entry.get(&id.store())? else {
+        continue;
+      };
+
+      let entry = RuneEntry::load(entry.value());
+
+      piles.insert(
+        entry.spaced_rune,
+        Pile {
+          amount: balance.n(),
+          divisibility: entry.divisibility,
+          symbol: entry.symbol,
+        },
+      );
+    }
+
+    Ok(piles)
+  }
+}
+
+impl Context<'_> {
+  /// Returns the amount minted by a mint of `id` in the next block, taking
+  /// earlier mints in the mempool into account.
+  fn mint(&mut self, id: RuneId) -> Result<Option<Lot>> {
+    let Some(entry) = self.rune_id_to_rune_entry.get(&id.store())? else {
+      return Ok(None);
+    };
+
+    let mut entry = RuneEntry::load(entry.value());
+
+    let mints = self.mints.entry(id).or_default();
+
+    entry.mints += *mints;
+
+    let Ok(amount) = entry.mintable(self.height.into()) else {
+      return Ok(None);
+    };
+
+    *mints += 1;
+
+    Ok(Some(Lot(amount)))
+  }
+
+  /// Resolves a confirmed output from the index, falling back to Bitcoin Core
+  /// for its value if the index has no UTXO entry for it.
+  fn resolve(&self, outpoint: OutPoint) -> Result<Option<UnconfirmedOutput>> {
+    let mut inscriptions = Vec::new();
+
+    let value = match self.outpoint_to_utxo_entry.get(&outpoint.store())? {
+      Some(utxo_entry) => {
+        let utxo_entry = utxo_entry.value().parse(self.index);
+
+        if self.index.index_inscriptions {
+          for (sequence_number, offset) in utxo_entry.parse_inscriptions() {
+            let entry = InscriptionEntry::load(
+              self
+                .sequence_number_to_inscription_entry
+                .get(sequence_number)?
+                .unwrap()
+                .value(),
+            );
+
+            inscriptions.push((entry.id, offset));
+          }
+        }
+
+        utxo_entry.total_value()
+      }
+      None => {
+        let Some(tx_out) = self
+          .index
+          .get_transaction(outpoint.txid)?
+          .and_then(|tx| tx.output.into_iter().nth(outpoint.vout.into_usize()))
+        else {
+          return Ok(None);
+        };
+
+        tx_out.value.to_sat()
+      }
+    };
+
+    let mut runes = HashMap::new();
+
+    if let Some(balances) = self.outpoint_to_rune_balances.get(&outpoint.store())? {
+      let buffer = balances.value();
+      let mut i = 0;
+      while i < buffer.len() {
+        let ((id, balance), len) = Index::decode_rune_balance(&buffer[i..]).unwrap();
+        i += len;
+        runes.insert(id, Lot(balance));
+      }
+    }
+
+    Ok(Some(UnconfirmedOutput {
+      inscriptions,
+      runes,
+      script_pubkey: ScriptBuf::new(),
+      value,
+    }))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn tx(lock_time: u32, parents: &[Txid]) -> Transaction {
+    Transaction {
+      version: Version(2),
+      lock_time: LockTime::from_consensus(lock_time),
+      input: parents
+        .iter()
+        .map(|txid| TxIn {
+          previous_output: OutPoint {
+            txid: *txid,
+            vout: 0,
+          },
+          ..default()
+        })
+        .collect(),
+      output: Vec::new(),
+    }
+  }
+
+  #[test]
+  fn parents_come_before_children() {
+    let a = tx(0, &[]);
+    let b = tx(1, &[a.compute_txid()]);
+    let c = tx(2, &[b.compute_txid(), a.compute_txid(), Txid::all_zeros()]);
+
+    let transactions = [a, b, c]
+      .into_iter()
+      .map(|tx| (tx.compute_txid(), tx))
+      .collect::<HashMap<Txid, Transaction>>();
+
+    let ordered = Mempool::parents_first(&transactions);
+
+    assert_eq!(ordered.len(), 3);
+
+    for (i, txid) in ordered.iter().enumerate() {
+      for txin in &transactions[txid].input {
+        if let Some(parent) = ordered
+          .iter()
+          .position(|txid| *txid == txin.previous_output.txid)
+        {
+          assert!(parent < i);
+        }
+      }
+    }
+  }
+}
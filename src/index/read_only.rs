@@ -0,0 +1,344 @@
+use {
+  super::*,
+  redb::StorageBackend,
+  sysinfo::{Pid, ProcessesToUpdate},
+};
+
+#[cfg(unix)]
+use std::os::unix::fs::FileExt;
+
+#[cfg(windows)]
+use std::os::windows::fs::FileExt;
+
+// offset and flag of redb's header byte recording whether the database was
+// closed cleanly
+const GOD_BYTE_OFFSET: u64 = 9;
+const RECOVERY_REQUIRED: u8 = 2;
+
+// oldest generation of index snapshot held open by this process, for each
+// readers directory
+static SNAPSHOTS: Mutex<BTreeMap<PathBuf, BTreeMap<u64, usize>>> = Mutex::new(BTreeMap::new());
+
+/// Coordinates `ord server --read-only` processes with the
+/// `ord index update --daemon` process writing the index they serve, using a
+/// directory next to the index.
+///
+/// redb reuses pages that no transaction of the writing process can see, so
+/// before it opens the index, the writer waits until every reader has reopened
+/// the index at the latest generation, meaning no reader can see pages freed
+/// by earlier sessions. The writer holds a read transaction on the state it
+/// opened while updating, and so does not reuse pages that readers can see.
+///
+/// The directory contains a `generation` file with the number of update
+/// sessions the writer has completed, and a file for each reader process,
+/// named by its PID, with the oldest generation of the snapshots it holds.
+pub(crate) struct Readers {
+  dir: PathBuf,
+}
+
+impl Readers {
+  pub(crate) fn new(index: &Path) -> Self {
+    let mut dir = index.as_os_str().to_owned();
+    dir.push(".readers");
+
+    Self { dir: dir.into() }
+  }
+
+  pub(crate) fn generation(&self) -> Result<u64> {
+    match fs::read_to_string(self.dir.join("generation")) {
+      Ok(generation) => Ok(generation.trim().parse()?),
+      Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(0),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  pub(crate) fn set_generation(&self, generation: u64) -> Result {
+    Self::write(&self.dir, "generation", generation)?;
+    Ok(())
+  }
+
+  /// Waits until every running reader holds only snapshots at or after
+  /// `generation`, returning `false` if ord is shutting down. Readers still
+  /// lagging after `timeout` are evicted by removing their registrations, so
+  /// requests they are serving from older snapshots may fail or return
+  /// inconsistent data once the index is updated.
+  pub(crate) fn wait(&self, generation: u64, timeout: Duration) -> Result<bool> {
+    let start = Instant::now();
+
+    let mut logged = Vec::new();
+
+    loop {
+      let lagging = self.lagging(generation)?;
+
+      if lagging.is_empty() {
+        return Ok(true);
+      }
+
+      if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+        return Ok(false);
+      }
+
+      let pids = lagging
+        .iter()
+        .map(u32::to_string)
+        .collect::<Vec<String>>()
+        .join(", ");
+
+      if start.elapsed() >= timeout {
+        log::warn!(
+          "evicting read-only servers that have not reopened index after {}: {pids}",
+          humantime::format_duration(timeout),
+        );
+
+        for pid in lagging {
+          match fs::remove_file(self.dir.join(pid.to_string())) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => return Err(err.into()),
+            _ => {}
+          }
+        }
+
+        return Ok(true);
+      }
+
+      if lagging != logged {
+        log::info!("waiting for read-only servers to reopen index: {pids}");
+        logged = lagging;
+      }
+
+      thread::sleep(Duration::from_millis(100));
+    }
+  }
+
+  /// PIDs of running readers holding snapshots older than `generation`.
+  pub(super) fn lagging(&self, generation: u64) -> Result<Vec<u32>> {
+    let entries = match fs::read_dir(&self.dir) {
+      Ok(entries) => entries,
+      Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+      Err(err) => return Err(err.into()),
+    };
+
+    let mut system = System::new();
+
+    let mut lagging = Vec::new();
+
+    for entry in entries {
+      let entry = entry?;
+
+      let Some(pid) = entry
+        .file_name()
+        .to_str()
+        .and_then(|name| name.parse::<u32>().ok())
+      else {
+        continue;
+      };
+
+      system.refresh_processes(ProcessesToUpdate::Some(&[Pid::from_u32(pid)]), true);
+
+      // readers that exit without closing their snapshots leave a stale file
+      if system.process(Pid::from_u32(pid)).is_none() {
+        fs::remove_file(entry.path()).ok();
+        continue;
+      }
+
+      // the file is removed when the reader closes its last snapshot
+      let Ok(oldest) = fs::read_to_string(entry.path()) else {
+        continue;
+      };
+
+      if oldest
+        .trim()
+        .parse::<u64>()
+        .map_or(true, |oldest| oldest < generation)
+      {
+        lagging.push(pid);
+      }
+    }
+
+    lagging.sort();
+
+    Ok(lagging)
+  }
+
+  fn write(dir: &Path, name: &str, value: u64) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+
+    let tmp = dir.join(format!("{name}.tmp"));
+
+    fs::write(&tmp, value.to_string())?;
+
+    fs::rename(tmp, dir.join(name))
+  }
+
+  fn register(dir: &Path, generations: &BTreeMap<u64, usize>) -> io::Result<()> {
+    let name = process::id().to_string();
+
+    match generations.keys().next() {
+      Some(oldest) => Self::write(dir, &name, *oldest),
+      None => match fs::remove_file(dir.join(name)) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(()),
+        result => result,
+      },
+    }
+  }
+}
+
+/// Registration of a snapshot held open by a read-only index, which must be
+/// created before the index is opened, so that the writer never misses it.
+pub(super) struct Snapshot {
+  dir: PathBuf,
+  generation: u64,
+}
+
+impl Snapshot {
+  pub(super) fn register(index: &Path) -> Result<Self> {
+    let readers = Readers::new(index);
+
+    let generation = readers.generation()?;
+
+    let registered = {
+      let mut snapshots = SNAPSHOTS.lock().unwrap();
+
+      let generations = snapshots.entry(readers.dir.clone()).or_default();
+
+      *generations.entry(generation).or_default() += 1;
+
+      Readers::register(&readers.dir, generations)
+    };
+
+    let snapshot = Self {
+      dir: readers.dir,
+      generation,
+    };
+
+    registered?;
+
+    Ok(snapshot)
+  }
+}
+
+impl Drop for Snapshot {
+  fn drop(&mut self) {
+    let mut snapshots = SNAPSHOTS.lock().unwrap();
+
+    let Some(generations) = snapshots.get_mut(&self.dir) else {
+      return;
+    };
+
+    if let Some(count) = generations.get_mut(&self.generation) {
+      *count -= 1;
+
+      if *count == 0 {
+        generations.remove(&self.generation);
+      }
+    }
+
+    if let Err(err) = Readers::register(&self.dir, generations) {
+      log::warn!("Updating read-only index registration: {err}");
+    }
+  }
+}
+
+/// Storage backend used to open an index that another process may be
+/// updating. The file is neither locked nor modified: writes that redb makes
+/// while opening the database are kept in memory. Opening fails while the
+/// writer has the database open, since redb would otherwise attempt to
+/// repair it.
+#[derive(Debug)]
+pub(super) struct ReadOnlyBackend {
+  file: File,
+  len: Mutex<u64>,
+  writes: Mutex<Vec<(u64, Vec<u8>)>>,
+}
+
+impl ReadOnlyBackend {
+  pub(super) fn open(path: &Path) -> io::Result<Self> {
+    let file = File::open(path)?;
+
+    Ok(Self {
+      len: Mutex::new(file.metadata()?.len()),
+      file,
+      writes: Mutex::new(Vec::new()),
+    })
+  }
+
+  #[cfg(unix)]
+  fn read_file(&self, buffer: &mut [u8], offset: u64) -> io::Result<()> {
+    self.file.read_exact_at(buffer, offset)
+  }
+
+  #[cfg(windows)]
+  fn read_file(&self, mut buffer: &mut [u8], mut offset: u64) -> io::Result<()> {
+    while !buffer.is_empty() {
+      match self.file.seek_read(buffer, offset)? {
+        0 => return Err(io::ErrorKind::UnexpectedEof.into()),
+        n => {
+          buffer = &mut buffer[n..];
+          offset += u64::try_from(n).unwrap();
+        }
+      }
+    }
+
+    Ok(())
+  }
+}
+
+impl StorageBackend for ReadOnlyBackend {
+  fn len(&self) -> io::Result<u64> {
+    Ok(*self.len.lock().unwrap())
+  }
+
+  fn read(&self, offset: u64, len: usize) -> io::Result<Vec<u8>> {
+    let mut buffer = vec![0; len];
+
+    let end = offset + u64::try_from(len).unwrap();
+
+    let available = end.min(self.file.metadata()?.len()).saturating_sub(offset);
+
+    self.read_file(&mut buffer[..usize::try_from(available).unwrap()], offset)?;
+
+    let writes = self.writes.lock().unwrap();
+
+    if (offset..end).contains(&GOD_BYTE_OFFSET)
+      && !writes.iter().any(|(start, data)| {
+        (*start..start + u64::try_from(data.len()).unwrap()).contains(&GOD_BYTE_OFFSET)
+      })
+      && buffer[usize::try_from(GOD_BYTE_OFFSET - offset).unwrap()] & RECOVERY_REQUIRED != 0
+    {
+      return Err(io::Error::other(
+        "index is open for writing by another process",
+      ));
+    }
+
+    for (start, data) in writes.iter() {
+      let data_end = start + u64::try_from(data.len()).unwrap();
+
+      if *start >= end || data_end <= offset {
+        continue;
+      }
+
+      let from = start.max(&offset);
+      let to = data_end.min(end);
+
+      buffer[usize::try_from(from - offset).unwrap()..usize::try_from(to - offset).unwrap()]
+        .copy_from_slice(
+          &data[usize::try_from(from - start).unwrap()..usize::try_from(to - start).unwrap()],
+        );
+    }
+
+    Ok(buffer)
+  }
+
+  fn set_len(&self, len: u64) -> io::Result<()> {
+    *self.len.lock().unwrap() = len;
+    Ok(())
+  }
+
+  fn sync_data(&self, _eventual: bool) -> io::Result<()> {
+    Ok(())
+  }
+
+  fn write(&self, offset: u64, data: &[u8]) -> io::Result<()> {
+    self.writes.lock().unwrap().push((offset, data.to_vec()));
+    Ok(())
+  }
+}
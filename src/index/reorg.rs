@@ -3,7 +3,7 @@ use {super::*, updater::BlockData};
 #[derive(Debug, PartialEq)]
 pub(crate) enum Error {
   Recoverable { height: u32, depth: u32 },
-  Unrecoverable,
+  Unrecoverable { height: u32, depth: u32 },
 }
 
 impl Display for Error {
@@ -12,17 +12,13 @@ impl Display for Error {
       Self::Recoverable { height, depth } => {
         write!(f, "{depth} block deep reorg detected at height {height}")
       }
-      Self::Unrecoverable => write!(f, "unrecoverable reorg detected"),
+      Self::Unrecoverable { .. } => write!(f, "unrecoverable reorg detected"),
     }
   }
 }
 
 impl std::error::Error for Error {}
 
-const MAX_SAVEPOINTS: u32 = 2;
-const SAVEPOINT_INTERVAL: u32 = 10;
-const CHAIN_TIP_DISTANCE: u32 = 21;
-
 pub(crate) struct Reorg {}
 
 impl Reorg {
@@ -32,27 +28,41 @@ impl Reorg {
     match index.block_hash(height.checked_sub(1))? {
       Some(index_prev_blockhash) if index_prev_blockhash == bitcoind_prev_blockhash => Ok(()),
       Some(index_prev_blockhash) if index_prev_blockhash != bitcoind_prev_blockhash => {
+        let savepoint_interval = index.settings.savepoint_interval();
+
         let max_recoverable_reorg_depth =
-          (MAX_SAVEPOINTS - 1) * SAVEPOINT_INTERVAL + height % SAVEPOINT_INTERVAL;
+          (index.settings.max_savepoints() - 1) * savepoint_interval + height % savepoint_interval;
 
         for depth in 1..max_recoverable_reorg_depth {
           let index_block_hash = index.block_hash(height.checked_sub(depth))?;
           let bitcoind_block_hash = index
-            .client
-            .get_block_hash(u64::from(height.saturating_sub(depth)))
-            .into_option()?;
+            .block_source
+            .block_hash(height.saturating_sub(depth))?;
 
           if index_block_hash == bitcoind_block_hash {
             return Err(anyhow!(reorg::Error::Recoverable { height, depth }));
           }
         }
 
-        Err(anyhow!(reorg::Error::Unrecoverable))
+        Err(anyhow!(reorg::Error::Unrecoverable {
+          height,
+          depth: max_recoverable_reorg_depth,
+        }))
       }
       _ => Ok(()),
     }
   }
 
+  pub(crate) fn has_savepoints(index: &Index) -> Result<bool> {
+    Ok(
+      index
+        .begin_write()?
+        .list_persistent_savepoints()?
+        .next()
+        .is_some(),
+    )
+  }
+
   pub(crate) fn handle_reorg(index: &Index, height: u32, depth: u32) -> Result {
     log::info!("rolling back database after reorg of depth {depth} at height {height}");
 
@@ -60,20 +70,232 @@ impl Reorg {
       panic!("set index durability to `Durability::Immediate` to test reorg handling");
     }
 
+    let rtx = index.begin_read()?;
+
     let mut wtx = index.begin_write()?;
 
-    let oldest_savepoint =
-      wtx.get_persistent_savepoint(wtx.list_persistent_savepoints()?.min().unwrap())?;
+    let oldest_savepoint = wtx.list_persistent_savepoints()?.min().unwrap();
+
+    Self::restore_savepoint(&rtx, &mut wtx, oldest_savepoint)?;
+
+    let reorgs = rtx
+      .0
+      .open_table(STATISTIC_TO_COUNT)?
+      .get(&Statistic::Reorgs.key())?
+      .map(|x| x.value())
+      .unwrap_or_default();
+
+    wtx
+      .open_table(STATISTIC_TO_COUNT)?
+      .insert(&Statistic::Reorgs.key(), &(reorgs + 1))?;
+
+    let block_count = wtx
+      .open_table(HEIGHT_TO_BLOCK_HEADER)?
+      .range(0..)?
+      .next_back()
+      .transpose()?
+      .map(|(height, _header)| height.value() + 1)
+      .unwrap_or(0);
+
+    let mut old_hashes = Vec::new();
 
-    wtx.restore_savepoint(&oldest_savepoint)?;
+    for height in block_count..rtx.block_count()? {
+      old_hashes.extend(rtx.block_hash(Some(height))?);
+    }
+
+    let mut events = vec![Event::Reorg {
+      depth,
+      height,
+      old_hashes,
+    }];
+
+    // undo events need the event log to know which events were rolled back
+    if index.index_events {
+      Self::log_rollback(&rtx, &wtx, block_count, &mut events)?;
+    }
 
     Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
     wtx.commit()?;
 
-    log::info!(
-      "successfully rolled back database to height {}",
-      index.begin_read()?.block_count()?
-    );
+    log::info!("successfully rolled back database to height {block_count}");
+
+    if let Some(sender) = &index.event_sender {
+      for event in events {
+        sender.blocking_send(event)?;
+      }
+    }
+
+    Ok(())
+  }
+
+  /// Roll the index back so that `height` is the last indexed block, returning
+  /// the number of blocks rolled back.
+  pub(crate) fn rollback(index: &Index, height: u32) -> Result<u32> {
+    let rtx = index.begin_read()?;
+
+    let block_count = rtx.block_count()?;
+
+    let target = height
+      .checked_add(1)
+      .filter(|target| *target < block_count)
+      .ok_or_else(|| {
+        anyhow!(
+          "cannot roll back to height {height}: index has {block_count} blocks, so it is already at or below that height",
+        )
+      })?;
+
+    let mut wtx = index.begin_write()?;
+
+    let live = wtx.list_persistent_savepoints()?.collect::<HashSet<u64>>();
+
+    let mut savepoints = Vec::new();
+
+    for result in rtx.0.open_table(SAVEPOINT_TO_HEIGHT)?.iter()? {
+      let (id, savepoint_height) = result?;
+      if live.contains(&id.value()) {
+        savepoints.push((id.value(), savepoint_height.value()));
+      }
+    }
+
+    if let Some((savepoint, _)) = savepoints
+      .iter()
+      .find(|(_, savepoint_height)| *savepoint_height == target)
+    {
+      log::info!("restoring savepoint at height {target}");
+      Self::restore_savepoint(&rtx, &mut wtx, *savepoint)?;
+    } else if index.index_undo_log {
+      // savepoints above the target are made stale by the rollback
+      for (savepoint, savepoint_height) in savepoints {
+        if savepoint_height > target {
+          wtx.delete_persistent_savepoint(savepoint)?;
+          wtx.open_table(SAVEPOINT_TO_HEIGHT)?.remove(savepoint)?;
+        }
+      }
+
+      for height in (target..block_count).rev() {
+        log::info!("undoing block {height}");
+
+        let undo_log = wtx
+          .open_table(HEIGHT_TO_UNDO_LOG)?
+          .remove(height)?
+          .ok_or_else(|| {
+            anyhow!("undo log for block {height} is missing, it may have been pruned by `--undo-log-depth`")
+          })?
+          .value()
+          .to_vec();
+
+        UndoLog::undo(&wtx, &undo_log)?;
+      }
+
+      wtx
+        .open_table(HEIGHT_TO_BLOCK_HEADER)?
+        .retain_in(target.., |_, _| false)?;
+
+      wtx
+        .open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?
+        .retain_in(target.., |_, _| false)?;
+
+      wtx
+        .open_table(WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?
+        .retain_in(target.., |_, _| false)?;
+
+      let first_rolled_back_event_id = Self::first_event_id(&rtx, target)?;
+
+      wtx
+        .open_table(EVENT_ID_TO_EVENT)?
+        .retain_in(first_rolled_back_event_id.., |_, _| false)?;
+
+      wtx
+        .open_table(HEIGHT_TO_LAST_EVENT_ID)?
+        .retain_in(target.., |_, _| false)?;
+    } else {
+      bail!("no savepoint at height {height}, and index was not built with `--index-undo-log`");
+    }
+
+    if index.index_events {
+      Self::log_rollback(&rtx, &wtx, target, &mut Vec::new())?;
+    }
+
+    Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
+    wtx.commit()?;
+
+    log::info!("rolled back index to height {height}");
+
+    Ok(block_count - target)
+  }
+
+  /// Id of the first event logged after `block_count` blocks were indexed.
+  fn first_event_id(rtx: &rtx::Rtx, block_count: u32) -> Result<u64> {
+    let Some(height) = block_count.checked_sub(1) else {
+      return Ok(0);
+    };
+
+    Ok(
+      rtx
+        .0
+        .open_table(HEIGHT_TO_LAST_EVENT_ID)?
+        .get(height)?
+        .map(|id| id.value())
+        .unwrap_or_default(),
+    )
+  }
+
+  /// Appends an undo event for each event logged after `block_count` blocks
+  /// were indexed, newest first, to `events`, and logs `events` after every
+  /// event logged before the rollback, so that consumers whose cursor is past
+  /// the rollback point still see them.
+  fn log_rollback(
+    rtx: &rtx::Rtx,
+    wtx: &WriteTransaction,
+    block_count: u32,
+    events: &mut Vec<Event>,
+  ) -> Result {
+    let event_id_to_event = rtx.0.open_table(EVENT_ID_TO_EVENT)?;
+
+    for result in event_id_to_event
+      .range(Self::first_event_id(rtx, block_count)?..)?
+      .rev()
+    {
+      let (_id, event) = result?;
+
+      match serde_json::from_slice(event.value())? {
+        // events logged by earlier rollbacks were not indexed from a block
+        Event::Reorg { .. } | Event::Undo(_) => {}
+        event => events.push(Event::Undo(Box::new(event))),
+      }
+    }
+
+    let mut log = wtx.open_table(EVENT_ID_TO_EVENT)?;
+
+    for (id, event) in (Index::next_event_id(&event_id_to_event)?..).zip(events.iter()) {
+      log.insert(id, serde_json::to_vec(event)?.as_slice())?;
+    }
+
+    Ok(())
+  }
+
+  /// Restore a savepoint, keeping its entry in `SAVEPOINT_TO_HEIGHT`, which
+  /// was written after the savepoint was taken, and dropping entries for
+  /// savepoints deleted since.
+  fn restore_savepoint(rtx: &rtx::Rtx, wtx: &mut WriteTransaction, id: u64) -> Result {
+    let height = rtx
+      .0
+      .open_table(SAVEPOINT_TO_HEIGHT)?
+      .get(id)?
+      .map(|height| height.value());
+
+    let savepoint = wtx.get_persistent_savepoint(id)?;
+    wtx.restore_savepoint(&savepoint)?;
+
+    let live = wtx.list_persistent_savepoints()?.collect::<HashSet<u64>>();
+
+    let mut savepoint_to_height = wtx.open_table(SAVEPOINT_TO_HEIGHT)?;
+
+    savepoint_to_height.retain(|id, _| live.contains(&id))?;
+
+    if let Some(height) = height {
+      savepoint_to_height.insert(id, height)?;
+    }
 
     Ok(())
   }
@@ -83,24 +305,22 @@ impl Reorg {
       return Ok(());
     }
 
-    if (height < SAVEPOINT_INTERVAL || height % SAVEPOINT_INTERVAL == 0)
-      && u32::try_from(
-        index
-          .settings
-          .bitcoin_rpc_client(None)?
-          .get_blockchain_info()?
-          .headers,
-      )
-      .unwrap()
-      .saturating_sub(height)
-        <= CHAIN_TIP_DISTANCE
+    let savepoint_interval = index.settings.savepoint_interval();
+
+    if (height < savepoint_interval || height % savepoint_interval == 0)
+      && index.block_source.header_count()?.saturating_sub(height)
+        <= index.settings.chain_tip_distance()
     {
       let wtx = index.begin_write()?;
 
       let savepoints = wtx.list_persistent_savepoints()?.collect::<Vec<u64>>();
 
-      if savepoints.len() >= usize::try_from(MAX_SAVEPOINTS).unwrap() {
-        wtx.delete_persistent_savepoint(savepoints.into_iter().min().unwrap())?;
+      if savepoints.len() >= usize::try_from(index.settings.max_savepoints()).unwrap() {
+        let oldest_savepoint = savepoints.into_iter().min().unwrap();
+        wtx.delete_persistent_savepoint(oldest_savepoint)?;
+        wtx
+          .open_table(SAVEPOINT_TO_HEIGHT)?
+          .remove(oldest_savepoint)?;
       }
 
       Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
@@ -109,7 +329,11 @@ impl Reorg {
       let wtx = index.begin_write()?;
 
       log::debug!("creating savepoint at height {}", height);
-      wtx.persistent_savepoint()?;
+      let savepoint = wtx.persistent_savepoint()?;
+
+      wtx
+        .open_table(SAVEPOINT_TO_HEIGHT)?
+        .insert(savepoint, height)?;
 
       Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
       wtx.commit()?;
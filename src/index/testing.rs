@@ -2,7 +2,9 @@ use {super::*, bitcoin::script::PushBytes, std::ffi::OsString, tempfile::TempDir
 
 pub(crate) struct ContextBuilder {
   args: Vec<OsString>,
+  block_files: bool,
   chain: Chain,
+  esplora: bool,
   event_sender: Option<tokio::sync::mpsc::Sender<Event>>,
   tempdir: Option<TempDir>,
 }
@@ -13,13 +15,17 @@ impl ContextBuilder {
   }
 
   pub(crate) fn try_build(self) -> Result<Context> {
-    let core = mockcore::builder().network(self.chain.network()).build();
+    let core = mockcore::builder()
+      .network(self.chain.network())
+      .block_files(self.block_files)
+      .esplora(self.esplora)
+      .build();
 
     let tempdir = self.tempdir.unwrap_or_else(|| TempDir::new().unwrap());
     let cookie_file = tempdir.path().join("cookie");
     fs::write(&cookie_file, "username:password").unwrap();
 
-    let command: Vec<OsString> = vec![
+    let mut command: Vec<OsString> = vec![
       "ord".into(),
       "--bitcoin-rpc-url".into(),
       core.url().into(),
@@ -30,6 +36,17 @@ impl ContextBuilder {
       format!("--chain={}", self.chain).into(),
     ];
 
+    if self.block_files {
+      command.push("--bitcoin-data-dir".into());
+      command.push(core.bitcoin_data_dir().into());
+      command.push("--block-files".into());
+    }
+
+    if self.esplora {
+      command.push("--esplora-url".into());
+      command.push(core.esplora_url().into());
+    }
+
     let options = Options::try_parse_from(command.into_iter().chain(self.args)).unwrap();
 
     let index = Index::open_with_event_sender(
@@ -56,11 +73,21 @@ impl ContextBuilder {
     self
   }
 
+  pub(crate) fn block_files(mut self) -> Self {
+    self.block_files = true;
+    self
+  }
+
   pub(crate) fn chain(mut self, chain: Chain) -> Self {
     self.chain = chain;
     self
   }
 
+  pub(crate) fn esplora(mut self) -> Self {
+    self.esplora = true;
+    self
+  }
+
   pub(crate) fn tempdir(mut self, tempdir: TempDir) -> Self {
     self.tempdir = Some(tempdir);
     self
@@ -83,7 +110,9 @@ impl Context {
   pub(crate) fn builder() -> ContextBuilder {
     ContextBuilder {
       args: Vec::new(),
+      block_files: false,
       chain: Chain::Regtest,
+      esplora: false,
       event_sender: None,
       tempdir: None,
     }
@@ -0,0 +1,356 @@
+use {
+  super::*,
+  redb::{AccessGuard, Key, Value},
+  std::borrow::Borrow,
+};
+
+macro_rules! undoable_tables {
+  (
+    tables: [$($id:literal => $table:ident),* $(,)?],
+    multimap_tables: [$($multimap_id:literal => $multimap_table:ident),* $(,)?] $(,)?
+  ) => {
+    const TABLES: &[(u8, &str)] = &[
+      $(($id, stringify!($table)),)*
+      $(($multimap_id, stringify!($multimap_table)),)*
+    ];
+
+    const MULTIMAP_TABLE_IDS: &[u8] = &[$($multimap_id),*];
+
+    const _: () = {
+      let mut i = 0;
+      while i < TABLES.len() {
+        let mut j = i + 1;
+        while j < TABLES.len() {
+          assert!(TABLES[i].0 != TABLES[j].0, "undoable table ids must be unique");
+          j += 1;
+        }
+        i += 1;
+      }
+    };
+
+    fn restore(wtx: &WriteTransaction, entry: &UndoEntry) -> Result {
+      match entry.table {
+        $($id => restore_table(wtx, $table, entry),)*
+        $($multimap_id => restore_multimap_table(wtx, $multimap_table, entry),)*
+        id => Err(anyhow!("undo log entry for unknown table {id}")),
+      }
+    }
+  };
+}
+
+// Table ids are stored in undo logs, so they must never change or be reused.
+// New tables get the next unused id. Tables that only grow, like
+// `HEIGHT_TO_BLOCK_HEADER` and `EVENT_ID_TO_EVENT`, are not logged, since
+// rolling back just truncates them.
+undoable_tables! {
+  tables: [
+    0 => CONTENT_TYPE_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID,
+    1 => CONTENT_TYPE_TO_COUNT,
+    2 => HOME_INSCRIPTIONS,
+    3 => INSCRIPTION_ID_TO_SEQUENCE_NUMBER,
+    4 => INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER,
+    5 => MEDIA_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID,
+    6 => MEDIA_TO_COUNT,
+    7 => METAPROTOCOL_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID,
+    8 => METAPROTOCOL_TO_COUNT,
+    9 => OUTPOINT_TO_RUNE_BALANCES,
+    10 => OUTPOINT_TO_UTXO_ENTRY,
+    11 => RUNE_ACTIVITY_ID_TO_RUNE_ACTIVITY_ENTRY,
+    12 => RUNE_ID_AND_SCRIPT_PUBKEY_TO_BALANCE,
+    13 => RUNE_ID_TO_RUNE_ENTRY,
+    14 => RUNE_TO_RUNE_ID,
+    15 => SAT_TO_SATPOINT,
+    16 => SCRIPT_PUBKEY_AND_RUNE_ID_TO_BALANCE,
+    17 => SCRIPT_PUBKEY_AND_TX_POSITION_TO_HISTORY_ENTRY,
+    18 => SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY,
+    19 => SEQUENCE_NUMBER_TO_RUNE_ID,
+    20 => SEQUENCE_NUMBER_TO_SATPOINT,
+    21 => STATISTIC_TO_COUNT,
+    22 => TRANSACTION_ID_TO_RUNE,
+    23 => TRANSACTION_ID_TO_TRANSACTION,
+    24 => TRANSFER_ID_TO_TRANSFER_ENTRY,
+  ],
+  multimap_tables: [
+    25 => CONTENT_HASH_TO_SEQUENCE_NUMBER,
+    26 => DELEGATE_TO_SEQUENCE_NUMBER,
+    27 => RUNE_ID_TO_OUTPOINT_BALANCE,
+    28 => RUNE_ID_TO_SCRIPT_PUBKEY_BALANCE,
+    29 => SAT_TO_SEQUENCE_NUMBER,
+    30 => SCRIPT_PUBKEY_TO_OUTPOINT,
+    31 => SEQUENCE_NUMBER_TO_CHILDREN,
+    32 => TERM_TO_SEQUENCE_NUMBER,
+  ],
+}
+
+const ABSENT: u8 = 0;
+const PRESENT: u8 = 1;
+
+/// The state of a single key, or key-value pair for multimap tables, before
+/// it was written by the block being undone.
+#[derive(Debug, PartialEq)]
+struct UndoEntry {
+  table: u8,
+  key: Vec<u8>,
+  value: Option<Vec<u8>>,
+  present: bool,
+}
+
+/// Records the previous state of every key written while indexing a block, so
+/// that the block can later be rolled back. Writes go through the undo log
+/// whether or not it is enabled, and are only recorded if it is.
+#[derive(Default)]
+pub(super) struct UndoLog {
+  buffer: Option<Vec<u8>>,
+}
+
+impl UndoLog {
+  pub(super) fn new(enabled: bool) -> Self {
+    Self {
+      buffer: enabled.then(Vec::new),
+    }
+  }
+
+  pub(super) fn into_bytes(self) -> Option<Vec<u8>> {
+    self.buffer
+  }
+
+  pub(super) fn insert<'k, 'v, K: Key + 'static, V: Value + 'static>(
+    &mut self,
+    table: &mut Table<K, V>,
+    key: impl Borrow<K::SelfType<'k>>,
+    value: impl Borrow<V::SelfType<'v>>,
+  ) -> Result {
+    let Some(id) = self.table_id(table.name()) else {
+      table.insert(key, value)?;
+      return Ok(());
+    };
+
+    let old = table.insert(key.borrow(), value)?;
+
+    self.record(
+      id,
+      K::as_bytes(key.borrow()).as_ref(),
+      old
+        .as_ref()
+        .map(|old| V::as_bytes(&old.value()).as_ref().to_vec()),
+    );
+
+    Ok(())
+  }
+
+  pub(super) fn remove<'t, 'k, K: Key + 'static, V: Value + 'static>(
+    &mut self,
+    table: &'t mut Table<K, V>,
+    key: impl Borrow<K::SelfType<'k>>,
+  ) -> Result<Option<AccessGuard<'t, V>>> {
+    let Some(id) = self.table_id(table.name()) else {
+      return Ok(table.remove(key)?);
+    };
+
+    let old = table.remove(key.borrow())?;
+
+    if let Some(old) = &old {
+      self.record(
+        id,
+        K::as_bytes(key.borrow()).as_ref(),
+        Some(V::as_bytes(&old.value()).as_ref().to_vec()),
+      );
+    }
+
+    Ok(old)
+  }
+
+  pub(super) fn pop_first<K: Key + 'static, V: Value + 'static>(
+    &mut self,
+    table: &mut Table<K, V>,
+  ) -> Result {
+    let id = self.table_id(table.name());
+
+    if let (Some(id), Some((key, value))) = (id, table.pop_first()?) {
+      self.record(
+        id,
+        K::as_bytes(&key.value()).as_ref(),
+        Some(V::as_bytes(&value.value()).as_ref().to_vec()),
+      );
+    }
+
+    Ok(())
+  }
+
+  pub(super) fn insert_multimap<'k, 'v, K: Key + 'static, V: Key + 'static>(
+    &mut self,
+    table: &mut MultimapTable<K, V>,
+    key: impl Borrow<K::SelfType<'k>>,
+    value: impl Borrow<V::SelfType<'v>>,
+  ) -> Result<bool> {
+    let id = self.table_id(table.name());
+
+    let present = table.insert(key.borrow(), value.borrow())?;
+
+    if let (Some(id), false) = (id, present) {
+      self.record_multimap(
+        id,
+        K::as_bytes(key.borrow()).as_ref(),
+        V::as_bytes(value.borrow()).as_ref(),
+        false,
+      );
+    }
+
+    Ok(present)
+  }
+
+  pub(super) fn remove_multimap<'k, 'v, K: Key + 'static, V: Key + 'static>(
+    &mut self,
+    table: &mut MultimapTable<K, V>,
+    key: impl Borrow<K::SelfType<'k>>,
+    value: impl Borrow<V::SelfType<'v>>,
+  ) -> Result<bool> {
+    let id = self.table_id(table.name());
+
+    let present = table.remove(key.borrow(), value.borrow())?;
+
+    if let (Some(id), true) = (id, present) {
+      self.record_multimap(
+        id,
+        K::as_bytes(key.borrow()).as_ref(),
+        V::as_bytes(value.borrow()).as_ref(),
+        true,
+      );
+    }
+
+    Ok(present)
+  }
+
+  /// Revert the writes recorded in `undo_log`, most recent first.
+  pub(super) fn undo(wtx: &WriteTransaction, undo_log: &[u8]) -> Result {
+    for entry in Self::decode(undo_log)?.iter().rev() {
+      restore(wtx, entry)?;
+    }
+
+    Ok(())
+  }
+
+  fn table_id(&self, name: &str) -> Option<u8> {
+    self.buffer.as_ref()?;
+
+    Some(
+      TABLES
+        .iter()
+        .find(|(_id, table)| *table == name)
+        .map(|(id, _table)| *id)
+        .unwrap_or_else(|| panic!("table {name} is not undoable")),
+    )
+  }
+
+  fn record(&mut self, table: u8, key: &[u8], value: Option<Vec<u8>>) {
+    let buffer = self.buffer.as_mut().unwrap();
+
+    buffer.push(table);
+    Self::push_bytes(buffer, key);
+
+    match value {
+      Some(value) => {
+        buffer.push(PRESENT);
+        Self::push_bytes(buffer, &value);
+      }
+      None => buffer.push(ABSENT),
+    }
+  }
+
+  fn record_multimap(&mut self, table: u8, key: &[u8], value: &[u8], present: bool) {
+    let buffer = self.buffer.as_mut().unwrap();
+
+    buffer.push(table);
+    Self::push_bytes(buffer, key);
+    buffer.push(if present { PRESENT } else { ABSENT });
+    Self::push_bytes(buffer, value);
+  }
+
+  fn push_bytes(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    varint::encode_to_vec(bytes.len().try_into().unwrap(), buffer);
+    buffer.extend_from_slice(bytes);
+  }
+
+  fn decode(mut buffer: &[u8]) -> Result<Vec<UndoEntry>> {
+    fn bytes<'a>(buffer: &mut &'a [u8]) -> Result<&'a [u8]> {
+      let (len, varint_len) = varint::decode(buffer)?;
+      let len = usize::try_from(len)?;
+      let bytes = buffer
+        .get(varint_len..varint_len + len)
+        .ok_or_else(|| anyhow!("truncated undo log"))?;
+      *buffer = &buffer[varint_len + len..];
+      Ok(bytes)
+    }
+
+    fn byte(buffer: &mut &[u8]) -> Result<u8> {
+      let (&byte, rest) = buffer
+        .split_first()
+        .ok_or_else(|| anyhow!("truncated undo log"))?;
+      *buffer = rest;
+      Ok(byte)
+    }
+
+    let mut entries = Vec::new();
+
+    while !buffer.is_empty() {
+      let table = byte(&mut buffer)?;
+      let key = bytes(&mut buffer)?.to_vec();
+      let present = byte(&mut buffer)? == PRESENT;
+
+      let value = if present || MULTIMAP_TABLE_IDS.contains(&table) {
+        Some(bytes(&mut buffer)?.to_vec())
+      } else {
+        None
+      };
+
+      entries.push(UndoEntry {
+        table,
+        key,
+        value,
+        present,
+      });
+    }
+
+    Ok(entries)
+  }
+}
+
+fn restore_table<K: Key + 'static, V: Value + 'static>(
+  wtx: &WriteTransaction,
+  definition: TableDefinition<K, V>,
+  entry: &UndoEntry,
+) -> Result {
+  let mut table = wtx.open_table(definition)?;
+
+  let key = K::from_bytes(&entry.key);
+
+  match &entry.value {
+    Some(value) => {
+      table.insert(key, V::from_bytes(value))?;
+    }
+    None => {
+      table.remove(key)?;
+    }
+  }
+
+  Ok(())
+}
+
+fn restore_multimap_table<K: Key + 'static, V: Key + 'static>(
+  wtx: &WriteTransaction,
+  definition: MultimapTableDefinition<K, V>,
+  entry: &UndoEntry,
+) -> Result {
+  let mut table = wtx.open_multimap_table(definition)?;
+
+  let key = K::from_bytes(&entry.key);
+  let value = V::from_bytes(entry.value.as_ref().unwrap());
+
+  if entry.present {
+    table.insert(key, value)?;
+  } else {
+    table.remove(key, value)?;
+  }
+
+  Ok(())
+}
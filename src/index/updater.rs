@@ -1,49 +1,40 @@
 use {
-  self::{inscription_updater::InscriptionUpdater, rune_updater::RuneUpdater},
+  self::{
+    event_emitter::EventEmitter, inscription_updater::InscriptionUpdater, rune_updater::RuneUpdater,
+  },
   super::{fetcher::Fetcher, *},
-  futures::future::try_join_all,
+  futures::{future::try_join_all, FutureExt},
   tokio::sync::{
     broadcast::{self, error::TryRecvError},
     mpsc::{self},
   },
 };
 
+mod event_emitter;
 mod inscription_updater;
+mod pipeline;
 mod rune_updater;
 
 pub(crate) struct BlockData {
+  pub(crate) artifacts: Vec<Option<Artifact>>,
+  pub(crate) envelopes: Vec<Vec<ParsedEnvelope>>,
   pub(crate) header: Header,
   pub(crate) txdata: Vec<(Transaction, Txid)>,
 }
 
-impl From<Block> for BlockData {
-  fn from(block: Block) -> Self {
-    BlockData {
-      header: block.header,
-      txdata: block
-        .txdata
-        .into_iter()
-        .map(|transaction| {
-          let txid = transaction.compute_txid();
-          (transaction, txid)
-        })
-        .collect(),
-    }
-  }
-}
-
 pub(crate) struct Updater<'index> {
   pub(super) height: u32,
   pub(super) index: &'index Index,
   pub(super) outputs_cached: u64,
   pub(super) outputs_traversed: u64,
   pub(super) sat_ranges_since_flush: u64,
+  pub(super) transactions_since_commit: u64,
 }
 
 impl<'index> Updater<'index> {
   pub(crate) fn update_index(&mut self, mut wtx: WriteTransaction) -> Result {
     let start = Instant::now();
-    let starting_height = u32::try_from(self.index.client.get_block_count()?).unwrap() + 1;
+    let starting_height = self.index.block_source.block_count()? + 1;
     let starting_index_height = self.height;
 
     wtx
@@ -73,25 +64,46 @@ impl<'index> Updater<'index> {
 
     let rx = Self::fetch_blocks_from(self.index, self.height)?;
 
+    let mut last_commit = Instant::now();
+
     let (mut output_sender, mut txout_receiver) = Self::spawn_fetcher(self.index)?;
 
     let mut uncommitted = 0;
     let mut utxo_cache = HashMap::new();
-    while let Ok(block) = rx.recv() {
+    while let Some(block) = rx.recv() {
+      self.transactions_since_commit += u64::try_from(block.txdata.len()).unwrap();
+
+      let mut undo_log = UndoLog::new(self.index.index_undo_log);
+
       self.index_block(
         &mut output_sender,
         &mut txout_receiver,
         &mut wtx,
         block,
         &mut utxo_cache,
+        &mut undo_log,
       )?;
 
+      // flush after every block so that each write is logged with the block
+      // that made it
+      if self.index.index_undo_log {
+        self.flush(&wtx, &mut utxo_cache, &mut undo_log)?;
+
+        let mut height_to_undo_log = wtx.open_table(HEIGHT_TO_UNDO_LOG)?;
+
+        height_to_undo_log.insert(self.height - 1, undo_log.into_bytes().unwrap().as_slice())?;
+
+        if let Some(depth) = self.index.settings.undo_log_depth() {
+          height_to_undo_log.retain_in(..self.height.saturating_sub(depth), |_, _| false)?;
+        }
+      }
+
       if let Some(progress_bar) = &mut progress_bar {
         progress_bar.inc(1);
 
         if progress_bar.position() > progress_bar.length().unwrap() {
-          if let Ok(count) = self.index.client.get_block_count() {
-            progress_bar.set_length(count + 1);
+          if let Ok(count) = self.index.block_source.block_count() {
+            progress_bar.set_length((count + 1).into());
           } else {
             log::warn!("Failed to fetch latest block height");
           }
@@ -101,7 +113,7 @@ impl<'index> Updater<'index> {
       uncommitted += 1;
 
       if uncommitted == self.index.settings.commit_interval() {
-        self.commit(wtx, utxo_cache)?;
+        self.commit(wtx, utxo_cache, uncommitted, &mut last_commit)?;
         utxo_cache = HashMap::new();
         uncommitted = 0;
         wtx = self.index.begin_write()?;
@@ -140,7 +152,7 @@ impl<'index> Updater<'index> {
     }
 
     if uncommitted > 0 {
-      self.commit(wtx, utxo_cache)?;
+      self.commit(wtx, utxo_cache, uncommitted, &mut last_commit)?;
     }
 
     if let Some(progress_bar) = &mut progress_bar {
@@ -150,17 +162,16 @@ impl<'index> Updater<'index> {
     Ok(())
   }
 
-  fn fetch_blocks_from(
-    index: &Index,
-    mut height: u32,
-  ) -> Result<std::sync::mpsc::Receiver<BlockData>> {
-    let (tx, rx) = std::sync::mpsc::sync_channel(32);
+  fn fetch_blocks_from(index: &Index, mut height: u32) -> Result<pipeline::Receiver> {
+    let (tx, rx) = pipeline::spawn(index, 32);
 
     let first_index_height = index.first_index_height;
 
     let height_limit = index.height_limit;
 
-    let client = index.settings.bitcoin_rpc_client(None)?;
+    let block_source = index.block_source.clone();
+
+    let mut block_files = Self::open_block_files(index, height);
 
     thread::spawn(move || loop {
       if let Some(height_limit) = height_limit {
@@ -169,10 +180,30 @@ impl<'index> Updater<'index> {
         }
       }
 
-      match Self::get_block_with_retries(&client, height, first_index_height) {
+      let block = match block_files
+        .as_mut()
+        .map(|block_files| block_files.block(&*block_source, height))
+      {
+        Some(Ok(Some(mut block))) => {
+          if height < first_index_height {
+            block.txdata.clear();
+          }
+          Ok(Some(block))
+        }
+        Some(Err(err)) => {
+          log::warn!("failed to read block {height} from block files, falling back to RPC: {err}");
+          block_files = None;
+          Self::get_block_with_retries(&*block_source, height, first_index_height)
+        }
+        Some(Ok(None)) | None => {
+          Self::get_block_with_retries(&*block_source, height, first_index_height)
+        }
+      };
+
+      match block {
         Ok(Some(block)) => {
-          if let Err(err) = tx.send(block.into()) {
-            log::info!("Block receiver disconnected: {err}");
+          if !tx.send(height, block) {
+            log::info!("Block receiver disconnected");
             break;
           }
           height += 1;
@@ -188,30 +219,69 @@ impl<'index> Updater<'index> {
     Ok(rx)
   }
 
+  fn open_block_files(index: &Index, start: u32) -> Option<BlockFiles> {
+    let dir = index.settings.blocks_dir()?;
+
+    // blocks near the tip may still be reorged, so fetch them over RPC
+    let end = index
+      .block_source
+      .block_count()
+      .ok()?
+      .checked_sub(index.settings.chain_tip_distance())?;
+
+    if end <= start {
+      return None;
+    }
+
+    log::info!(
+      "Reading blocks {start} through {end} from block files in {}…",
+      dir.display()
+    );
+
+    match BlockFiles::open(
+      &dir,
+      index.settings.chain().network(),
+      &*index.block_source,
+      start,
+      end,
+    ) {
+      Ok(Some(block_files)) => Some(block_files),
+      Ok(None) => {
+        log::warn!("blocks {start} through {end} not found in block files, fetching over RPC");
+        None
+      }
+      Err(err) => {
+        log::warn!("failed to read block files, fetching blocks over RPC: {err}");
+        None
+      }
+    }
+  }
+
   fn get_block_with_retries(
-    client: &Client,
+    block_source: &dyn BlockSource,
     height: u32,
     first_index_height: u32,
   ) -> Result<Option<Block>> {
     let mut errors = 0;
     loop {
-      match client
-        .get_block_hash(height.into())
-        .into_option()
-        .and_then(|option| {
-          option
-            .map(|hash| {
-              if height >= first_index_height {
-                Ok(client.get_block(&hash)?)
-              } else {
-                Ok(Block {
-                  header: client.get_block_header(&hash)?,
-                  txdata: Vec::new(),
-                })
-              }
-            })
-            .transpose()
-        }) {
+      match block_source.block_hash(height).and_then(|option| {
+        option
+          .map(|hash| {
+            if height >= first_index_height {
+              block_source
+                .block(hash)?
+                .ok_or_else(|| anyhow!("block {hash} not found"))
+            } else {
+              Ok(Block {
+                header: block_source
+                  .block_header(hash)?
+                  .ok_or_else(|| anyhow!("block header {hash} not found"))?,
+                txdata: Vec::new(),
+              })
+            }
+          })
+          .transpose()
+      }) {
         Err(err) => {
           if cfg!(test) {
             return Err(err);
@@ -234,7 +304,14 @@ impl<'index> Updater<'index> {
   }
 
   fn spawn_fetcher(index: &Index) -> Result<(mpsc::Sender<OutPoint>, broadcast::Receiver<TxOut>)> {
-    let fetcher = Fetcher::new(&index.settings)?;
+    // batch JSON-RPC requests are much faster than fetching transactions one
+    // at a time, which is all other block sources support
+    let fetcher = match index.settings.esplora_url() {
+      Some(_) => None,
+      None => Some(Fetcher::new(&index.settings)?),
+    };
+
+    let block_source = index.block_source.clone();
 
     // A block probably has no more than 20k inputs
     const CHANNEL_BUFFER_SIZE: usize = 20_000;
@@ -277,8 +354,30 @@ impl<'index> Updater<'index> {
           let chunk_size = (outpoints.len() / parallel_requests) + 1;
           let mut futs = Vec::with_capacity(parallel_requests);
           for chunk in outpoints.chunks(chunk_size) {
-            let txids = chunk.iter().map(|outpoint| outpoint.txid).collect();
-            let fut = fetcher.get_transactions(txids);
+            let txids = chunk
+              .iter()
+              .map(|outpoint| outpoint.txid)
+              .collect::<Vec<Txid>>();
+            let fut = match &fetcher {
+              Some(fetcher) => fetcher.get_transactions(txids).boxed(),
+              None => {
+                let block_source = block_source.clone();
+                async move {
+                  task::spawn_blocking(move || {
+                    txids
+                      .into_iter()
+                      .map(|txid| {
+                        block_source
+                          .transaction(txid)?
+                          .ok_or_else(|| anyhow!("transaction {txid} not found"))
+                      })
+                      .collect::<Result<Vec<Transaction>>>()
+                  })
+                  .await?
+                }
+                .boxed()
+              }
+            };
             futs.push(fut);
           }
 
@@ -311,14 +410,17 @@ impl<'index> Updater<'index> {
     output_sender: &mut mpsc::Sender<OutPoint>,
     txout_receiver: &mut broadcast::Receiver<TxOut>,
     wtx: &mut WriteTransaction,
-    block: BlockData,
+    mut block: BlockData,
     utxo_cache: &mut HashMap<OutPoint, UtxoEntryBuf>,
+    undo_log: &mut UndoLog,
   ) -> Result<()> {
     Reorg::detect_reorg(&block, self.height, self.index)?;
 
     let start = Instant::now();
     let mut sat_ranges_written = 0;
     let mut outputs_in_block = 0;
+    let mut spent_script_pubkeys = HashMap::new();
+    let mut address_history = AddressHistory::default();
 
     log::info!(
       "Block {} at {} with {} transactions…",
@@ -332,24 +434,54 @@ impl<'index> Updater<'index> {
       wtx.open_table(INSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
     let mut statistic_to_count = wtx.open_table(STATISTIC_TO_COUNT)?;
 
+    let event_id_to_event = if self.index.index_events {
+      Some(wtx.open_table(EVENT_ID_TO_EVENT)?)
+    } else {
+      None
+    };
+
+    let next_event_id = match &event_id_to_event {
+      Some(event_id_to_event) => Index::next_event_id(event_id_to_event)?,
+      None => 0,
+    };
+
+    let mut event_emitter = EventEmitter {
+      event_id_to_event,
+      event_sender: self.index.event_sender.clone(),
+      next_event_id,
+    };
+
     if self.index.index_inscriptions || self.index.index_addresses || self.index.index_sats {
       self.index_utxo_entries(
-        &block,
+        &mut block,
         txout_receiver,
         output_sender,
         utxo_cache,
         wtx,
+        &mut event_emitter,
         &mut inscription_id_to_sequence_number,
         &mut statistic_to_count,
         &mut sat_ranges_written,
         &mut outputs_in_block,
+        &mut spent_script_pubkeys,
+        &mut address_history,
+        undo_log,
       )?;
     }
 
     if self.index.index_runes && self.height >= self.index.settings.first_rune_height() {
       let mut outpoint_to_rune_balances = wtx.open_table(OUTPOINT_TO_RUNE_BALANCES)?;
+      let mut rune_activity_id_to_rune_activity_entry =
+        wtx.open_table(RUNE_ACTIVITY_ID_TO_RUNE_ACTIVITY_ENTRY)?;
+      let mut rune_id_and_script_pubkey_to_balance =
+        wtx.open_table(RUNE_ID_AND_SCRIPT_PUBKEY_TO_BALANCE)?;
+      let mut rune_id_to_outpoint_balance = wtx.open_multimap_table(RUNE_ID_TO_OUTPOINT_BALANCE)?;
       let mut rune_id_to_rune_entry = wtx.open_table(RUNE_ID_TO_RUNE_ENTRY)?;
+      let mut rune_id_to_script_pubkey_balance =
+        wtx.open_multimap_table(RUNE_ID_TO_SCRIPT_PUBKEY_BALANCE)?;
       let mut rune_to_rune_id = wtx.open_table(RUNE_TO_RUNE_ID)?;
+      let mut script_pubkey_and_rune_id_to_balance =
+        wtx.open_table(SCRIPT_PUBKEY_AND_RUNE_ID_TO_BALANCE)?;
       let mut sequence_number_to_rune_id = wtx.open_table(SEQUENCE_NUMBER_TO_RUNE_ID)?;
       let mut transaction_id_to_rune = wtx.open_table(TRANSACTION_ID_TO_RUNE)?;
 
@@ -359,40 +491,88 @@ impl<'index> Updater<'index> {
         .unwrap_or(0);
 
       let mut rune_updater = RuneUpdater {
-        event_sender: self.index.event_sender.as_ref(),
+        address_history: self
+          .index
+          .has_address_history_index()
+          .then_some(&mut address_history),
+        event_emitter: &mut event_emitter,
+        block_source: &*self.index.block_source,
         block_time: block.header.time,
         burned: HashMap::new(),
-        client: &self.index.client,
         height: self.height,
+        id_and_script_pubkey_to_balance: &mut rune_id_and_script_pubkey_to_balance,
         id_to_entry: &mut rune_id_to_rune_entry,
+        id_to_outpoint_balance: &mut rune_id_to_outpoint_balance,
+        id_to_script_pubkey_balance: &mut rune_id_to_script_pubkey_balance,
+        index_activity: self.index.index_rune_activity,
         inscription_id_to_sequence_number: &mut inscription_id_to_sequence_number,
         minimum: Rune::minimum_at_height(
           self.index.settings.chain().network(),
           Height(self.height),
         ),
         outpoint_to_balances: &mut outpoint_to_rune_balances,
+        rune_activity_id_to_entry: &mut rune_activity_id_to_rune_activity_entry,
         rune_to_id: &mut rune_to_rune_id,
         runes,
+        script_pubkey_and_id_to_balance: &mut script_pubkey_and_rune_id_to_balance,
         sequence_number_to_rune_id: &mut sequence_number_to_rune_id,
+        spent_script_pubkeys: self.index.index_addresses.then_some(&spent_script_pubkeys),
         statistic_to_count: &mut statistic_to_count,
         transaction_id_to_rune: &mut transaction_id_to_rune,
+        tx_activity_count: 0,
+        undo_log,
       };
 
-      for (i, (tx, txid)) in block.txdata.iter().enumerate() {
-        rune_updater.index_runes(u32::try_from(i).unwrap(), tx, *txid)?;
+      for (i, ((tx, txid), artifact)) in block
+        .txdata
+        .iter()
+        .zip(mem::take(&mut block.artifacts))
+        .enumerate()
+      {
+        rune_updater.index_runes(u32::try_from(i).unwrap(), tx, *txid, artifact)?;
       }
 
       rune_updater.update()?;
     }
 
+    if self.index.has_address_history_index() {
+      let mut script_pubkey_and_tx_position_to_history_entry =
+        wtx.open_table(SCRIPT_PUBKEY_AND_TX_POSITION_TO_HISTORY_ENTRY)?;
+
+      let tx_positions = block
+        .txdata
+        .iter()
+        .enumerate()
+        .map(|(i, (_tx, txid))| (*txid, u32::try_from(i).unwrap()))
+        .collect::<HashMap<Txid, u32>>();
+
+      for (txid, script_pubkey, entry) in address_history.into_entries() {
+        undo_log.insert(
+          &mut script_pubkey_and_tx_position_to_history_entry,
+          (script_pubkey.as_bytes(), self.height, tx_positions[&txid]),
+          serde_json::to_vec(&entry)?.as_slice(),
+        )?;
+      }
+    }
+
     height_to_block_header.insert(&self.height, &block.header.store())?;
 
+    if self.index.index_events {
+      wtx
+        .open_table(HEIGHT_TO_LAST_EVENT_ID)?
+        .insert(&self.height, event_emitter.next_event_id)?;
+    }
+
     self.height += 1;
     self.outputs_traversed += outputs_in_block;
 
+    let elapsed = start.elapsed();
+
+    self.index.block_durations.lock().unwrap().observe(elapsed);
+
     log::info!(
       "Wrote {sat_ranges_written} sat ranges from {outputs_in_block} outputs in {} ms",
-      (Instant::now() - start).as_millis(),
+      elapsed.as_millis(),
     );
 
     Ok(())
@@ -400,20 +580,37 @@ impl<'index> Updater<'index> {
 
   fn index_utxo_entries<'wtx>(
     &mut self,
-    block: &BlockData,
+    block: &mut BlockData,
     txout_receiver: &mut broadcast::Receiver<TxOut>,
     output_sender: &mut mpsc::Sender<OutPoint>,
     utxo_cache: &mut HashMap<OutPoint, UtxoEntryBuf>,
     wtx: &'wtx WriteTransaction,
+    event_emitter: &mut EventEmitter<'wtx>,
     inscription_id_to_sequence_number: &mut Table<'wtx, (u128, u128, u32), u32>,
     statistic_to_count: &mut Table<'wtx, u64, u64>,
     sat_ranges_written: &mut u64,
     outputs_in_block: &mut u64,
+    spent_script_pubkeys: &mut HashMap<OutPoint, ScriptBuf>,
+    address_history: &mut AddressHistory,
+    undo_log: &mut UndoLog,
   ) -> Result<(), Error> {
+    let mut content_hash_to_sequence_number =
+      wtx.open_multimap_table(CONTENT_HASH_TO_SEQUENCE_NUMBER)?;
+    let mut content_type_and_sequence_number_to_inscription_id =
+      wtx.open_table(CONTENT_TYPE_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID)?;
+    let mut content_type_to_count = wtx.open_table(CONTENT_TYPE_TO_COUNT)?;
+    let mut delegate_to_sequence_number = wtx.open_multimap_table(DELEGATE_TO_SEQUENCE_NUMBER)?;
+    let mut term_to_sequence_number = wtx.open_multimap_table(TERM_TO_SEQUENCE_NUMBER)?;
     let mut height_to_last_sequence_number = wtx.open_table(HEIGHT_TO_LAST_SEQUENCE_NUMBER)?;
     let mut home_inscriptions = wtx.open_table(HOME_INSCRIPTIONS)?;
     let mut inscription_number_to_sequence_number =
       wtx.open_table(INSCRIPTION_NUMBER_TO_SEQUENCE_NUMBER)?;
+    let mut media_and_sequence_number_to_inscription_id =
+      wtx.open_table(MEDIA_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID)?;
+    let mut media_to_count = wtx.open_table(MEDIA_TO_COUNT)?;
+    let mut metaprotocol_and_sequence_number_to_inscription_id =
+      wtx.open_table(METAPROTOCOL_AND_SEQUENCE_NUMBER_TO_INSCRIPTION_ID)?;
+    let mut metaprotocol_to_count = wtx.open_table(METAPROTOCOL_TO_COUNT)?;
     let mut outpoint_to_utxo_entry = wtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
     let mut sat_to_satpoint = wtx.open_table(SAT_TO_SATPOINT)?;
     let mut sat_to_sequence_number = wtx.open_multimap_table(SAT_TO_SEQUENCE_NUMBER)?;
@@ -422,6 +619,7 @@ impl<'index> Updater<'index> {
     let mut sequence_number_to_inscription_entry =
       wtx.open_table(SEQUENCE_NUMBER_TO_INSCRIPTION_ENTRY)?;
     let mut transaction_id_to_transaction = wtx.open_table(TRANSACTION_ID_TO_TRANSACTION)?;
+    let mut transfer_id_to_transfer_entry = wtx.open_table(TRANSFER_ID_TO_TRANSFER_ENTRY)?;
 
     let index_inscriptions = self.height >= self.index.settings.first_inscription_height()
       && self.index.index_inscriptions;
@@ -500,7 +698,13 @@ impl<'index> Updater<'index> {
 
     let mut inscription_updater = InscriptionUpdater {
       blessed_inscription_count,
+      content_hash_to_sequence_number: &mut content_hash_to_sequence_number,
+      content_type_and_sequence_number_to_inscription_id:
+        &mut content_type_and_sequence_number_to_inscription_id,
+      content_type_to_count: &mut content_type_to_count,
       cursed_inscription_count,
+      delegate_to_sequence_number: &mut delegate_to_sequence_number,
+      event_emitter,
       flotsam: Vec::new(),
       height: self.height,
       home_inscription_count,
@@ -508,19 +712,27 @@ impl<'index> Updater<'index> {
       id_to_sequence_number: inscription_id_to_sequence_number,
       inscription_number_to_sequence_number: &mut inscription_number_to_sequence_number,
       lost_sats,
+      media_and_sequence_number_to_inscription_id: &mut media_and_sequence_number_to_inscription_id,
+      media_to_count: &mut media_to_count,
+      metaprotocol_and_sequence_number_to_inscription_id:
+        &mut metaprotocol_and_sequence_number_to_inscription_id,
+      metaprotocol_to_count: &mut metaprotocol_to_count,
       next_sequence_number,
       reward: Height(self.height).subsidy(),
       sat_to_sequence_number: &mut sat_to_sequence_number,
       sequence_number_to_children: &mut sequence_number_to_children,
       sequence_number_to_entry: &mut sequence_number_to_inscription_entry,
+      term_to_sequence_number: &mut term_to_sequence_number,
       timestamp: block.header.time,
       transaction_buffer: Vec::new(),
       transaction_id_to_transaction: &mut transaction_id_to_transaction,
+      transfer_id_to_transfer_entry: &mut transfer_id_to_transfer_entry,
       unbound_inscriptions,
     };
 
     let mut coinbase_inputs = Vec::new();
     let mut lost_sat_ranges = Vec::new();
+    let mut envelopes = mem::take(&mut block.envelopes);
 
     if self.index.index_sats {
       let h = Height(self.height);
@@ -551,10 +763,14 @@ impl<'index> Updater<'index> {
             let entry = if let Some(entry) = utxo_cache.remove(&OutPoint::load(outpoint)) {
               self.outputs_cached += 1;
               entry
-            } else if let Some(entry) = outpoint_to_utxo_entry.remove(&outpoint)? {
+            } else if let Some(entry) = undo_log.remove(&mut outpoint_to_utxo_entry, &outpoint)? {
               if self.index.index_addresses {
                 let script_pubkey = entry.value().parse(self.index).script_pubkey();
-                if !script_pubkey_to_outpoint.remove(script_pubkey, outpoint)? {
+                if !undo_log.remove_multimap(
+                  &mut script_pubkey_to_outpoint,
+                  script_pubkey,
+                  outpoint,
+                )? {
                   panic!("script pubkey entry ({script_pubkey:?}, {outpoint:?}) not found");
                 }
               }
@@ -588,6 +804,17 @@ impl<'index> Updater<'index> {
         .map(|entry| entry.parse(self.index))
         .collect::<Vec<ParsedUtxoEntry>>();
 
+      // rune holders are aggregated by script pubkey, which the rune updater
+      // can't look up once the spent outputs have been removed
+      if self.index.index_addresses && self.index.index_runes {
+        for (input, entry) in tx.input.iter().zip(&input_utxo_entries) {
+          spent_script_pubkeys.insert(
+            input.previous_output,
+            ScriptBuf::from_bytes(entry.script_pubkey().to_vec()),
+          );
+        }
+      }
+
       let mut output_utxo_entries = tx
         .output
         .iter()
@@ -620,6 +847,7 @@ impl<'index> Updater<'index> {
           leftover_sat_ranges,
           sat_ranges_written,
           outputs_in_block,
+          undo_log,
         )?;
       } else {
         input_sat_ranges = None;
@@ -637,14 +865,25 @@ impl<'index> Updater<'index> {
         inscription_updater.index_inscriptions(
           tx,
           *txid,
+          mem::take(&mut envelopes[tx_offset]),
           &input_utxo_entries,
           &mut output_utxo_entries,
           utxo_cache,
           self.index,
           input_sat_ranges.as_ref(),
+          undo_log,
         )?;
       }
 
+      if self.index.has_address_history_index() {
+        self.index_transaction_address_history(
+          *txid,
+          &input_utxo_entries,
+          &output_utxo_entries,
+          address_history,
+        );
+      }
+
       for (vout, output_utxo_entry) in output_utxo_entries.into_iter().enumerate() {
         let vout = u32::try_from(vout).unwrap();
         utxo_cache.insert(OutPoint { txid: *txid, vout }, output_utxo_entry);
@@ -667,7 +906,8 @@ impl<'index> Updater<'index> {
       for chunk in lost_sat_ranges.chunks_exact(11) {
         let (start, end) = SatRange::load(chunk.try_into().unwrap());
         if !Sat(start).common() {
-          sat_to_satpoint.insert(
+          undo_log.insert(
+            &mut sat_to_satpoint,
             &start,
             &SatPoint {
               outpoint: OutPoint::null(),
@@ -689,7 +929,8 @@ impl<'index> Updater<'index> {
       *utxo_entry = UtxoEntryBuf::merged(utxo_entry, &new_utxo_entry, self.index);
     }
 
-    statistic_to_count.insert(
+    undo_log.insert(
+      statistic_to_count,
       &Statistic::LostSats.key(),
       &if self.index.index_sats {
         lost_sats
@@ -698,17 +939,20 @@ impl<'index> Updater<'index> {
       },
     )?;
 
-    statistic_to_count.insert(
+    undo_log.insert(
+      statistic_to_count,
       &Statistic::CursedInscriptions.key(),
       &inscription_updater.cursed_inscription_count,
     )?;
 
-    statistic_to_count.insert(
+    undo_log.insert(
+      statistic_to_count,
       &Statistic::BlessedInscriptions.key(),
       &inscription_updater.blessed_inscription_count,
     )?;
 
-    statistic_to_count.insert(
+    undo_log.insert(
+      statistic_to_count,
       &Statistic::UnboundInscriptions.key(),
       &inscription_updater.unbound_inscriptions,
     )?;
@@ -716,6 +960,46 @@ impl<'index> Updater<'index> {
     Ok(())
   }
 
+  fn index_transaction_address_history(
+    &self,
+    txid: Txid,
+    input_utxo_entries: &[ParsedUtxoEntry],
+    output_utxo_entries: &[UtxoEntryBuf],
+    address_history: &mut AddressHistory,
+  ) {
+    for input in input_utxo_entries {
+      let entry = address_history.entry(txid, input.script_pubkey());
+
+      entry.sent += input.total_value();
+
+      if self.index.index_inscriptions {
+        entry.inscriptions_sent.extend(
+          input
+            .parse_inscriptions()
+            .into_iter()
+            .map(|(sequence_number, _offset)| sequence_number),
+        );
+      }
+    }
+
+    for output in output_utxo_entries {
+      let output = output.parse(self.index);
+
+      let entry = address_history.entry(txid, output.script_pubkey());
+
+      entry.received += output.total_value();
+
+      if self.index.index_inscriptions {
+        entry.inscriptions_received.extend(
+          output
+            .parse_inscriptions()
+            .into_iter()
+            .map(|(sequence_number, _offset)| sequence_number),
+        );
+      }
+    }
+  }
+
   fn index_transaction_output_script_pubkeys(
     &mut self,
     tx: &Transaction,
@@ -736,6 +1020,7 @@ impl<'index> Updater<'index> {
     leftover_sat_ranges: &mut Vec<u8>,
     sat_ranges_written: &mut u64,
     outputs_traversed: &mut u64,
+    undo_log: &mut UndoLog,
   ) -> Result {
     let mut pending_input_sat_range = None;
     let mut input_sat_ranges_iter = input_sat_ranges
@@ -771,7 +1056,8 @@ impl<'index> Updater<'index> {
         });
 
         if !Sat(range.0).common() {
-          sat_to_satpoint.insert(
+          undo_log.insert(
+            sat_to_satpoint,
             &range.0,
             &SatPoint {
               outpoint,
@@ -813,10 +1099,76 @@ impl<'index> Updater<'index> {
     Ok(())
   }
 
+  fn flush(
+    &mut self,
+    wtx: &WriteTransaction,
+    utxo_cache: &mut HashMap<OutPoint, UtxoEntryBuf>,
+    undo_log: &mut UndoLog,
+  ) -> Result {
+    let mut outpoint_to_utxo_entry = wtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
+    let mut script_pubkey_to_outpoint = wtx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
+    let mut sequence_number_to_satpoint = wtx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
+    let mut statistic_to_count = wtx.open_table(STATISTIC_TO_COUNT)?;
+
+    for (outpoint, mut utxo_entry) in utxo_cache.drain() {
+      if Index::is_special_outpoint(outpoint) {
+        if let Some(old_entry) = outpoint_to_utxo_entry.get(&outpoint.store())? {
+          utxo_entry = UtxoEntryBuf::merged(old_entry.value(), &utxo_entry, self.index);
+        }
+      }
+
+      undo_log.insert(
+        &mut outpoint_to_utxo_entry,
+        &outpoint.store(),
+        utxo_entry.as_ref(),
+      )?;
+
+      let utxo_entry = utxo_entry.parse(self.index);
+      if self.index.index_addresses {
+        let script_pubkey = utxo_entry.script_pubkey();
+        undo_log.insert_multimap(
+          &mut script_pubkey_to_outpoint,
+          script_pubkey,
+          &outpoint.store(),
+        )?;
+      }
+
+      if self.index.index_inscriptions {
+        for (sequence_number, offset) in utxo_entry.parse_inscriptions() {
+          let satpoint = SatPoint { outpoint, offset };
+          undo_log.insert(
+            &mut sequence_number_to_satpoint,
+            sequence_number,
+            &satpoint.store(),
+          )?;
+        }
+      }
+    }
+
+    for (statistic, n) in [
+      (Statistic::OutputsTraversed, &mut self.outputs_traversed),
+      (Statistic::SatRanges, &mut self.sat_ranges_since_flush),
+    ] {
+      let value = statistic_to_count
+        .get(&statistic.key())?
+        .map(|x| x.value())
+        .unwrap_or_default()
+        + *n;
+
+      undo_log.insert(&mut statistic_to_count, &statistic.key(), &value)?;
+
+      *n = 0;
+    }
+
+    Ok(())
+  }
+
   fn commit(
     &mut self,
     wtx: WriteTransaction,
-    utxo_cache: HashMap<OutPoint, UtxoEntryBuf>,
+    mut utxo_cache: HashMap<OutPoint, UtxoEntryBuf>,
+    blocks: usize,
+    last_commit: &mut Instant,
   ) -> Result {
     log::info!(
       "Committing at block height {}, {} outputs traversed, {} in map, {} cached",
@@ -826,42 +1178,34 @@ impl<'index> Updater<'index> {
       self.outputs_cached
     );
 
-    {
-      let mut outpoint_to_utxo_entry = wtx.open_table(OUTPOINT_TO_UTXO_ENTRY)?;
-      let mut script_pubkey_to_outpoint = wtx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
-      let mut sequence_number_to_satpoint = wtx.open_table(SEQUENCE_NUMBER_TO_SATPOINT)?;
-
-      for (outpoint, mut utxo_entry) in utxo_cache {
-        if Index::is_special_outpoint(outpoint) {
-          if let Some(old_entry) = outpoint_to_utxo_entry.get(&outpoint.store())? {
-            utxo_entry = UtxoEntryBuf::merged(old_entry.value(), &utxo_entry, self.index);
-          }
-        }
-
-        outpoint_to_utxo_entry.insert(&outpoint.store(), utxo_entry.as_ref())?;
-
-        let utxo_entry = utxo_entry.parse(self.index);
-        if self.index.index_addresses {
-          let script_pubkey = utxo_entry.script_pubkey();
-          script_pubkey_to_outpoint.insert(script_pubkey, &outpoint.store())?;
-        }
+    let start = Instant::now();
 
-        if self.index.index_inscriptions {
-          for (sequence_number, offset) in utxo_entry.parse_inscriptions() {
-            let satpoint = SatPoint { outpoint, offset };
-            sequence_number_to_satpoint.insert(sequence_number, &satpoint.store())?;
-          }
-        }
-      }
-    }
+    self.flush(&wtx, &mut utxo_cache, &mut UndoLog::default())?;
 
-    Index::increment_statistic(&wtx, Statistic::OutputsTraversed, self.outputs_traversed)?;
-    self.outputs_traversed = 0;
-    Index::increment_statistic(&wtx, Statistic::SatRanges, self.sat_ranges_since_flush)?;
-    self.sat_ranges_since_flush = 0;
     Index::increment_statistic(&wtx, Statistic::Commits, 1)?;
+    Index::increment_statistic(&wtx, Statistic::IndexedBlocks, u64::try_from(blocks)?)?;
+    Index::increment_statistic(
+      &wtx,
+      Statistic::IndexedTransactions,
+      self.transactions_since_commit,
+    )?;
+    Index::increment_statistic(
+      &wtx,
+      Statistic::IndexingTime,
+      u64::try_from(last_commit.elapsed().as_micros())?,
+    )?;
     wtx.commit()?;
 
+    self
+      .index
+      .commit_durations
+      .lock()
+      .unwrap()
+      .observe(start.elapsed());
+
+    self.transactions_since_commit = 0;
+    *last_commit = Instant::now();
+
     // Commit twice since due to a bug redb will only reuse pages freed in the
     // transaction before last.
     self.index.begin_write()?.commit()?;
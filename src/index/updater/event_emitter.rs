@@ -0,0 +1,23 @@
+use super::*;
+
+pub(super) struct EventEmitter<'tx> {
+  pub(super) event_id_to_event: Option<Table<'tx, u64, &'static [u8]>>,
+  pub(super) event_sender: Option<mpsc::Sender<Event>>,
+  pub(super) next_event_id: u64,
+}
+
+impl EventEmitter<'_> {
+  pub(super) fn emit(&mut self, event: Event) -> Result {
+    if let Some(event_id_to_event) = &mut self.event_id_to_event {
+      event_id_to_event.insert(self.next_event_id, serde_json::to_vec(&event)?.as_slice())?;
+
+      self.next_event_id += 1;
+    }
+
+    if let Some(sender) = &self.event_sender {
+      sender.blocking_send(event)?;
+    }
+
+    Ok(())
+  }
+}
@@ -18,16 +18,25 @@ pub(super) struct Flotsam {
   inscription_id: InscriptionId,
   offset: u64,
   origin: Origin,
+  // transaction that created or spent the inscription, which differs from the
+  // transaction of its new location when it is sent to fees
+  txid: Txid,
 }
 
 #[derive(Debug, Clone)]
 enum Origin {
   New {
+    content_hash: Option<[u8; 32]>,
+    content_type: Option<String>,
     cursed: bool,
+    delegate: Option<InscriptionId>,
     fee: u64,
     hidden: bool,
+    media: Media,
+    metaprotocol: Option<String>,
     parents: Vec<InscriptionId>,
     reinscription: bool,
+    terms: BTreeSet<String>,
     unbound: bool,
     vindicated: bool,
   },
@@ -39,7 +48,13 @@ enum Origin {
 
 pub(super) struct InscriptionUpdater<'a, 'tx> {
   pub(super) blessed_inscription_count: u64,
+  pub(super) content_hash_to_sequence_number: &'a mut MultimapTable<'tx, &'static [u8; 32], u32>,
+  pub(super) content_type_and_sequence_number_to_inscription_id:
+    &'a mut Table<'tx, (&'static str, u32), InscriptionIdValue>,
+  pub(super) content_type_to_count: &'a mut Table<'tx, &'static str, u64>,
   pub(super) cursed_inscription_count: u64,
+  pub(super) delegate_to_sequence_number: &'a mut MultimapTable<'tx, InscriptionIdValue, u32>,
+  pub(super) event_emitter: &'a mut EventEmitter<'tx>,
   pub(super) flotsam: Vec<Flotsam>,
   pub(super) height: u32,
   pub(super) home_inscription_count: u64,
@@ -47,6 +62,12 @@ pub(super) struct InscriptionUpdater<'a, 'tx> {
   pub(super) id_to_sequence_number: &'a mut Table<'tx, InscriptionIdValue, u32>,
   pub(super) inscription_number_to_sequence_number: &'a mut Table<'tx, i32, u32>,
   pub(super) lost_sats: u64,
+  pub(super) media_and_sequence_number_to_inscription_id:
+    &'a mut Table<'tx, (&'static str, u32), InscriptionIdValue>,
+  pub(super) media_to_count: &'a mut Table<'tx, &'static str, u64>,
+  pub(super) metaprotocol_and_sequence_number_to_inscription_id:
+    &'a mut Table<'tx, (&'static str, u32), InscriptionIdValue>,
+  pub(super) metaprotocol_to_count: &'a mut Table<'tx, &'static str, u64>,
   pub(super) next_sequence_number: u32,
   pub(super) reward: u64,
   pub(super) transaction_buffer: Vec<u8>,
@@ -54,7 +75,9 @@ pub(super) struct InscriptionUpdater<'a, 'tx> {
   pub(super) sat_to_sequence_number: &'a mut MultimapTable<'tx, u64, u32>,
   pub(super) sequence_number_to_children: &'a mut MultimapTable<'tx, u32, u32>,
   pub(super) sequence_number_to_entry: &'a mut Table<'tx, u32, InscriptionEntryValue>,
+  pub(super) term_to_sequence_number: &'a mut MultimapTable<'tx, &'static str, u32>,
   pub(super) timestamp: u32,
+  pub(super) transfer_id_to_transfer_entry: &'a mut Table<'tx, (u32, u32), TransferEntryValue>,
   pub(super) unbound_inscriptions: u64,
 }
 
@@ -63,11 +86,13 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
     &mut self,
     tx: &Transaction,
     txid: Txid,
+    envelopes: Vec<ParsedEnvelope>,
     input_utxo_entries: &[ParsedUtxoEntry],
     output_utxo_entries: &mut [UtxoEntryBuf],
     utxo_cache: &mut HashMap<OutPoint, UtxoEntryBuf>,
     index: &Index,
     input_sat_ranges: Option<&Vec<&[u8]>>,
+    undo_log: &mut UndoLog,
   ) -> Result {
     let mut floating_inscriptions = Vec::new();
     let mut id_counter = 0;
@@ -80,7 +105,6 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
       .map(|txout| txout.value.to_sat())
       .sum::<u64>();
 
-    let envelopes = ParsedEnvelope::from_transaction(tx);
     let has_new_inscriptions = !envelopes.is_empty();
     let mut envelopes = envelopes.into_iter().peekable();
 
@@ -118,6 +142,7 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
             sequence_number,
             old_satpoint,
           },
+          txid,
         });
 
         inscribed_offsets
@@ -196,16 +221,37 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
           inscription_id,
           offset,
           origin: Origin::New {
+            content_hash: if index.index_content_hashes {
+              inscription.payload.content_hash()
+            } else {
+              None
+            },
+            content_type: inscription
+              .payload
+              .content_type()
+              .map(Media::normalize_content_type)
+              .filter(|content_type| !content_type.is_empty()),
             cursed: curse.is_some() && !jubilant,
+            delegate: inscription.payload.delegate(),
             fee: 0,
             hidden: inscription.payload.hidden(),
+            media: inscription.payload.media(),
+            metaprotocol: inscription.payload.metaprotocol().map(str::to_string),
             parents: inscription.payload.parents(),
             reinscription: inscribed_offsets.contains_key(&offset),
+            terms: if index.index_full_text {
+              full_text::text(&inscription.payload)
+                .map(|text| full_text::terms(&text))
+                .unwrap_or_default()
+            } else {
+              BTreeSet::new()
+            },
             unbound: input_value == 0
               || curse == Some(Curse::UnrecognizedEvenField)
               || inscription.payload.unrecognized_even_field,
             vindicated: curse.is_some() && jubilant,
           },
+          txid,
         });
 
         inscribed_offsets
@@ -222,9 +268,11 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
       tx.consensus_encode(&mut self.transaction_buffer)
         .expect("in-memory writers don't error");
 
-      self
-        .transaction_id_to_transaction
-        .insert(&txid.store(), self.transaction_buffer.as_slice())?;
+      undo_log.insert(
+        self.transaction_id_to_transaction,
+        &txid.store(),
+        self.transaction_buffer.as_slice(),
+      )?;
 
       self.transaction_buffer.clear();
     }
@@ -294,14 +342,14 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
         new_locations.push((
           new_satpoint,
           inscriptions.next().unwrap(),
-          txout.script_pubkey.is_op_return(),
+          txout.script_pubkey.as_script(),
         ));
       }
 
       output_value = end;
     }
 
-    for (new_satpoint, flotsam, op_return) in new_locations.into_iter() {
+    for (new_satpoint, flotsam, script_pubkey) in new_locations.into_iter() {
       let output_utxo_entry =
         &mut output_utxo_entries[usize::try_from(new_satpoint.outpoint.vout).unwrap()];
 
@@ -309,10 +357,11 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
         input_sat_ranges,
         flotsam,
         new_satpoint,
-        op_return,
+        Some(script_pubkey),
         Some(output_utxo_entry),
         utxo_cache,
         index,
+        undo_log,
       )?;
     }
 
@@ -326,10 +375,11 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
           input_sat_ranges,
           flotsam,
           new_satpoint,
-          false,
+          None,
           None,
           utxo_cache,
           index,
+          undo_log,
         )?;
       }
       self.lost_sats += self.reward - output_value;
@@ -369,13 +419,16 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
     input_sat_ranges: Option<&Vec<&[u8]>>,
     flotsam: Flotsam,
     new_satpoint: SatPoint,
-    op_return: bool,
+    script_pubkey: Option<&Script>,
     mut normal_output_utxo_entry: Option<&mut UtxoEntryBuf>,
     utxo_cache: &mut HashMap<OutPoint, UtxoEntryBuf>,
     index: &Index,
+    undo_log: &mut UndoLog,
   ) -> Result {
     let inscription_id = flotsam.inscription_id;
-    let (unbound, sequence_number) = match flotsam.origin {
+    let txid = flotsam.txid;
+    let op_return = script_pubkey.is_some_and(|script_pubkey| script_pubkey.is_op_return());
+    let (unbound, sequence_number, old_satpoint) = match flotsam.origin {
       Origin::Old {
         sequence_number,
         old_satpoint,
@@ -392,30 +445,35 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
           let mut charms = entry.charms;
           Charm::Burned.set(&mut charms);
 
-          self.sequence_number_to_entry.insert(
+          undo_log.insert(
+            self.sequence_number_to_entry,
             sequence_number,
             &InscriptionEntry { charms, ..entry }.store(),
           )?;
         }
 
-        if let Some(ref sender) = index.event_sender {
-          sender.blocking_send(Event::InscriptionTransferred {
-            block_height: self.height,
-            inscription_id,
-            new_location: new_satpoint,
-            old_location: old_satpoint,
-            sequence_number,
-          })?;
-        }
+        self.event_emitter.emit(Event::InscriptionTransferred {
+          block_height: self.height,
+          inscription_id,
+          new_location: new_satpoint,
+          old_location: old_satpoint,
+          sequence_number,
+        })?;
 
-        (false, sequence_number)
+        (false, sequence_number, Some(old_satpoint))
       }
       Origin::New {
+        content_hash,
+        content_type,
         cursed,
+        delegate,
         fee,
         hidden,
+        media,
+        metaprotocol,
         parents,
         reinscription,
+        terms,
         unbound,
         vindicated,
       } => {
@@ -432,9 +490,11 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
         let sequence_number = self.next_sequence_number;
         self.next_sequence_number += 1;
 
-        self
-          .inscription_number_to_sequence_number
-          .insert(inscription_number, sequence_number)?;
+        undo_log.insert(
+          self.inscription_number_to_sequence_number,
+          inscription_number,
+          sequence_number,
+        )?;
 
         let sat = if unbound {
           None
@@ -473,7 +533,7 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
         }
 
         if let Some(Sat(n)) = sat {
-          self.sat_to_sequence_number.insert(&n, &sequence_number)?;
+          undo_log.insert_multimap(self.sat_to_sequence_number, &n, &sequence_number)?;
         }
 
         let parent_sequence_numbers = parents
@@ -485,26 +545,27 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
               .unwrap()
               .value();
 
-            self
-              .sequence_number_to_children
-              .insert(parent_sequence_number, sequence_number)?;
+            undo_log.insert_multimap(
+              self.sequence_number_to_children,
+              parent_sequence_number,
+              sequence_number,
+            )?;
 
             Ok(parent_sequence_number)
           })
           .collect::<Result<Vec<u32>>>()?;
 
-        if let Some(ref sender) = index.event_sender {
-          sender.blocking_send(Event::InscriptionCreated {
-            block_height: self.height,
-            charms,
-            inscription_id,
-            location: (!unbound).then_some(new_satpoint),
-            parent_inscription_ids: parents,
-            sequence_number,
-          })?;
-        }
+        self.event_emitter.emit(Event::InscriptionCreated {
+          block_height: self.height,
+          charms,
+          inscription_id,
+          location: (!unbound).then_some(new_satpoint),
+          parent_inscription_ids: parents,
+          sequence_number,
+        })?;
 
-        self.sequence_number_to_entry.insert(
+        undo_log.insert(
+          self.sequence_number_to_entry,
           sequence_number,
           &InscriptionEntry {
             charms,
@@ -520,23 +581,79 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
           .store(),
         )?;
 
-        self
-          .id_to_sequence_number
-          .insert(&inscription_id.store(), sequence_number)?;
+        undo_log.insert(
+          self.id_to_sequence_number,
+          &inscription_id.store(),
+          sequence_number,
+        )?;
+
+        if let Some(delegate) = delegate {
+          undo_log.insert_multimap(
+            self.delegate_to_sequence_number,
+            &delegate.store(),
+            sequence_number,
+          )?;
+        }
+
+        if let Some(content_hash) = &content_hash {
+          undo_log.insert_multimap(
+            self.content_hash_to_sequence_number,
+            content_hash,
+            sequence_number,
+          )?;
+        }
+
+        if index.index_content_types {
+          if let Some(content_type) = &content_type {
+            undo_log.insert(
+              self.content_type_and_sequence_number_to_inscription_id,
+              (content_type.as_str(), sequence_number),
+              inscription_id.store(),
+            )?;
+
+            Self::increment_count(self.content_type_to_count, content_type, undo_log)?;
+          }
+
+          undo_log.insert(
+            self.media_and_sequence_number_to_inscription_id,
+            (media.kind(), sequence_number),
+            inscription_id.store(),
+          )?;
+
+          Self::increment_count(self.media_to_count, media.kind(), undo_log)?;
+        }
+
+        if index.index_metaprotocols {
+          if let Some(metaprotocol) = &metaprotocol {
+            undo_log.insert(
+              self.metaprotocol_and_sequence_number_to_inscription_id,
+              (metaprotocol.as_str(), sequence_number),
+              inscription_id.store(),
+            )?;
+
+            Self::increment_count(self.metaprotocol_to_count, metaprotocol, undo_log)?;
+          }
+        }
+
+        for term in &terms {
+          undo_log.insert_multimap(self.term_to_sequence_number, term.as_str(), sequence_number)?;
+        }
 
         if !hidden {
-          self
-            .home_inscriptions
-            .insert(&sequence_number, inscription_id.store())?;
+          undo_log.insert(
+            self.home_inscriptions,
+            &sequence_number,
+            inscription_id.store(),
+          )?;
 
           if self.home_inscription_count == 100 {
-            self.home_inscriptions.pop_first()?;
+            undo_log.pop_first(self.home_inscriptions)?;
           } else {
             self.home_inscription_count += 1;
           }
         }
 
-        (unbound, sequence_number)
+        (unbound, sequence_number, None)
       }
     };
 
@@ -565,6 +682,41 @@ impl<'a, 'tx> InscriptionUpdater<'a, 'tx> {
 
     output_utxo_entry.push_inscription(sequence_number, satpoint.offset, index);
 
+    if index.index_inscription_history {
+      let transfer_number = self
+        .transfer_id_to_transfer_entry
+        .range((sequence_number, 0)..=(sequence_number, u32::MAX))?
+        .next_back()
+        .transpose()?
+        .map(|(id, _)| id.value().1 + 1)
+        .unwrap_or_default();
+
+      undo_log.insert(
+        self.transfer_id_to_transfer_entry,
+        (sequence_number, transfer_number),
+        TransferEntry {
+          height: self.height,
+          new_satpoint: satpoint,
+          old_satpoint,
+          script_pubkey: script_pubkey.map(Script::to_owned),
+          txid,
+        }
+        .store(),
+      )?;
+    }
+
     Ok(())
   }
+
+  fn increment_count(
+    table: &mut Table<&'static str, u64>,
+    key: &str,
+    undo_log: &mut UndoLog,
+  ) -> Result {
+    let count = table
+      .get(key)?
+      .map(|count| count.value())
+      .unwrap_or_default();
+    undo_log.insert(table, key, count + 1)
+  }
 }
@@ -0,0 +1,123 @@
+use {
+  super::*,
+  std::sync::mpsc::{self, SyncSender},
+};
+
+type Job = (u32, Block, SyncSender<BlockData>);
+
+/// Which parts of a block the indexer will need, decided per height so that
+/// workers don't decipher runestones or extract envelopes below the first
+/// rune or inscription height.
+#[derive(Clone, Copy)]
+struct Parse {
+  first_inscription_height: Option<u32>,
+  first_rune_height: Option<u32>,
+}
+
+impl Parse {
+  fn block(self, height: u32, block: Block) -> BlockData {
+    let txdata = block
+      .txdata
+      .into_iter()
+      .map(|transaction| {
+        let txid = transaction.compute_txid();
+        (transaction, txid)
+      })
+      .collect::<Vec<(Transaction, Txid)>>();
+
+    BlockData {
+      artifacts: if self.first_rune_height.is_some_and(|first| height >= first) {
+        txdata
+          .iter()
+          .map(|(tx, _txid)| Runestone::decipher(tx))
+          .collect()
+      } else {
+        Vec::new()
+      },
+      envelopes: if self
+        .first_inscription_height
+        .is_some_and(|first| height >= first)
+      {
+        txdata
+          .iter()
+          .map(|(tx, _txid)| ParsedEnvelope::from_transaction(tx))
+          .collect()
+      } else {
+        Vec::new()
+      },
+      header: block.header,
+      txdata,
+    }
+  }
+}
+
+/// Sends fetched blocks to a pool of workers which compute txids, decipher
+/// runestones, and extract inscription envelopes, while the updater indexes
+/// earlier blocks on the main thread.
+pub(super) struct Sender {
+  jobs: mpsc::Sender<Job>,
+  results: SyncSender<mpsc::Receiver<BlockData>>,
+}
+
+impl Sender {
+  /// Queues `block` for parsing, returning false if the receiver has been
+  /// dropped.
+  pub(super) fn send(&self, height: u32, block: Block) -> bool {
+    let (tx, rx) = mpsc::sync_channel(1);
+
+    self.results.send(rx).is_ok() && self.jobs.send((height, block, tx)).is_ok()
+  }
+}
+
+/// Receives parsed blocks in the order they were sent.
+pub(super) struct Receiver {
+  results: mpsc::Receiver<mpsc::Receiver<BlockData>>,
+}
+
+impl Receiver {
+  pub(super) fn recv(&self) -> Option<BlockData> {
+    self.results.recv().ok()?.recv().ok()
+  }
+}
+
+pub(super) fn spawn(index: &Index, capacity: usize) -> (Sender, Receiver) {
+  let parse = Parse {
+    first_inscription_height: index
+      .index_inscriptions
+      .then(|| index.settings.first_inscription_height()),
+    first_rune_height: index
+      .index_runes
+      .then(|| index.settings.first_rune_height()),
+  };
+
+  let (jobs, job_receiver) = mpsc::channel::<Job>();
+  let (results, result_receiver) = mpsc::sync_channel(capacity);
+
+  let job_receiver = Arc::new(Mutex::new(job_receiver));
+
+  let workers = thread::available_parallelism()
+    .map(|n| n.get().saturating_sub(1))
+    .unwrap_or_default()
+    .max(1);
+
+  for _ in 0..workers {
+    let job_receiver = job_receiver.clone();
+
+    thread::spawn(move || loop {
+      let job = job_receiver.lock().unwrap().recv();
+
+      let Ok((height, block, tx)) = job else {
+        break;
+      };
+
+      tx.send(parse.block(height, block)).ok();
+    });
+  }
+
+  (
+    Sender { jobs, results },
+    Receiver {
+      results: result_receiver,
+    },
+  )
+}
@@ -1,27 +1,47 @@
 use super::*;
 
-pub(super) struct RuneUpdater<'a, 'tx, 'client> {
+pub(super) struct RuneUpdater<'a, 'tx, 'source> {
+  pub(super) address_history: Option<&'a mut AddressHistory>,
+  pub(super) block_source: &'source dyn BlockSource,
   pub(super) block_time: u32,
   pub(super) burned: HashMap<RuneId, Lot>,
-  pub(super) client: &'client Client,
-  pub(super) event_sender: Option<&'a mpsc::Sender<Event>>,
+  pub(super) event_emitter: &'a mut EventEmitter<'tx>,
   pub(super) height: u32,
+  pub(super) id_and_script_pubkey_to_balance:
+    &'a mut Table<'tx, (RuneIdValue, &'static [u8]), u128>,
   pub(super) id_to_entry: &'a mut Table<'tx, RuneIdValue, RuneEntryValue>,
+  pub(super) id_to_outpoint_balance: &'a mut MultimapTable<'tx, RuneIdValue, (u128, OutPointValue)>,
+  pub(super) id_to_script_pubkey_balance:
+    &'a mut MultimapTable<'tx, RuneIdValue, (u128, &'static [u8])>,
+  pub(super) index_activity: bool,
   pub(super) inscription_id_to_sequence_number: &'a Table<'tx, InscriptionIdValue, u32>,
   pub(super) minimum: Rune,
   pub(super) outpoint_to_balances: &'a mut Table<'tx, &'static OutPointValue, &'static [u8]>,
+  pub(super) rune_activity_id_to_entry:
+    &'a mut Table<'tx, (RuneIdValue, u32, u32, u32), RuneActivityEntryValue>,
   pub(super) rune_to_id: &'a mut Table<'tx, u128, RuneIdValue>,
   pub(super) runes: u64,
+  pub(super) script_pubkey_and_id_to_balance:
+    &'a mut Table<'tx, (&'static [u8], RuneIdValue), u128>,
   pub(super) sequence_number_to_rune_id: &'a mut Table<'tx, u32, RuneIdValue>,
+  pub(super) spent_script_pubkeys: Option<&'a HashMap<OutPoint, ScriptBuf>>,
   pub(super) statistic_to_count: &'a mut Table<'tx, u64, u64>,
   pub(super) transaction_id_to_rune: &'a mut Table<'tx, &'static TxidValue, u128>,
+  pub(super) tx_activity_count: u32,
+  pub(super) undo_log: &'a mut UndoLog,
 }
 
-impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
-  pub(super) fn index_runes(&mut self, tx_index: u32, tx: &Transaction, txid: Txid) -> Result<()> {
-    let artifact = Runestone::decipher(tx);
+impl<'a, 'tx, 'source> RuneUpdater<'a, 'tx, 'source> {
+  pub(super) fn index_runes(
+    &mut self,
+    tx_index: u32,
+    tx: &Transaction,
+    txid: Txid,
+    artifact: Option<Artifact>,
+  ) -> Result<()> {
+    self.tx_activity_count = 0;
 
-    let mut unallocated = self.unallocated(tx)?;
+    let (mut unallocated, sources) = self.unallocated(tx, txid)?;
 
     let mut allocated: Vec<HashMap<RuneId, Lot>> = vec![HashMap::new(); tx.output.len()];
 
@@ -30,14 +50,22 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
         if let Some(amount) = self.mint(id)? {
           *unallocated.entry(id).or_default() += amount;
 
-          if let Some(sender) = self.event_sender {
-            sender.blocking_send(Event::RuneMinted {
-              block_height: self.height,
-              txid,
-              rune_id: id,
-              amount: amount.n(),
-            })?;
-          }
+          self.record_activity(
+            tx_index,
+            id,
+            RuneActivityKind::Mint,
+            amount,
+            &sources,
+            None,
+            txid,
+          )?;
+
+          self.event_emitter.emit(Event::RuneMinted {
+            block_height: self.height,
+            txid,
+            rune_id: id,
+            amount: amount.n(),
+          })?;
         }
       }
 
@@ -45,81 +73,30 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
 
       if let Artifact::Runestone(runestone) = artifact {
         if let Some((id, ..)) = etched {
-          *unallocated.entry(id).or_default() +=
-            runestone.etching.unwrap().premine.unwrap_or_default();
-        }
-
-        for Edict { id, amount, output } in runestone.edicts.iter().copied() {
-          let amount = Lot(amount);
-
-          // edicts with output values greater than the number of outputs
-          // should never be produced by the edict parser
-          let output = usize::try_from(output).unwrap();
-          assert!(output <= tx.output.len());
-
-          let id = if id == RuneId::default() {
-            let Some((id, ..)) = etched else {
-              continue;
-            };
-
-            id
-          } else {
-            id
-          };
-
-          let Some(balance) = unallocated.get_mut(&id) else {
-            continue;
-          };
-
-          let mut allocate = |balance: &mut Lot, amount: Lot, output: usize| {
-            if amount > 0 {
-              *balance -= amount;
-              *allocated[output].entry(id).or_default() += amount;
-            }
-          };
-
-          if output == tx.output.len() {
-            // find non-OP_RETURN outputs
-            let destinations = tx
-              .output
-              .iter()
-              .enumerate()
-              .filter_map(|(output, tx_out)| {
-                (!tx_out.script_pubkey.is_op_return()).then_some(output)
-              })
-              .collect::<Vec<usize>>();
-
-            if !destinations.is_empty() {
-              if amount == 0 {
-                // if amount is zero, divide balance between eligible outputs
-                let amount = *balance / destinations.len() as u128;
-                let remainder = usize::try_from(*balance % destinations.len() as u128).unwrap();
-
-                for (i, output) in destinations.iter().enumerate() {
-                  allocate(
-                    balance,
-                    if i < remainder { amount + 1 } else { amount },
-                    *output,
-                  );
-                }
-              } else {
-                // if amount is non-zero, distribute amount to eligible outputs
-                for output in destinations {
-                  allocate(balance, amount.min(*balance), output);
-                }
-              }
-            }
-          } else {
-            // Get the allocatable amount
-            let amount = if amount == 0 {
-              *balance
-            } else {
-              amount.min(*balance)
-            };
-
-            allocate(balance, amount, output);
+          let premine = runestone.etching.unwrap().premine.unwrap_or_default();
+
+          *unallocated.entry(id).or_default() += premine;
+
+          if premine > 0 {
+            self.record_activity(
+              tx_index,
+              id,
+              RuneActivityKind::Mint,
+              Lot(premine),
+              &sources,
+              None,
+              txid,
+            )?;
           }
         }
+
+        allocation::apply_edicts(
+          tx,
+          &runestone.edicts,
+          etched.map(|(id, _rune)| id),
+          &mut unallocated,
+          &mut allocated,
+        );
       }
 
       if let Some((id, rune)) = etched {
@@ -132,6 +109,18 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
     if let Some(Artifact::Cenotaph(_)) = artifact {
       for (id, balance) in unallocated {
         *burned.entry(id).or_default() += balance;
+
+        if balance > 0 {
+          self.record_activity(
+            tx_index,
+            id,
+            RuneActivityKind::Burn,
+            balance,
+            &sources,
+            None,
+            txid,
+          )?;
+        }
       }
     } else {
       let pointer = artifact
@@ -143,17 +132,7 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
 
       // assign all un-allocated runes to the default output, or the first non
       // OP_RETURN output if there is no default
-      if let Some(vout) = pointer
-        .map(|pointer| pointer.into_usize())
-        .inspect(|&pointer| assert!(pointer < allocated.len()))
-        .or_else(|| {
-          tx.output
-            .iter()
-            .enumerate()
-            .find(|(_vout, tx_out)| !tx_out.script_pubkey.is_op_return())
-            .map(|(vout, _tx_out)| vout)
-        })
-      {
+      if let Some(vout) = allocation::default_output(tx, pointer) {
         for (id, balance) in unallocated {
           if balance > 0 {
             *allocated[vout].entry(id).or_default() += balance;
@@ -163,6 +142,16 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
         for (id, balance) in unallocated {
           if balance > 0 {
             *burned.entry(id).or_default() += balance;
+
+            self.record_activity(
+              tx_index,
+              id,
+              RuneActivityKind::Burn,
+              balance,
+              &sources,
+              None,
+              txid,
+            )?;
           }
         }
       }
@@ -175,57 +164,77 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
         continue;
       }
 
+      let outpoint = OutPoint {
+        txid,
+        vout: vout.try_into().unwrap(),
+      };
+
+      let mut balances = balances.into_iter().collect::<Vec<(RuneId, Lot)>>();
+
+      // Sort balances by id so tests can assert balances in a fixed order
+      balances.sort();
+
       // increment burned balances
       if tx.output[vout].script_pubkey.is_op_return() {
-        for (id, balance) in &balances {
-          *burned.entry(*id).or_default() += *balance;
+        for (id, balance) in balances {
+          *burned.entry(id).or_default() += balance;
+
+          self.record_activity(
+            tx_index,
+            id,
+            RuneActivityKind::Burn,
+            balance,
+            &sources,
+            Some(outpoint),
+            txid,
+          )?;
         }
         continue;
       }
 
       buffer.clear();
 
-      let mut balances = balances.into_iter().collect::<Vec<(RuneId, Lot)>>();
-
-      // Sort balances by id so tests can assert balances in a fixed order
-      balances.sort();
-
-      let outpoint = OutPoint {
-        txid,
-        vout: vout.try_into().unwrap(),
-      };
-
       for (id, balance) in balances {
         Index::encode_rune_balance(id, balance.n(), &mut buffer);
 
-        if let Some(sender) = self.event_sender {
-          sender.blocking_send(Event::RuneTransferred {
-            outpoint,
-            block_height: self.height,
-            txid,
-            rune_id: id,
-            amount: balance.0,
-          })?;
-        }
+        self.credit_holder(id, outpoint, &tx.output[vout].script_pubkey, balance.n())?;
+
+        self.record_activity(
+          tx_index,
+          id,
+          RuneActivityKind::Transfer,
+          balance,
+          &sources,
+          Some(outpoint),
+          txid,
+        )?;
+
+        self.event_emitter.emit(Event::RuneTransferred {
+          outpoint,
+          block_height: self.height,
+          txid,
+          rune_id: id,
+          amount: balance.0,
+        })?;
       }
 
-      self
-        .outpoint_to_balances
-        .insert(&outpoint.store(), buffer.as_slice())?;
+      self.undo_log.insert(
+        self.outpoint_to_balances,
+        &outpoint.store(),
+        buffer.as_slice(),
+      )?;
     }
 
     // increment entries with burned runes
     for (id, amount) in burned {
       *self.burned.entry(id).or_default() += amount;
 
-      if let Some(sender) = self.event_sender {
-        sender.blocking_send(Event::RuneBurned {
-          block_height: self.height,
-          txid,
-          rune_id: id,
-          amount: amount.n(),
-        })?;
-      }
+      self.event_emitter.emit(Event::RuneBurned {
+        block_height: self.height,
+        txid,
+        rune_id: id,
+        amount: amount.n(),
+      })?;
     }
 
     Ok(())
@@ -235,7 +244,9 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
     for (rune_id, burned) in self.burned {
       let mut entry = RuneEntry::load(self.id_to_entry.get(&rune_id.store())?.unwrap().value());
       entry.burned = entry.burned.checked_add(burned.n()).unwrap();
-      self.id_to_entry.insert(&rune_id.store(), entry.store())?;
+      self
+        .undo_log
+        .insert(self.id_to_entry, &rune_id.store(), entry.store())?;
     }
 
     Ok(())
@@ -248,17 +259,21 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
     id: RuneId,
     rune: Rune,
   ) -> Result {
-    self.rune_to_id.insert(rune.store(), id.store())?;
     self
-      .transaction_id_to_rune
-      .insert(&txid.store(), rune.store())?;
+      .undo_log
+      .insert(self.rune_to_id, rune.store(), id.store())?;
+    self
+      .undo_log
+      .insert(self.transaction_id_to_rune, &txid.store(), rune.store())?;
 
     let number = self.runes;
     self.runes += 1;
 
-    self
-      .statistic_to_count
-      .insert(&Statistic::Runes.into(), self.runes)?;
+    self.undo_log.insert(
+      self.statistic_to_count,
+      &Statistic::Runes.into(),
+      self.runes,
+    )?;
 
     let entry = match artifact {
       Artifact::Cenotaph(_) => RuneEntry {
@@ -306,15 +321,15 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
       }
     };
 
-    self.id_to_entry.insert(id.store(), entry.store())?;
+    self
+      .undo_log
+      .insert(self.id_to_entry, id.store(), entry.store())?;
 
-    if let Some(sender) = self.event_sender {
-      sender.blocking_send(Event::RuneEtched {
-        block_height: self.height,
-        txid,
-        rune_id: id,
-      })?;
-    }
+    self.event_emitter.emit(Event::RuneEtched {
+      block_height: self.height,
+      txid,
+      rune_id: id,
+    })?;
 
     let inscription_id = InscriptionId { txid, index: 0 };
 
@@ -322,9 +337,120 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
       .inscription_id_to_sequence_number
       .get(&inscription_id.store())?
     {
-      self
-        .sequence_number_to_rune_id
-        .insert(sequence_number.value(), id.store())?;
+      self.undo_log.insert(
+        self.sequence_number_to_rune_id,
+        sequence_number.value(),
+        id.store(),
+      )?;
+    }
+
+    Ok(())
+  }
+
+  fn credit_holder(
+    &mut self,
+    id: RuneId,
+    outpoint: OutPoint,
+    script_pubkey: &Script,
+    balance: u128,
+  ) -> Result {
+    self.undo_log.insert_multimap(
+      self.id_to_outpoint_balance,
+      id.store(),
+      (balance, outpoint.store()),
+    )?;
+
+    if self.spent_script_pubkeys.is_some() {
+      self.update_script_pubkey_balance(id, script_pubkey.as_bytes(), |old| old + balance)?;
+    }
+
+    if let Some(address_history) = self.address_history.as_mut() {
+      *address_history
+        .entry(outpoint.txid, script_pubkey.as_bytes())
+        .runes_received
+        .entry(id)
+        .or_default() += balance;
+    }
+
+    Ok(())
+  }
+
+  fn debit_holder(&mut self, txid: Txid, id: RuneId, outpoint: OutPoint, balance: u128) -> Result {
+    self.undo_log.remove_multimap(
+      self.id_to_outpoint_balance,
+      id.store(),
+      (balance, outpoint.store()),
+    )?;
+
+    if let Some(spent_script_pubkeys) = self.spent_script_pubkeys {
+      let script_pubkey = spent_script_pubkeys
+        .get(&outpoint)
+        .ok_or_else(|| anyhow!("script pubkey for spent output {outpoint} not found"))?;
+
+      self.update_script_pubkey_balance(id, script_pubkey.as_bytes(), |old| old - balance)?;
+
+      if let Some(address_history) = self.address_history.as_mut() {
+        *address_history
+          .entry(txid, script_pubkey.as_bytes())
+          .runes_sent
+          .entry(id)
+          .or_default() += balance;
+      }
+    }
+
+    Ok(())
+  }
+
+  fn update_script_pubkey_balance(
+    &mut self,
+    id: RuneId,
+    script_pubkey: &[u8],
+    update: impl FnOnce(u128) -> u128,
+  ) -> Result {
+    let old = self
+      .id_and_script_pubkey_to_balance
+      .get((id.store(), script_pubkey))?
+      .map(|balance| balance.value())
+      .unwrap_or_default();
+
+    let new = update(old);
+
+    if old > 0 {
+      self.undo_log.remove_multimap(
+        self.id_to_script_pubkey_balance,
+        id.store(),
+        (old, script_pubkey),
+      )?;
+    }
+
+    if new > 0 {
+      self.undo_log.insert(
+        self.id_and_script_pubkey_to_balance,
+        (id.store(), script_pubkey),
+        new,
+      )?;
+
+      self.undo_log.insert(
+        self.script_pubkey_and_id_to_balance,
+        (script_pubkey, id.store()),
+        new,
+      )?;
+
+      self.undo_log.insert_multimap(
+        self.id_to_script_pubkey_balance,
+        id.store(),
+        (new, script_pubkey),
+      )?;
+    } else {
+      self.undo_log.remove(
+        self.id_and_script_pubkey_to_balance,
+        (id.store(), script_pubkey),
+      )?;
+
+      self.undo_log.remove(
+        self.script_pubkey_and_id_to_balance,
+        (script_pubkey, id.store()),
+      )?;
     }
 
     Ok(())
@@ -363,9 +489,11 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
         .map(|entry| entry.value())
         .unwrap_or_default();
 
-      self
-        .statistic_to_count
-        .insert(&Statistic::ReservedRunes.into(), reserved_runes + 1)?;
+      self.undo_log.insert(
+        self.statistic_to_count,
+        &Statistic::ReservedRunes.into(),
+        reserved_runes + 1,
+      )?;
 
       Rune::reserved(self.height.into(), tx_index)
     };
@@ -394,11 +522,48 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
 
     rune_entry.mints += 1;
 
-    self.id_to_entry.insert(&id.store(), rune_entry.store())?;
+    self
+      .undo_log
+      .insert(self.id_to_entry, &id.store(), rune_entry.store())?;
 
     Ok(Some(Lot(amount)))
   }
 
+  fn record_activity(
+    &mut self,
+    tx_index: u32,
+    id: RuneId,
+    kind: RuneActivityKind,
+    amount: Lot,
+    sources: &HashMap<RuneId, Vec<OutPoint>>,
+    output: Option<OutPoint>,
+    txid: Txid,
+  ) -> Result {
+    if !self.index_activity {
+      return Ok(());
+    }
+
+    let n = self.tx_activity_count;
+
+    self.tx_activity_count += 1;
+
+    self.undo_log.insert(
+      self.rune_activity_id_to_entry,
+      (id.store(), self.height, tx_index, n),
+      RuneActivityEntry {
+        amount: amount.n(),
+        height: self.height,
+        inputs: sources.get(&id).cloned().unwrap_or_default(),
+        kind,
+        output,
+        txid,
+      }
+      .store(),
+    )?;
+
+    Ok(())
+  }
+
   fn tx_commits_to_rune(&self, tx: &Transaction, rune: Rune) -> Result<bool> {
     let commitment = rune.commitment();
 
@@ -424,20 +589,15 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
           continue;
         }
 
-        let Some(tx_info) = self
-          .client
-          .get_raw_transaction_info(&input.previous_output.txid, None)
-          .into_option()?
-        else {
+        let Some(commit_tx) = self.block_source.transaction(input.previous_output.txid)? else {
           panic!(
             "can't get input transaction: {}",
             input.previous_output.txid
           );
         };
 
-        let taproot = tx_info.vout[input.previous_output.vout.into_usize()]
-          .script_pub_key
-          .script()?
+        let taproot = commit_tx.output[input.previous_output.vout.into_usize()]
+          .script_pubkey
           .is_p2tr();
 
         if !taproot {
@@ -445,17 +605,11 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
         }
 
         let commit_tx_height = self
-          .client
-          .get_block_header_info(&tx_info.blockhash.unwrap())
-          .into_option()?
-          .unwrap()
-          .height;
-
-        let confirmations = self
-          .height
-          .checked_sub(commit_tx_height.try_into().unwrap())
-          .unwrap()
-          + 1;
+          .block_source
+          .transaction_height(input.previous_output.txid)?
+          .unwrap();
+
+        let confirmations = self.height.checked_sub(commit_tx_height).unwrap() + 1;
 
         if confirmations >= Runestone::COMMIT_CONFIRMATIONS.into() {
           return Ok(true);
@@ -466,26 +620,45 @@ impl<'a, 'tx, 'client> RuneUpdater<'a, 'tx, 'client> {
     Ok(false)
   }
 
-  fn unallocated(&mut self, tx: &Transaction) -> Result<HashMap<RuneId, Lot>> {
+  fn unallocated(
+    &mut self,
+    tx: &Transaction,
+    txid: Txid,
+  ) -> Result<(HashMap<RuneId, Lot>, HashMap<RuneId, Vec<OutPoint>>)> {
     // map of rune ID to un-allocated balance of that rune
     let mut unallocated: HashMap<RuneId, Lot> = HashMap::new();
 
+    // map of rune ID to the inputs that contained that rune
+    let mut sources: HashMap<RuneId, Vec<OutPoint>> = HashMap::new();
+
     // increment unallocated runes with the runes in tx inputs
     for input in &tx.input {
+      let mut balances = Vec::new();
+
       if let Some(guard) = self
-        .outpoint_to_balances
-        .remove(&input.previous_output.store())?
+        .undo_log
+        .remove(self.outpoint_to_balances, &input.previous_output.store())?
       {
         let buffer = guard.value();
         let mut i = 0;
         while i < buffer.len() {
           let ((id, balance), len) = Index::decode_rune_balance(&buffer[i..]).unwrap();
           i += len;
-          *unallocated.entry(id).or_default() += balance;
+          balances.push((id, balance));
+        }
+      }
+
+      for (id, balance) in balances {
+        *unallocated.entry(id).or_default() += balance;
+
+        if self.index_activity {
+          sources.entry(id).or_default().push(input.previous_output);
         }
+
+        self.debit_holder(txid, id, input.previous_output, balance)?;
       }
     }
 
-    Ok(unallocated)
+    Ok((unallocated, sources))
   }
 }
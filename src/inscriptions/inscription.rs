@@ -2,12 +2,17 @@ use {
   super::*,
   anyhow::ensure,
   bitcoin::blockdata::opcodes,
-  brotli::enc::{writer::CompressorWriter, BrotliEncoderParams},
+  brotli::{
+    enc::{writer::CompressorWriter, BrotliEncoderParams},
+    Decompressor,
+  },
   http::header::HeaderValue,
   io::Write,
   std::str,
 };
 
+const MAX_DECODED_BODY_SIZE: u64 = 16 * 1024 * 1024;
+
 #[derive(Debug, PartialEq, Clone, Serialize, Deserialize, Eq, Default)]
 pub struct Inscription {
   pub body: Option<Vec<u8>>,
@@ -226,6 +231,34 @@ impl Inscription {
     Some(self.body()?.len())
   }
 
+  /// SHA-256 of the body after undoing its content encoding. Bodies with an
+  /// encoding other than `br`, which fail to decode, or which decode to more
+  /// than `MAX_DECODED_BODY_SIZE` bytes are not hashed.
+  pub(crate) fn content_hash(&self) -> Option<[u8; 32]> {
+    let body = self.body()?;
+
+    let Some(content_encoding) = &self.content_encoding else {
+      return Some(sha256::Hash::hash(body).to_byte_array());
+    };
+
+    if content_encoding.as_slice() != b"br" {
+      return None;
+    }
+
+    let mut decoded = Vec::new();
+
+    Decompressor::new(body, 4096)
+      .take(MAX_DECODED_BODY_SIZE + 1)
+      .read_to_end(&mut decoded)
+      .ok()?;
+
+    if decoded.len() as u64 > MAX_DECODED_BODY_SIZE {
+      return None;
+    }
+
+    Some(sha256::Hash::hash(&decoded).to_byte_array())
+  }
+
   pub fn content_type(&self) -> Option<&str> {
     str::from_utf8(self.content_type.as_ref()?).ok()
   }
@@ -682,6 +715,58 @@ mod tests {
     );
   }
 
+  #[test]
+  fn content_hash_is_hash_of_decoded_body() {
+    let body = b"foo".repeat(100);
+
+    let mut compressed = Vec::new();
+
+    CompressorWriter::new(&mut compressed, 4096, 11, 22)
+      .write_all(&body)
+      .unwrap();
+
+    let hash = sha256::Hash::hash(&body).to_byte_array();
+
+    assert_eq!(
+      Inscription {
+        body: Some(body.clone()),
+        ..default()
+      }
+      .content_hash(),
+      Some(hash),
+    );
+
+    assert_eq!(
+      Inscription {
+        body: Some(compressed.clone()),
+        content_encoding: Some("br".into()),
+        ..default()
+      }
+      .content_hash(),
+      Some(hash),
+    );
+
+    assert_eq!(
+      Inscription {
+        body: Some(compressed),
+        content_encoding: Some("gzip".into()),
+        ..default()
+      }
+      .content_hash(),
+      None,
+    );
+
+    assert_eq!(
+      Inscription {
+        body: Some(body),
+        content_encoding: Some("br".into()),
+        ..default()
+      }
+      .content_hash(),
+      None,
+    );
+  }
+
   #[test]
   fn pointer_decode() {
     assert_eq!(
@@ -108,6 +108,38 @@ impl Media {
     ("video/webm",                  GENERIC, Video,            &["webm"]),
   ];
 
+  pub(crate) const KINDS: &'static [&'static str] = &[
+    "audio", "code", "font", "iframe", "image", "markdown", "model", "pdf", "text", "unknown",
+    "video",
+  ];
+
+  pub(crate) fn kind(self) -> &'static str {
+    match self {
+      Audio => "audio",
+      Code(_) => "code",
+      Font => "font",
+      Iframe => "iframe",
+      Image(_) => "image",
+      Markdown => "markdown",
+      Model => "model",
+      Pdf => "pdf",
+      Text => "text",
+      Unknown => "unknown",
+      Video => "video",
+    }
+  }
+
+  /// Strip parameters and normalize case, so that `text/plain;charset=utf-8`
+  /// and `TEXT/PLAIN` are both indexed as `text/plain`.
+  pub(crate) fn normalize_content_type(content_type: &str) -> String {
+    content_type
+      .split(';')
+      .next()
+      .unwrap_or_default()
+      .trim()
+      .to_ascii_lowercase()
+  }
+
   pub(crate) fn content_type_for_path(
     path: &Path,
   ) -> Result<(&'static str, BrotliEncoderMode), Error> {
@@ -228,4 +260,28 @@ mod tests {
       }
     }
   }
+
+  #[test]
+  fn kinds_are_sorted_and_complete() {
+    assert!(Media::KINDS.windows(2).all(|w| w[0] < w[1]));
+
+    for (_, _, media, _) in Media::TABLE {
+      assert!(Media::KINDS.contains(&media.kind()));
+    }
+
+    assert!(Media::KINDS.contains(&Media::Unknown.kind()));
+  }
+
+  #[test]
+  fn normalize_content_type() {
+    assert_eq!(
+      Media::normalize_content_type("text/plain;charset=utf-8"),
+      "text/plain"
+    );
+    assert_eq!(
+      Media::normalize_content_type(" Image/SVG+XML ; foo=bar"),
+      "image/svg+xml"
+    );
+    assert_eq!(Media::normalize_content_type(""), "");
+  }
 }
@@ -40,7 +40,7 @@ use {
     },
     consensus::{self, Decodable, Encodable},
     hash_types::{BlockHash, TxMerkleNode},
-    hashes::Hash,
+    hashes::{sha256, Hash},
     policy::MAX_STANDARD_TX_WEIGHT,
     script,
     transaction::Version,
@@ -80,7 +80,7 @@ use {
     str::FromStr,
     sync::{
       atomic::{self, AtomicBool},
-      Arc, Mutex,
+      Arc, Mutex, RwLock,
     },
     thread,
     time::{Duration, Instant, SystemTime},
@@ -92,7 +92,7 @@ use {
 pub use self::{
   chain::Chain,
   fee_rate::FeeRate,
-  index::{Index, RuneEntry},
+  index::{Index, InscriptionFilter, RuneActivityEntry, RuneActivityKind, RuneEntry},
   inscriptions::{Envelope, Inscription, InscriptionId},
   object::Object,
   options::Options,
@@ -118,6 +118,7 @@ pub mod index;
 mod inscriptions;
 mod into_usize;
 mod macros;
+mod metrics;
 mod object;
 pub mod options;
 pub mod outgoing;
@@ -129,6 +130,7 @@ pub mod subcommand;
 mod tally;
 pub mod templates;
 pub mod wallet;
+mod zmq;
 
 type Result<T = (), E = Error> = std::result::Result<T, E>;
 type SnafuResult<T = (), E = SnafuError> = std::result::Result<T, E>;
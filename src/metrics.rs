@@ -0,0 +1,155 @@
+use {super::*, std::fmt::Write};
+
+/// Upper bounds, in seconds, of the buckets of every histogram ord exports.
+const BUCKETS: [f64; 12] = [
+  0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 10.0,
+];
+
+/// Distribution of durations in the Prometheus histogram model, with
+/// cumulative bucket counts computed when exported.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Histogram {
+  buckets: [u64; BUCKETS.len()],
+  count: u64,
+  sum: f64,
+}
+
+impl Histogram {
+  pub(crate) fn observe(&mut self, duration: Duration) {
+    let seconds = duration.as_secs_f64();
+
+    if let Some(i) = BUCKETS.iter().position(|bound| seconds <= *bound) {
+      self.buckets[i] += 1;
+    }
+
+    self.count += 1;
+    self.sum += seconds;
+  }
+}
+
+/// Writer for the Prometheus text exposition format.
+#[derive(Default)]
+pub(crate) struct Metrics {
+  buffer: String,
+}
+
+impl Metrics {
+  /// Writes the `HELP` and `TYPE` lines that precede the samples of a metric.
+  pub(crate) fn header(&mut self, name: &str, kind: &str, help: &str) {
+    writeln!(self.buffer, "# HELP {name} {help}").unwrap();
+    writeln!(self.buffer, "# TYPE {name} {kind}").unwrap();
+  }
+
+  pub(crate) fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+    self.buffer.push_str(name);
+
+    if !labels.is_empty() {
+      self.buffer.push('{');
+
+      for (i, (label, value)) in labels.iter().enumerate() {
+        if i > 0 {
+          self.buffer.push(',');
+        }
+
+        write!(self.buffer, "{label}=\"").unwrap();
+
+        for c in value.chars() {
+          match c {
+            '\\' => self.buffer.push_str("\\\\"),
+            '"' => self.buffer.push_str("\\\""),
+            '\n' => self.buffer.push_str("\\n"),
+            c => self.buffer.push(c),
+          }
+        }
+
+        self.buffer.push('"');
+      }
+
+      self.buffer.push('}');
+    }
+
+    writeln!(self.buffer, " {value}").unwrap();
+  }
+
+  pub(crate) fn counter(&mut self, name: &str, help: &str, value: impl Display) {
+    self.header(name, "counter", help);
+    self.sample(name, &[], value);
+  }
+
+  pub(crate) fn gauge(&mut self, name: &str, help: &str, value: impl Display) {
+    self.header(name, "gauge", help);
+    self.sample(name, &[], value);
+  }
+
+  /// Writes the samples of a histogram. The header must be written first.
+  pub(crate) fn histogram(&mut self, name: &str, labels: &[(&str, &str)], histogram: &Histogram) {
+    let mut cumulative = 0;
+
+    for (bound, count) in BUCKETS.iter().zip(histogram.buckets) {
+      cumulative += count;
+      let le = bound.to_string();
+      self.sample(
+        &format!("{name}_bucket"),
+        &[labels, &[("le", &le)]].concat(),
+        cumulative,
+      );
+    }
+
+    self.sample(
+      &format!("{name}_bucket"),
+      &[labels, &[("le", "+Inf")]].concat(),
+      histogram.count,
+    );
+    self.sample(&format!("{name}_sum"), labels, histogram.sum);
+    self.sample(&format!("{name}_count"), labels, histogram.count);
+  }
+}
+
+impl Display for Metrics {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    f.write_str(&self.buffer)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn histogram_buckets_are_cumulative() {
+    let mut histogram = Histogram::default();
+    histogram.observe(Duration::from_millis(2));
+    histogram.observe(Duration::from_millis(20));
+    histogram.observe(Duration::from_secs(60));
+
+    let mut metrics = Metrics::default();
+    metrics.histogram("duration_seconds", &[("route", "/")], &histogram);
+
+    let metrics = metrics.to_string();
+
+    assert!(metrics.contains("duration_seconds_bucket{route=\"/\",le=\"0.001\"} 0\n"));
+    assert!(metrics.contains("duration_seconds_bucket{route=\"/\",le=\"0.0025\"} 1\n"));
+    assert!(metrics.contains("duration_seconds_bucket{route=\"/\",le=\"0.025\"} 2\n"));
+    assert!(metrics.contains("duration_seconds_bucket{route=\"/\",le=\"10\"} 2\n"));
+    assert!(metrics.contains("duration_seconds_bucket{route=\"/\",le=\"+Inf\"} 3\n"));
+    assert!(metrics.contains("duration_seconds_sum{route=\"/\"} 60.022\n"));
+    assert!(metrics.contains("duration_seconds_count{route=\"/\"} 3\n"));
+  }
+
+  #[test]
+  fn label_values_are_escaped() {
+    let mut metrics = Metrics::default();
+    metrics.sample("foo", &[("bar", "a\"b\\c\nd")], 1);
+    assert_eq!(metrics.to_string(), "foo{bar=\"a\\\"b\\\\c\\nd\"} 1\n");
+  }
+
+  #[test]
+  fn counter() {
+    let mut metrics = Metrics::default();
+    metrics.counter("ord_foo_total", "Number of foos.", 7);
+    assert_eq!(
+      metrics.to_string(),
+      "# HELP ord_foo_total Number of foos.\n# TYPE ord_foo_total counter\nord_foo_total 7\n",
+    );
+  }
+}
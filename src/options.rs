@@ -21,10 +21,25 @@ pub struct Options {
     help = "Authenticate to Bitcoin Core RPC as <BITCOIN_RPC_USERNAME>."
   )]
   pub(crate) bitcoin_rpc_username: Option<String>,
+  #[arg(
+    long,
+    help = "Subscribe to Bitcoin Core ZMQ block notifications at <BITCOIN_ZMQ_URL>."
+  )]
+  pub(crate) bitcoin_zmq_url: Option<String>,
   #[arg(long, help = "Max <N> requests in flight. [default: 12]")]
   pub(crate) bitcoin_rpc_limit: Option<u32>,
+  #[arg(
+    long,
+    help = "Read blocks from Bitcoin Core block files in <BITCOIN_DATA_DIR> during initial sync."
+  )]
+  pub(crate) block_files: bool,
   #[arg(long = "chain", value_enum, help = "Use <CHAIN>. [default: mainnet]")]
   pub(crate) chain_argument: Option<Chain>,
+  #[arg(
+    long,
+    help = "Only create savepoints within <CHAIN_TIP_DISTANCE> blocks of the chain tip. [default: 21]"
+  )]
+  pub(crate) chain_tip_distance: Option<u32>,
   #[arg(
     long,
     help = "Commit to index every <COMMIT_INTERVAL> blocks. [default: 5000]"
@@ -38,10 +53,20 @@ pub struct Options {
   pub(crate) cookie_file: Option<PathBuf>,
   #[arg(long, alias = "datadir", help = "Store index in <DATA_DIR>.")]
   pub(crate) data_dir: Option<PathBuf>,
+  #[arg(
+    long,
+    help = "Fetch blocks, transactions, and the mempool from the Esplora REST API at <ESPLORA_URL> instead of Bitcoin Core RPC. Block info, which needs block stats, is only available from Bitcoin Core."
+  )]
+  pub(crate) esplora_url: Option<String>,
   #[arg(long, help = "Limit index to <HEIGHT_LIMIT> blocks.")]
   pub(crate) height_limit: Option<u32>,
   #[arg(long, help = "Use index at <INDEX>.")]
   pub(crate) index: Option<PathBuf>,
+  #[arg(
+    long,
+    help = "Track the transaction history of addresses. Requires `--index-addresses`."
+  )]
+  pub(crate) index_address_history: bool,
   #[arg(long, help = "Track unspent output addresses.")]
   pub(crate) index_addresses: bool,
   #[arg(
@@ -49,14 +74,35 @@ pub struct Options {
     help = "Set index cache size to <INDEX_CACHE_SIZE> bytes. [default: 1/4 available RAM]"
   )]
   pub(crate) index_cache_size: Option<usize>,
+  #[arg(long, help = "Track SHA-256 hashes of inscription content.")]
+  pub(crate) index_content_hashes: bool,
+  #[arg(long, help = "Track inscription content types and media kinds.")]
+  pub(crate) index_content_types: bool,
+  #[arg(long, help = "Store index events in a replayable log.")]
+  pub(crate) index_events: bool,
+  #[arg(long, help = "Index text inscription bodies for full-text search.")]
+  pub(crate) index_full_text: bool,
+  #[arg(long, help = "Track transfer history of inscriptions.")]
+  pub(crate) index_inscription_history: bool,
+  #[arg(long, help = "Track inscription metaprotocols.")]
+  pub(crate) index_metaprotocols: bool,
+  #[arg(long, help = "Track rune mints, transfers, and burns.")]
+  pub(crate) index_rune_activity: bool,
   #[arg(long, help = "Track location of runes.")]
   pub(crate) index_runes: bool,
   #[arg(long, help = "Track location of all satoshis.")]
   pub(crate) index_sats: bool,
   #[arg(long, help = "Store transactions in index.")]
   pub(crate) index_transactions: bool,
+  #[arg(long, help = "Store undo log for rolling back index to any height.")]
+  pub(crate) index_undo_log: bool,
   #[arg(long, help = "Run in integration test mode.")]
   pub(crate) integration_test: bool,
+  #[arg(
+    long,
+    help = "Keep <MAX_SAVEPOINTS> savepoints for recovering from reorgs. [default: 2]"
+  )]
+  pub(crate) max_savepoints: Option<u32>,
   #[clap(long, short, long, help = "Specify output format. [default: json]")]
   pub(crate) format: Option<OutputFormat>,
   #[arg(
@@ -66,6 +112,16 @@ pub struct Options {
     help = "Do not index inscriptions."
   )]
   pub(crate) no_index_inscriptions: bool,
+  #[arg(
+    long,
+    help = "Roll back to the oldest savepoint and re-sync on unrecoverable reorgs."
+  )]
+  pub(crate) rollback_unrecoverable_reorgs: bool,
+  #[arg(
+    long,
+    help = "Create a savepoint every <SAVEPOINT_INTERVAL> blocks. [default: 10]"
+  )]
+  pub(crate) savepoint_interval: Option<u32>,
   #[arg(
     long,
     help = "Require basic HTTP authentication with <SERVER_PASSWORD>. Credentials are sent in cleartext. Consider using authentication in conjunction with HTTPS."
@@ -76,6 +132,11 @@ pub struct Options {
     help = "Require basic HTTP authentication with <SERVER_USERNAME>. Credentials are sent in cleartext. Consider using authentication in conjunction with HTTPS."
   )]
   pub(crate) server_username: Option<String>,
+  #[arg(
+    long,
+    help = "Only keep undo logs for the last <UNDO_LOG_DEPTH> blocks, limiting how far the index can be rolled back without a savepoint. [default: unlimited]"
+  )]
+  pub(crate) undo_log_depth: Option<u32>,
   #[arg(long, short, help = "Use regtest. Equivalent to `--chain regtest`.")]
   pub(crate) regtest: bool,
   #[arg(long, short, help = "Use signet. Equivalent to `--chain signet`.")]
@@ -6108,4 +6108,104 @@ mod tests {
         [],
       );
   }
+
+  #[test]
+  fn mints_and_burns_are_recorded_in_rune_activity() {
+    let context = Context::builder()
+      .args(["--index-runes", "--index-rune-activity"])
+      .build();
+
+    let (_, id) = context.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(Rune(RUNE)),
+          terms: Some(Terms {
+            amount: Some(1000),
+            cap: Some(100),
+            ..default()
+          }),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+    );
+
+    let mint = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0, Witness::new())],
+      op_return: Some(
+        Runestone {
+          mint: Some(id),
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let burn = context.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(
+        context.index.block_count().unwrap().into_usize() - 1,
+        1,
+        0,
+        Witness::new(),
+      )],
+      op_return: Some(
+        Runestone {
+          pointer: Some(10),
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    context.mine_blocks(1);
+
+    let height = context.index.block_count().unwrap() - 1;
+
+    pretty_assert_eq!(
+      context
+        .index
+        .get_rune_activity_paginated(id, 10, 0)
+        .unwrap(),
+      (
+        vec![
+          RuneActivityEntry {
+            amount: 1000,
+            height,
+            inputs: vec![OutPoint {
+              txid: mint,
+              vout: 0,
+            }],
+            kind: RuneActivityKind::Burn,
+            output: None,
+            txid: burn,
+          },
+          RuneActivityEntry {
+            amount: 1000,
+            height: height - 1,
+            inputs: Vec::new(),
+            kind: RuneActivityKind::Transfer,
+            output: Some(OutPoint {
+              txid: mint,
+              vout: 0,
+            }),
+            txid: mint,
+          },
+          RuneActivityEntry {
+            amount: 1000,
+            height: height - 1,
+            inputs: Vec::new(),
+            kind: RuneActivityKind::Mint,
+            output: None,
+            txid: mint,
+          },
+        ],
+        false
+      )
+    );
+  }
 }
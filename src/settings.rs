@@ -8,26 +8,43 @@ pub struct Settings {
   bitcoin_rpc_password: Option<String>,
   bitcoin_rpc_url: Option<String>,
   bitcoin_rpc_username: Option<String>,
+  bitcoin_zmq_url: Option<String>,
+  block_files: bool,
   chain: Option<Chain>,
+  chain_tip_distance: Option<u32>,
   commit_interval: Option<usize>,
   config: Option<PathBuf>,
   config_dir: Option<PathBuf>,
   cookie_file: Option<PathBuf>,
   data_dir: Option<PathBuf>,
+  esplora_url: Option<String>,
   height_limit: Option<u32>,
   hidden: Option<HashSet<InscriptionId>>,
   http_port: Option<u16>,
   index: Option<PathBuf>,
+  index_address_history: bool,
   index_addresses: bool,
   index_cache_size: Option<usize>,
+  index_content_hashes: bool,
+  index_content_types: bool,
+  index_events: bool,
+  index_full_text: bool,
+  index_inscription_history: bool,
+  index_metaprotocols: bool,
+  index_rune_activity: bool,
   index_runes: bool,
   index_sats: bool,
   index_transactions: bool,
+  index_undo_log: bool,
   integration_test: bool,
+  max_savepoints: Option<u32>,
   no_index_inscriptions: bool,
+  rollback_unrecoverable_reorgs: bool,
+  savepoint_interval: Option<u32>,
   server_password: Option<String>,
   server_url: Option<String>,
   server_username: Option<String>,
+  undo_log_depth: Option<u32>,
 }
 
 impl Settings {
@@ -103,6 +120,18 @@ impl Settings {
       _ => {}
     };
 
+    if settings.max_savepoints == Some(0) {
+      bail!("max savepoints must be greater than zero");
+    }
+
+    if settings.savepoint_interval == Some(0) {
+      bail!("savepoint interval must be greater than zero");
+    }
+
+    if settings.undo_log_depth == Some(0) {
+      bail!("undo log depth must be greater than zero");
+    }
+
     Ok(settings)
   }
 
@@ -113,12 +142,16 @@ impl Settings {
       bitcoin_rpc_password: self.bitcoin_rpc_password.or(source.bitcoin_rpc_password),
       bitcoin_rpc_url: self.bitcoin_rpc_url.or(source.bitcoin_rpc_url),
       bitcoin_rpc_username: self.bitcoin_rpc_username.or(source.bitcoin_rpc_username),
+      bitcoin_zmq_url: self.bitcoin_zmq_url.or(source.bitcoin_zmq_url),
+      block_files: self.block_files || source.block_files,
       chain: self.chain.or(source.chain),
+      chain_tip_distance: self.chain_tip_distance.or(source.chain_tip_distance),
       commit_interval: self.commit_interval.or(source.commit_interval),
       config: self.config.or(source.config),
       config_dir: self.config_dir.or(source.config_dir),
       cookie_file: self.cookie_file.or(source.cookie_file),
       data_dir: self.data_dir.or(source.data_dir),
+      esplora_url: self.esplora_url.or(source.esplora_url),
       height_limit: self.height_limit.or(source.height_limit),
       hidden: Some(
         self
@@ -131,16 +164,30 @@ impl Settings {
       ),
       http_port: self.http_port.or(source.http_port),
       index: self.index.or(source.index),
+      index_address_history: self.index_address_history || source.index_address_history,
       index_addresses: self.index_addresses || source.index_addresses,
       index_cache_size: self.index_cache_size.or(source.index_cache_size),
+      index_content_hashes: self.index_content_hashes || source.index_content_hashes,
+      index_content_types: self.index_content_types || source.index_content_types,
+      index_events: self.index_events || source.index_events,
+      index_full_text: self.index_full_text || source.index_full_text,
+      index_inscription_history: self.index_inscription_history || source.index_inscription_history,
+      index_metaprotocols: self.index_metaprotocols || source.index_metaprotocols,
+      index_rune_activity: self.index_rune_activity || source.index_rune_activity,
       index_runes: self.index_runes || source.index_runes,
       index_sats: self.index_sats || source.index_sats,
       index_transactions: self.index_transactions || source.index_transactions,
+      index_undo_log: self.index_undo_log || source.index_undo_log,
       integration_test: self.integration_test || source.integration_test,
+      max_savepoints: self.max_savepoints.or(source.max_savepoints),
       no_index_inscriptions: self.no_index_inscriptions || source.no_index_inscriptions,
+      rollback_unrecoverable_reorgs: self.rollback_unrecoverable_reorgs
+        || source.rollback_unrecoverable_reorgs,
+      savepoint_interval: self.savepoint_interval.or(source.savepoint_interval),
       server_password: self.server_password.or(source.server_password),
       server_url: self.server_url.or(source.server_url),
       server_username: self.server_username.or(source.server_username),
+      undo_log_depth: self.undo_log_depth.or(source.undo_log_depth),
     }
   }
 
@@ -151,31 +198,48 @@ impl Settings {
       bitcoin_rpc_password: options.bitcoin_rpc_password,
       bitcoin_rpc_url: options.bitcoin_rpc_url,
       bitcoin_rpc_username: options.bitcoin_rpc_username,
+      bitcoin_zmq_url: options.bitcoin_zmq_url,
+      block_files: options.block_files,
       chain: options
         .signet
         .then_some(Chain::Signet)
         .or(options.regtest.then_some(Chain::Regtest))
         .or(options.testnet.then_some(Chain::Testnet))
         .or(options.chain_argument),
+      chain_tip_distance: options.chain_tip_distance,
       commit_interval: options.commit_interval,
       config: options.config,
       config_dir: options.config_dir,
       cookie_file: options.cookie_file,
       data_dir: options.data_dir,
+      esplora_url: options.esplora_url,
       height_limit: options.height_limit,
       hidden: None,
       http_port: None,
       index: options.index,
+      index_address_history: options.index_address_history,
       index_addresses: options.index_addresses,
       index_cache_size: options.index_cache_size,
+      index_content_hashes: options.index_content_hashes,
+      index_content_types: options.index_content_types,
+      index_events: options.index_events,
+      index_full_text: options.index_full_text,
+      index_inscription_history: options.index_inscription_history,
+      index_metaprotocols: options.index_metaprotocols,
+      index_rune_activity: options.index_rune_activity,
       index_runes: options.index_runes,
       index_sats: options.index_sats,
       index_transactions: options.index_transactions,
+      index_undo_log: options.index_undo_log,
       integration_test: options.integration_test,
+      max_savepoints: options.max_savepoints,
       no_index_inscriptions: options.no_index_inscriptions,
+      rollback_unrecoverable_reorgs: options.rollback_unrecoverable_reorgs,
+      savepoint_interval: options.savepoint_interval,
       server_password: options.server_password,
       server_url: None,
       server_username: options.server_username,
+      undo_log_depth: options.undo_log_depth,
     }
   }
 
@@ -244,26 +308,43 @@ impl Settings {
       bitcoin_rpc_password: get_string("BITCOIN_RPC_PASSWORD"),
       bitcoin_rpc_url: get_string("BITCOIN_RPC_URL"),
       bitcoin_rpc_username: get_string("BITCOIN_RPC_USERNAME"),
+      bitcoin_zmq_url: get_string("BITCOIN_ZMQ_URL"),
+      block_files: get_bool("BLOCK_FILES"),
       chain: get_chain("CHAIN")?,
+      chain_tip_distance: get_u32("CHAIN_TIP_DISTANCE")?,
       commit_interval: get_usize("COMMIT_INTERVAL")?,
       config: get_path("CONFIG"),
       config_dir: get_path("CONFIG_DIR"),
       cookie_file: get_path("COOKIE_FILE"),
       data_dir: get_path("DATA_DIR"),
+      esplora_url: get_string("ESPLORA_URL"),
       height_limit: get_u32("HEIGHT_LIMIT")?,
       hidden: inscriptions("HIDDEN")?,
       http_port: get_u16("HTTP_PORT")?,
       index: get_path("INDEX"),
+      index_address_history: get_bool("INDEX_ADDRESS_HISTORY"),
       index_addresses: get_bool("INDEX_ADDRESSES"),
       index_cache_size: get_usize("INDEX_CACHE_SIZE")?,
+      index_content_hashes: get_bool("INDEX_CONTENT_HASHES"),
+      index_content_types: get_bool("INDEX_CONTENT_TYPES"),
+      index_events: get_bool("INDEX_EVENTS"),
+      index_full_text: get_bool("INDEX_FULL_TEXT"),
+      index_inscription_history: get_bool("INDEX_INSCRIPTION_HISTORY"),
+      index_metaprotocols: get_bool("INDEX_METAPROTOCOLS"),
+      index_rune_activity: get_bool("INDEX_RUNE_ACTIVITY"),
       index_runes: get_bool("INDEX_RUNES"),
       index_sats: get_bool("INDEX_SATS"),
       index_transactions: get_bool("INDEX_TRANSACTIONS"),
+      index_undo_log: get_bool("INDEX_UNDO_LOG"),
       integration_test: get_bool("INTEGRATION_TEST"),
+      max_savepoints: get_u32("MAX_SAVEPOINTS")?,
       no_index_inscriptions: get_bool("NO_INDEX_INSCRIPTIONS"),
+      rollback_unrecoverable_reorgs: get_bool("ROLLBACK_UNRECOVERABLE_REORGS"),
+      savepoint_interval: get_u32("SAVEPOINT_INTERVAL")?,
       server_password: get_string("SERVER_PASSWORD"),
       server_url: get_string("SERVER_URL"),
       server_username: get_string("SERVER_USERNAME"),
+      undo_log_depth: get_u32("UNDO_LOG_DEPTH")?,
     })
   }
 
@@ -274,26 +355,43 @@ impl Settings {
       bitcoin_rpc_url: Some(rpc_url.into()),
       bitcoin_rpc_username: None,
       bitcoin_rpc_limit: None,
+      bitcoin_zmq_url: None,
+      block_files: false,
       chain: Some(Chain::Regtest),
+      chain_tip_distance: None,
       commit_interval: None,
       config: None,
       config_dir: None,
       cookie_file: None,
       data_dir: Some(dir.into()),
+      esplora_url: None,
       height_limit: None,
       hidden: None,
       http_port: None,
       index: None,
+      index_address_history: false,
       index_addresses: true,
       index_cache_size: None,
+      index_content_hashes: false,
+      index_content_types: false,
+      index_events: false,
+      index_full_text: false,
+      index_inscription_history: false,
+      index_metaprotocols: false,
+      index_rune_activity: false,
       index_runes: true,
       index_sats: true,
       index_transactions: false,
+      index_undo_log: false,
       integration_test: false,
+      max_savepoints: None,
       no_index_inscriptions: false,
+      rollback_unrecoverable_reorgs: false,
+      savepoint_interval: None,
       server_password: None,
       server_url: Some(server_url.into()),
       server_username: None,
+      undo_log_depth: None,
     }
   }
 
@@ -341,16 +439,21 @@ impl Settings {
           .unwrap_or_else(|| format!("127.0.0.1:{}", chain.default_rpc_port())),
       ),
       bitcoin_rpc_username: self.bitcoin_rpc_username,
+      bitcoin_zmq_url: self.bitcoin_zmq_url,
+      block_files: self.block_files,
       chain: Some(chain),
+      chain_tip_distance: Some(self.chain_tip_distance.unwrap_or(21)),
       commit_interval: Some(self.commit_interval.unwrap_or(5000)),
       config: None,
       config_dir: None,
       cookie_file: Some(cookie_file),
       data_dir: Some(data_dir),
+      esplora_url: self.esplora_url,
       height_limit: self.height_limit,
       hidden: self.hidden,
       http_port: self.http_port,
       index: Some(index),
+      index_address_history: self.index_address_history,
       index_addresses: self.index_addresses,
       index_cache_size: Some(match self.index_cache_size {
         Some(index_cache_size) => index_cache_size,
@@ -360,14 +463,26 @@ impl Settings {
           usize::try_from(sys.total_memory() / 4)?
         }
       }),
+      index_content_hashes: self.index_content_hashes,
+      index_content_types: self.index_content_types,
+      index_events: self.index_events,
+      index_full_text: self.index_full_text,
+      index_inscription_history: self.index_inscription_history,
+      index_metaprotocols: self.index_metaprotocols,
+      index_rune_activity: self.index_rune_activity,
       index_runes: self.index_runes,
       index_sats: self.index_sats,
       index_transactions: self.index_transactions,
+      index_undo_log: self.index_undo_log,
       integration_test: self.integration_test,
+      max_savepoints: Some(self.max_savepoints.unwrap_or(2)),
       no_index_inscriptions: self.no_index_inscriptions,
+      rollback_unrecoverable_reorgs: self.rollback_unrecoverable_reorgs,
+      savepoint_interval: Some(self.savepoint_interval.unwrap_or(10)),
       server_password: self.server_password,
       server_url: self.server_url,
       server_username: self.server_username,
+      undo_log_depth: self.undo_log_depth,
     })
   }
 
@@ -464,6 +579,10 @@ impl Settings {
     self.chain.unwrap()
   }
 
+  pub fn chain_tip_distance(&self) -> u32 {
+    self.chain_tip_distance.unwrap()
+  }
+
   pub fn commit_interval(&self) -> usize {
     self.commit_interval.unwrap()
   }
@@ -501,6 +620,10 @@ impl Settings {
     self.data_dir.as_ref().unwrap().into()
   }
 
+  pub fn esplora_url(&self) -> Option<&str> {
+    self.esplora_url.as_deref()
+  }
+
   pub fn first_inscription_height(&self) -> u32 {
     if self.integration_test {
       0
@@ -525,6 +648,10 @@ impl Settings {
     self.index.as_ref().unwrap()
   }
 
+  pub fn index_address_history_raw(&self) -> bool {
+    self.index_address_history
+  }
+
   pub fn index_addresses_raw(&self) -> bool {
     self.index_addresses
   }
@@ -533,6 +660,34 @@ impl Settings {
     !self.no_index_inscriptions
   }
 
+  pub fn index_content_hashes_raw(&self) -> bool {
+    self.index_content_hashes
+  }
+
+  pub fn index_content_types_raw(&self) -> bool {
+    self.index_content_types
+  }
+
+  pub fn index_events_raw(&self) -> bool {
+    self.index_events
+  }
+
+  pub fn index_full_text_raw(&self) -> bool {
+    self.index_full_text
+  }
+
+  pub fn index_inscription_history_raw(&self) -> bool {
+    self.index_inscription_history
+  }
+
+  pub fn index_metaprotocols_raw(&self) -> bool {
+    self.index_metaprotocols
+  }
+
+  pub fn index_rune_activity_raw(&self) -> bool {
+    self.index_rune_activity
+  }
+
   pub fn index_runes_raw(&self) -> bool {
     self.index_runes
   }
@@ -549,10 +704,18 @@ impl Settings {
     self.index_transactions
   }
 
+  pub fn index_undo_log_raw(&self) -> bool {
+    self.index_undo_log
+  }
+
   pub fn integration_test(&self) -> bool {
     self.integration_test
   }
 
+  pub fn max_savepoints(&self) -> u32 {
+    self.max_savepoints.unwrap()
+  }
+
   pub fn is_hidden(&self, inscription_id: InscriptionId) -> bool {
     self
       .hidden
@@ -573,9 +736,34 @@ impl Settings {
     self.bitcoin_rpc_limit.unwrap()
   }
 
+  pub fn bitcoin_zmq_url(&self) -> Option<&str> {
+    self.bitcoin_zmq_url.as_deref()
+  }
+
+  pub fn blocks_dir(&self) -> Option<PathBuf> {
+    self.block_files.then(|| {
+      self
+        .chain()
+        .join_with_data_dir(self.bitcoin_data_dir.as_ref().unwrap())
+        .join("blocks")
+    })
+  }
+
+  pub fn rollback_unrecoverable_reorgs(&self) -> bool {
+    self.rollback_unrecoverable_reorgs
+  }
+
+  pub fn savepoint_interval(&self) -> u32 {
+    self.savepoint_interval.unwrap()
+  }
+
   pub fn server_url(&self) -> Option<&str> {
     self.server_url.as_deref()
   }
+
+  pub fn undo_log_depth(&self) -> Option<u32> {
+    self.undo_log_depth
+  }
 }
 
 #[cfg(test)]
@@ -638,6 +826,48 @@ mod tests {
     );
   }
 
+  #[test]
+  fn zero_savepoint_settings_are_an_error() {
+    assert_eq!(
+      Settings::merge(
+        Options {
+          max_savepoints: Some(0),
+          ..default()
+        },
+        Default::default(),
+      )
+      .unwrap_err()
+      .to_string(),
+      "max savepoints must be greater than zero"
+    );
+
+    assert_eq!(
+      Settings::merge(
+        Options {
+          savepoint_interval: Some(0),
+          ..default()
+        },
+        Default::default(),
+      )
+      .unwrap_err()
+      .to_string(),
+      "savepoint interval must be greater than zero"
+    );
+
+    assert_eq!(
+      Settings::merge(
+        Options {
+          undo_log_depth: Some(0),
+          ..default()
+        },
+        Default::default(),
+      )
+      .unwrap_err()
+      .to_string(),
+      "undo log depth must be greater than zero"
+    );
+  }
+
   #[test]
   fn auth_with_user_and_pass() {
     assert_eq!(
@@ -1005,26 +1235,43 @@ mod tests {
       ("BITCOIN_RPC_PASSWORD", "bitcoin password"),
       ("BITCOIN_RPC_URL", "url"),
       ("BITCOIN_RPC_USERNAME", "bitcoin username"),
+      ("BITCOIN_ZMQ_URL", "zmq url"),
+      ("BLOCK_FILES", "1"),
       ("CHAIN", "signet"),
+      ("CHAIN_TIP_DISTANCE", "5"),
       ("COMMIT_INTERVAL", "1"),
       ("CONFIG", "config"),
       ("CONFIG_DIR", "config dir"),
       ("COOKIE_FILE", "cookie file"),
       ("DATA_DIR", "/data/dir"),
+      ("ESPLORA_URL", "esplora url"),
       ("HEIGHT_LIMIT", "3"),
       ("HIDDEN", "6fb976ab49dcec017f1e201e84395983204ae1a7c2abf7ced0a85d692e442799i0 703e5f7c49d82aab99e605af306b9a30e991e57d42f982908a962a81ac439832i0"),
     ("HTTP_PORT", "8080"),
       ("INDEX", "index"),
       ("INDEX_CACHE_SIZE", "4"),
+      ("INDEX_ADDRESS_HISTORY", "1"),
       ("INDEX_ADDRESSES", "1"),
+      ("INDEX_CONTENT_HASHES", "1"),
+      ("INDEX_CONTENT_TYPES", "1"),
+      ("INDEX_EVENTS", "1"),
+      ("INDEX_FULL_TEXT", "1"),
+      ("INDEX_INSCRIPTION_HISTORY", "1"),
+      ("INDEX_METAPROTOCOLS", "1"),
+      ("INDEX_RUNE_ACTIVITY", "1"),
       ("INDEX_RUNES", "1"),
       ("INDEX_SATS", "1"),
       ("INDEX_TRANSACTIONS", "1"),
+      ("INDEX_UNDO_LOG", "1"),
       ("INTEGRATION_TEST", "1"),
+      ("MAX_SAVEPOINTS", "6"),
       ("NO_INDEX_INSCRIPTIONS", "1"),
+      ("ROLLBACK_UNRECOVERABLE_REORGS", "1"),
+      ("SAVEPOINT_INTERVAL", "7"),
       ("SERVER_PASSWORD", "server password"),
       ("SERVER_URL", "server url"),
       ("SERVER_USERNAME", "server username"),
+      ("UNDO_LOG_DEPTH", "8"),
     ]
     .into_iter()
     .map(|(key, value)| (key.into(), value.into()))
@@ -1038,12 +1285,16 @@ mod tests {
         bitcoin_rpc_password: Some("bitcoin password".into()),
         bitcoin_rpc_url: Some("url".into()),
         bitcoin_rpc_username: Some("bitcoin username".into()),
+        bitcoin_zmq_url: Some("zmq url".into()),
+        block_files: true,
         chain: Some(Chain::Signet),
+        chain_tip_distance: Some(5),
         commit_interval: Some(1),
         config: Some("config".into()),
         config_dir: Some("config dir".into()),
         cookie_file: Some("cookie file".into()),
         data_dir: Some("/data/dir".into()),
+        esplora_url: Some("esplora url".into()),
         height_limit: Some(3),
         hidden: Some(
           vec![
@@ -1059,16 +1310,29 @@ mod tests {
         ),
         http_port: Some(8080),
         index: Some("index".into()),
+        index_address_history: true,
         index_addresses: true,
         index_cache_size: Some(4),
+        index_content_hashes: true,
+        index_content_types: true,
+        index_events: true,
+        index_full_text: true,
+        index_inscription_history: true,
+        index_metaprotocols: true,
+        index_rune_activity: true,
         index_runes: true,
         index_sats: true,
         index_transactions: true,
+        index_undo_log: true,
         integration_test: true,
+        max_savepoints: Some(6),
         no_index_inscriptions: true,
+        rollback_unrecoverable_reorgs: true,
+        savepoint_interval: Some(7),
         server_password: Some("server password".into()),
         server_url: Some("server url".into()),
         server_username: Some("server username".into()),
+        undo_log_depth: Some(8),
       }
     );
   }
@@ -1084,23 +1348,40 @@ mod tests {
           "--bitcoin-rpc-password=bitcoin password",
           "--bitcoin-rpc-url=url",
           "--bitcoin-rpc-username=bitcoin username",
+          "--bitcoin-zmq-url=zmq url",
+          "--block-files",
           "--chain=signet",
+          "--chain-tip-distance=5",
           "--commit-interval=1",
           "--config=config",
           "--config-dir=config dir",
           "--cookie-file=cookie file",
           "--datadir=/data/dir",
+          "--esplora-url=esplora url",
           "--height-limit=3",
+          "--index-address-history",
           "--index-addresses",
           "--index-cache-size=4",
+          "--index-content-hashes",
+          "--index-content-types",
+          "--index-events",
+          "--index-full-text",
+          "--index-inscription-history",
+          "--index-metaprotocols",
+          "--index-rune-activity",
           "--index-runes",
           "--index-sats",
           "--index-transactions",
           "--index=index",
+          "--index-undo-log",
           "--integration-test",
+          "--max-savepoints=6",
           "--no-index-inscriptions",
+          "--rollback-unrecoverable-reorgs",
+          "--savepoint-interval=7",
           "--server-password=server password",
           "--server-username=server username",
+          "--undo-log-depth=8",
         ])
         .unwrap()
       ),
@@ -1110,26 +1391,43 @@ mod tests {
         bitcoin_rpc_password: Some("bitcoin password".into()),
         bitcoin_rpc_url: Some("url".into()),
         bitcoin_rpc_username: Some("bitcoin username".into()),
+        bitcoin_zmq_url: Some("zmq url".into()),
+        block_files: true,
         chain: Some(Chain::Signet),
+        chain_tip_distance: Some(5),
         commit_interval: Some(1),
         config: Some("config".into()),
         config_dir: Some("config dir".into()),
         cookie_file: Some("cookie file".into()),
         data_dir: Some("/data/dir".into()),
+        esplora_url: Some("esplora url".into()),
         height_limit: Some(3),
         hidden: None,
         http_port: None,
         index: Some("index".into()),
+        index_address_history: true,
         index_addresses: true,
         index_cache_size: Some(4),
+        index_content_hashes: true,
+        index_content_types: true,
+        index_events: true,
+        index_full_text: true,
+        index_inscription_history: true,
+        index_metaprotocols: true,
+        index_rune_activity: true,
         index_runes: true,
         index_sats: true,
         index_transactions: true,
+        index_undo_log: true,
         integration_test: true,
+        max_savepoints: Some(6),
         no_index_inscriptions: true,
+        rollback_unrecoverable_reorgs: true,
+        savepoint_interval: Some(7),
         server_password: Some("server password".into()),
         server_url: None,
         server_username: Some("server username".into()),
+        undo_log_depth: Some(8),
       }
     );
   }
@@ -1,6 +1,7 @@
 use super::*;
 
 pub mod balances;
+pub mod collection;
 pub mod decode;
 pub mod env;
 pub mod epochs;
@@ -23,6 +24,8 @@ pub mod wallets;
 pub(crate) enum Subcommand {
   #[command(about = "List all rune balances")]
   Balances,
+  #[command(subcommand, about = "Collection commands")]
+  Collection(collection::CollectionSubcommand),
   #[command(about = "Decode a transaction")]
   Decode(decode::Decode),
   #[command(about = "Start a regtest ord and bitcoind instance")]
@@ -38,7 +41,7 @@ pub(crate) enum Subcommand {
   #[command(about = "Parse a satoshi from ordinal notation")]
   Parse(parse::Parse),
   #[command(about = "List all runes")]
-  Runes,
+  Runes(runes::Runes),
   #[command(about = "Run the explorer server")]
   Server(server::Server),
   #[command(about = "Display settings")]
@@ -63,6 +66,7 @@ impl Subcommand {
   pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
     match self {
       Self::Balances => balances::run(settings),
+      Self::Collection(collection) => collection.run(settings),
       Self::Decode(decode) => decode.run(settings),
       Self::Env(env) => env.run(),
       Self::Epochs => epochs::run(),
@@ -70,12 +74,17 @@ impl Subcommand {
       Self::Index(index) => index.run(settings),
       Self::List(list) => list.run(settings),
       Self::Parse(parse) => parse.run(),
-      Self::Runes => runes::run(settings),
+      Self::Runes(runes) => runes.run(settings),
       Self::Server(server) => {
-        let index = Arc::new(Index::open(&settings)?);
+        let (event_sender, event_receiver) = tokio::sync::mpsc::channel(1024);
+        let index = Arc::new(if server.read_only {
+          Index::open_read_only(&settings)?
+        } else {
+          Index::open_with_event_sender(&settings, Some(event_sender))?
+        });
         let handle = axum_server::Handle::new();
         LISTENERS.lock().unwrap().push(handle.clone());
-        server.run(settings, index, handle)
+        server.run(settings, index, handle, event_receiver)
       }
       Self::Settings => settings::run(settings),
       Self::Subsidy(subsidy) => subsidy.run(),
@@ -0,0 +1,17 @@
+use super::*;
+
+pub mod snapshot;
+
+#[derive(Debug, Parser)]
+pub(crate) enum CollectionSubcommand {
+  #[command(about = "List the holders of a parent inscription's children")]
+  Snapshot(snapshot::Snapshot),
+}
+
+impl CollectionSubcommand {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    match self {
+      Self::Snapshot(snapshot) => snapshot.run(settings),
+    }
+  }
+}
@@ -0,0 +1,55 @@
+use super::*;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub height: u32,
+  pub holders: Vec<api::CollectionHolder>,
+  pub parent: InscriptionId,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Snapshot {
+  #[arg(long, help = "Snapshot holders as of block <HEIGHT>.")]
+  height: Option<u32>,
+  #[arg(long, help = "Snapshot holders of the children of <PARENT>.")]
+  parent: InscriptionId,
+}
+
+impl Snapshot {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let index = Index::open(&settings)?;
+
+    if self.height.is_some() {
+      ensure!(
+        index.has_inscription_history_index(),
+        "`ord collection snapshot --height` requires index created with `--index-inscription-history` flag",
+      );
+    } else {
+      ensure!(
+        index.has_address_index(),
+        "`ord collection snapshot` requires index created with `--index-addresses` flag",
+      );
+    }
+
+    index.update()?;
+
+    let block_height = index.block_height()?.unwrap_or(Height(0)).n();
+
+    if let Some(height) = self.height {
+      ensure!(
+        height <= block_height,
+        "height {height} is above index height {block_height}",
+      );
+    }
+
+    let holders = index
+      .get_collection_holders(self.parent, self.height)?
+      .ok_or_else(|| anyhow!("inscription {} not found", self.parent))?;
+
+    Ok(Some(Box::new(Output {
+      height: self.height.unwrap_or(block_height),
+      holders,
+      parent: self.parent,
+    })))
+  }
+}
@@ -1,25 +1,33 @@
 use super::*;
 
+pub mod check;
 mod export;
 pub mod info;
+pub mod rollback;
 mod update;
 
 #[derive(Debug, Parser)]
 pub(crate) enum IndexSubcommand {
+  #[command(about = "Check index tables for inconsistencies")]
+  Check(check::Check),
   #[command(about = "Write inscription numbers and ids to a tab-separated file")]
   Export(export::Export),
   #[command(about = "Print index statistics")]
   Info(info::Info),
+  #[command(about = "Roll back the index to an earlier height")]
+  Rollback(rollback::Rollback),
   #[command(about = "Update the index", alias = "run")]
-  Update,
+  Update(update::Update),
 }
 
 impl IndexSubcommand {
   pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
     match self {
+      Self::Check(check) => check.run(settings),
       Self::Export(export) => export.run(settings),
       Self::Info(info) => info.run(settings),
-      Self::Update => update::run(settings),
+      Self::Rollback(rollback) => rollback.run(settings),
+      Self::Update(update) => update.run(settings),
     }
   }
 }
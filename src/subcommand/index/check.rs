@@ -0,0 +1,21 @@
+use {super::*, crate::index::check::Violation};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Check {}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub block_count: u32,
+  pub violations: Vec<Violation>,
+}
+
+impl Check {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let index = Index::open(&settings)?;
+
+    Ok(Some(Box::new(Output {
+      block_count: index.block_count()?,
+      violations: index.check()?,
+    })))
+  }
+}
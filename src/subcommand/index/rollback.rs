@@ -0,0 +1,29 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Rollback {
+  #[arg(
+    long,
+    help = "Roll back index so that <HEIGHT> is the last indexed block"
+  )]
+  height: u32,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub blocks: u32,
+  pub height: u32,
+}
+
+impl Rollback {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let index = Index::open(&settings)?;
+
+    let blocks = index.rollback(self.height)?;
+
+    Ok(Some(Box::new(Output {
+      blocks,
+      height: self.height,
+    })))
+  }
+}
@@ -1,9 +1,84 @@
-use super::*;
+use {
+  super::*,
+  crate::index::{block_source, Readers},
+};
 
-pub(crate) fn run(settings: Settings) -> SubcommandResult {
-  let index = Index::open(&settings)?;
+#[derive(Debug, Parser)]
+pub(crate) struct Update {
+  #[arg(
+    long,
+    help = "Keep updating the index as new blocks arrive, closing it between updates so that it can be served by `ord server --read-only`."
+  )]
+  daemon: bool,
+  #[arg(
+    long,
+    default_value = "5s",
+    help = "With --daemon, check for new blocks every <POLLING_INTERVAL>."
+  )]
+  polling_interval: humantime::Duration,
+  #[arg(
+    long,
+    default_value = "1m",
+    help = "With --daemon, stop waiting for `ord server --read-only` processes to reopen the index after <READER_TIMEOUT> and update it anyway."
+  )]
+  reader_timeout: humantime::Duration,
+}
+
+impl Update {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    if !self.daemon {
+      Index::open(&settings)?.update()?;
+      return Ok(None);
+    }
+
+    let block_source = block_source::open(&settings)?;
+
+    let mut blocks = settings
+      .bitcoin_zmq_url()
+      .map(|url| {
+        zmq::Notifications::subscribe(url, vec![zmq::Topic::Block], self.polling_interval.into())
+      })
+      .transpose()?
+      .map(|notifications| notifications.listener(zmq::Topic::Block));
+
+    let readers = Readers::new(settings.index());
+
+    let mut indexed = None;
+
+    while !SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+      match block_source
+        .block_count()
+        .and_then(|height| block_source.block_hash(height))
+      {
+        Ok(Some(tip)) if indexed == Some(tip) => {}
+        Ok(_) => {
+          let generation = readers.generation()?;
+
+          if !readers.wait(generation, self.reader_timeout.into())? {
+            break;
+          }
+
+          let index = Index::open(&settings)?;
+
+          if let Err(error) = index.update_holding_snapshot() {
+            log::warn!("Updating index: {error}");
+          }
+
+          indexed = index.block_hash(None)?;
+
+          drop(index);
+
+          readers.set_generation(generation + 1)?;
+        }
+        Err(error) => log::warn!("Getting chain tip: {error}"),
+      }
 
-  index.update()?;
+      match &mut blocks {
+        Some(blocks) => blocks.wait(self.polling_interval.into()),
+        None => thread::sleep(self.polling_interval.into()),
+      }
+    }
 
-  Ok(None)
+    Ok(None)
+  }
 }
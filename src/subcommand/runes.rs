@@ -1,5 +1,28 @@
 use super::*;
 
+pub mod snapshot;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Runes {
+  #[command(subcommand)]
+  subcommand: Option<RunesSubcommand>,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) enum RunesSubcommand {
+  #[command(about = "List rune balances as of a past block height")]
+  Snapshot(snapshot::Snapshot),
+}
+
+impl Runes {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    match self.subcommand {
+      Some(RunesSubcommand::Snapshot(snapshot)) => snapshot.run(settings),
+      None => run(settings),
+    }
+  }
+}
+
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
 pub struct Output {
   pub runes: BTreeMap<Rune, RuneInfo>,
@@ -0,0 +1,90 @@
+use {
+  super::*,
+  std::io::{BufWriter, Write},
+};
+
+pub type Output = api::RuneSnapshot;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Snapshot {
+  #[arg(long, help = "Aggregate balances by address.")]
+  by_address: bool,
+  #[arg(long, help = "Write snapshot to <CSV> instead of printing it.")]
+  csv: Option<PathBuf>,
+  #[arg(long, help = "Snapshot balances as of block <HEIGHT>.")]
+  height: u32,
+  #[arg(long, help = "Snapshot balances of <RUNE>.")]
+  rune: SpacedRune,
+}
+
+impl Snapshot {
+  pub(crate) fn run(self, settings: Settings) -> SubcommandResult {
+    let index = Index::open(&settings)?;
+
+    ensure!(
+      index.has_rune_activity_index(),
+      "`ord runes snapshot` requires index created with `--index-runes` and `--index-rune-activity` flags",
+    );
+
+    index.update()?;
+
+    let (id, entry, _parent) = index
+      .rune(self.rune.rune)?
+      .ok_or_else(|| anyhow!("rune {} not found", self.rune))?;
+
+    let block_height = index.block_height()?.unwrap_or(Height(0)).n();
+
+    ensure!(
+      self.height <= block_height,
+      "height {} is above index height {block_height}",
+      self.height,
+    );
+
+    let snapshot = Output {
+      divisibility: entry.divisibility,
+      height: self.height,
+      holders: index.get_rune_snapshot(id, self.height, self.by_address)?,
+      id,
+      rune: entry.spaced_rune,
+      symbol: entry.symbol,
+    };
+
+    let Some(path) = self.csv else {
+      return Ok(Some(Box::new(snapshot)));
+    };
+
+    let mut writer = BufWriter::new(File::create(&path)?);
+
+    if self.by_address {
+      writeln!(writer, "address,script_pubkey,amount,balance")?;
+    } else {
+      writeln!(writer, "outpoint,amount,balance")?;
+    }
+
+    for holder in snapshot.holders {
+      let balance = Decimal {
+        value: holder.amount,
+        scale: snapshot.divisibility,
+      };
+
+      if self.by_address {
+        writeln!(
+          writer,
+          "{},{},{},{balance}",
+          holder.address.unwrap_or_default(),
+          holder
+            .script_pubkey
+            .map(|script_pubkey| script_pubkey.to_hex_string())
+            .unwrap_or_default(),
+          holder.amount,
+        )?;
+      } else if let Some(outpoint) = holder.outpoint {
+        writeln!(writer, "{outpoint},{},{balance}", holder.amount)?;
+      }
+    }
+
+    writer.flush()?;
+
+    Ok(None)
+  }
+}
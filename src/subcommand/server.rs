@@ -3,25 +3,39 @@ use {
     accept_encoding::AcceptEncoding,
     accept_json::AcceptJson,
     error::{OptionExt, ServerError, ServerResult},
+    http_metrics::HttpMetrics,
   },
   super::*,
+  crate::index::{event::Event, full_text, Readers},
+  crate::metrics::Metrics,
   crate::templates::{
-    AddressHtml, BlockHtml, BlocksHtml, ChildrenHtml, ClockSvg, CollectionsHtml, HomeHtml,
-    InputHtml, InscriptionHtml, InscriptionsBlockHtml, InscriptionsHtml, OutputHtml, PageContent,
-    PageHtml, ParentsHtml, PreviewAudioHtml, PreviewCodeHtml, PreviewFontHtml, PreviewImageHtml,
-    PreviewMarkdownHtml, PreviewModelHtml, PreviewPdfHtml, PreviewTextHtml, PreviewUnknownHtml,
-    PreviewVideoHtml, RareTxt, RuneHtml, RunesHtml, SatHtml, TransactionHtml,
+    AddressHistoryHtml, AddressHtml, BlockHtml, BlocksHtml, ChildrenHtml, ClockSvg,
+    CollectionHoldersHtml, CollectionsHtml, ContentHashHtml, DelegatorsHtml, HomeHtml, InputHtml,
+    InscriptionHistoryHtml, InscriptionHtml, InscriptionsBlockHtml, InscriptionsHtml,
+    MetaprotocolHtml, MetaprotocolsHtml, OutputHtml, PageContent, PageHtml, ParentsHtml,
+    PreviewAudioHtml, PreviewCodeHtml, PreviewFontHtml, PreviewImageHtml, PreviewMarkdownHtml,
+    PreviewModelHtml, PreviewPdfHtml, PreviewTextHtml, PreviewUnknownHtml, PreviewVideoHtml,
+    RareTxt, RuneActivityHtml, RuneHoldersHtml, RuneHtml, RunesHtml, SatHtml, TextSearchHtml,
+    TransactionHtml,
   },
   axum::{
     body,
-    extract::{DefaultBodyLimit, Extension, Json, Path, Query},
+    extract::{
+      ws::{Message, WebSocketUpgrade},
+      DefaultBodyLimit, Extension, Json, Path, Query,
+    },
     http::{header, HeaderValue, StatusCode, Uri},
-    response::{IntoResponse, Redirect, Response},
+    middleware::{self, Next},
+    response::{
+      sse::{self, KeepAlive, Sse},
+      IntoResponse, Redirect, Response,
+    },
     routing::{get, post},
     Router,
   },
   axum_server::Handle,
   brotli::Decompressor,
+  futures::Stream,
   rust_embed::RustEmbed,
   rustls_acme::{
     acme::{LETS_ENCRYPT_PRODUCTION_DIRECTORY, LETS_ENCRYPT_STAGING_DIRECTORY},
@@ -30,9 +44,13 @@ use {
     AcmeConfig,
   },
   std::{str, sync::Arc},
+  tokio::sync::{broadcast, mpsc},
   tokio_stream::StreamExt,
   tower_http::{
-    compression::CompressionLayer,
+    compression::{
+      predicate::{DefaultPredicate, NotForContentType, Predicate},
+      CompressionLayer,
+    },
     cors::{Any, CorsLayer},
     set_header::SetResponseHeaderLayer,
     validate_request::ValidateRequestHeaderLayer,
@@ -44,6 +62,7 @@ pub use server_config::ServerConfig;
 mod accept_encoding;
 mod accept_json;
 mod error;
+mod http_metrics;
 pub mod query;
 mod server_config;
 
@@ -53,12 +72,62 @@ enum SpawnConfig {
   Redirect(String),
 }
 
+#[derive(Default, Deserialize)]
+pub(crate) struct InscriptionsQuery {
+  pub(crate) content_type: Option<String>,
+  pub(crate) media: Option<String>,
+  pub(crate) page: Option<u32>,
+}
+
+impl InscriptionsQuery {
+  fn filter(&self, index: &Index) -> ServerResult<Option<InscriptionFilter>> {
+    let filter = match (&self.content_type, &self.media) {
+      (None, None) => return Ok(None),
+      (Some(_), Some(_)) => {
+        return Err(ServerError::BadRequest(
+          "only one of content_type and media may be specified".into(),
+        ))
+      }
+      // `+` in an unescaped query string decodes to a space, and content types
+      // never contain spaces, so `image/svg+xml` may be passed as-is
+      (Some(content_type), None) => InscriptionFilter::ContentType(
+        Media::normalize_content_type(content_type).replace(' ', "+"),
+      ),
+      (None, Some(media)) => InscriptionFilter::Media(
+        Media::KINDS
+          .iter()
+          .find(|kind| **kind == media.to_ascii_lowercase())
+          .ok_or_else(|| {
+            ServerError::BadRequest(format!(
+              "unknown media `{media}`, expected one of: {}",
+              Media::KINDS.join(", ")
+            ))
+          })?,
+      ),
+    };
+
+    if !index.has_content_type_index() {
+      return Err(ServerError::NotFound(
+        "this server has no content type index".into(),
+      ));
+    }
+
+    Ok(Some(filter))
+  }
+}
+
 #[derive(Deserialize)]
 pub(crate) struct OutputsQuery {
   #[serde(rename = "type")]
   pub(crate) ty: Option<OutputType>,
 }
 
+#[derive(Deserialize)]
+pub(crate) struct RuneSnapshotQuery {
+  #[serde(default)]
+  pub(crate) by_address: bool,
+}
+
 #[derive(Clone, Copy, Deserialize, Default, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub(crate) enum OutputType {
@@ -69,11 +138,105 @@ pub(crate) enum OutputType {
   Runic,
 }
 
+const COLLECTION_HOLDERS_PAGE_SIZE: usize = 100;
+
+const EVENTS_PAGE_SIZE: usize = 100;
+
+#[derive(Default, Deserialize)]
+pub(crate) struct EventsQuery {
+  pub(crate) address: Option<Address<NetworkUnchecked>>,
+  pub(crate) from: Option<u64>,
+  pub(crate) inscription_id: Option<InscriptionId>,
+  pub(crate) kind: Option<String>,
+  pub(crate) rune_id: Option<RuneId>,
+}
+
+struct EventFilter {
+  inscription_id: Option<InscriptionId>,
+  kinds: Option<Vec<String>>,
+  rune_id: Option<RuneId>,
+  script_pubkey: Option<ScriptBuf>,
+}
+
+impl EventFilter {
+  fn new(query: EventsQuery, chain: Chain) -> ServerResult<Self> {
+    let script_pubkey = query
+      .address
+      .map(|address| {
+        address
+          .require_network(chain.network())
+          .map(|address| address.script_pubkey())
+          .map_err(|err| ServerError::BadRequest(err.to_string()))
+      })
+      .transpose()?;
+
+    Ok(Self {
+      inscription_id: query.inscription_id,
+      kinds: query
+        .kind
+        .map(|kinds| kinds.split(',').map(str::to_string).collect()),
+      rune_id: query.rune_id,
+      script_pubkey,
+    })
+  }
+
+  fn matches(&self, index: &Index, event: &Event) -> Result<bool> {
+    if let Some(kinds) = &self.kinds {
+      if !kinds.iter().any(|kind| kind == event.kind()) {
+        return Ok(false);
+      }
+    }
+
+    if self.inscription_id.is_some() && event.inscription_id() != self.inscription_id {
+      return Ok(false);
+    }
+
+    if self.rune_id.is_some() && event.rune_id() != self.rune_id {
+      return Ok(false);
+    }
+
+    if let Some(script_pubkey) = &self.script_pubkey {
+      let Some(outpoint) = event.outpoint().filter(|outpoint| !outpoint.is_null()) else {
+        return Ok(false);
+      };
+
+      let Some(transaction) = index.get_transaction(outpoint.txid)? else {
+        return Ok(false);
+      };
+
+      return Ok(
+        transaction
+          .output
+          .into_iter()
+          .nth(outpoint.vout.into_usize())
+          .map(|output| output.script_pubkey == *script_pubkey)
+          .unwrap_or_default(),
+      );
+    }
+
+    Ok(true)
+  }
+}
+
+#[derive(Deserialize)]
+struct AddressHistoryQuery {
+  cursor: Option<String>,
+}
+
 #[derive(Deserialize)]
 struct Search {
   query: String,
 }
 
+#[derive(Deserialize)]
+struct SearchQuery {
+  page: Option<usize>,
+  q: Option<String>,
+  query: Option<String>,
+  #[serde(rename = "type")]
+  kind: Option<String>,
+}
+
 #[derive(RustEmbed)]
 #[folder = "static"]
 struct StaticAssets;
@@ -125,6 +288,17 @@ pub struct Server {
   pub(crate) redirect_http_to_https: bool,
   #[arg(long, alias = "nosync", help = "Do not update the index.")]
   pub(crate) no_sync: bool,
+  #[arg(
+    long,
+    alias = "follow",
+    help = "Serve an index updated by a separate `ord index update --daemon` process, opening it read-only and reopening it every <POLLING_INTERVAL> to pick up new blocks."
+  )]
+  pub(crate) read_only: bool,
+  #[arg(
+    long,
+    help = "Watch the Bitcoin Core mempool for unconfirmed inscriptions and rune transfers."
+  )]
+  pub(crate) mempool: bool,
   #[arg(
     long,
     help = "Proxy `/content/INSCRIPTION_ID` and other recursive endpoints to `<PROXY>` if the inscription is not present on current chain."
@@ -139,31 +313,142 @@ pub struct Server {
 }
 
 impl Server {
-  pub fn run(self, settings: Settings, index: Arc<Index>, handle: Handle) -> SubcommandResult {
+  pub fn run(
+    self,
+    settings: Settings,
+    index: Arc<Index>,
+    handle: Handle,
+    mut event_receiver: mpsc::Receiver<Event>,
+  ) -> SubcommandResult {
     Runtime::new()?.block_on(async {
-      let index_clone = index.clone();
-      let integration_test = settings.integration_test();
+      let (events, _) = broadcast::channel(1024);
 
-      let index_thread = thread::spawn(move || loop {
-        if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
-          break;
-        }
+      {
+        let events = events.clone();
+        tokio::spawn(async move {
+          while let Some(event) = event_receiver.recv().await {
+            events.send(event).ok();
+          }
+        });
+      }
 
-        if !self.no_sync {
-          if let Err(error) = index_clone.update() {
-            log::warn!("Updating index: {error}");
+      let polling_interval = if settings.integration_test() {
+        Duration::from_millis(100)
+      } else {
+        self.polling_interval.into()
+      };
+
+      let notifications = settings
+        .bitcoin_zmq_url()
+        .map(|url| {
+          let mut topics = vec![zmq::Topic::Block];
+
+          if self.mempool {
+            topics.push(zmq::Topic::Transaction);
           }
-        }
 
-        thread::sleep(if integration_test {
-          Duration::from_millis(100)
-        } else {
-          self.polling_interval.into()
-        });
-      });
+          zmq::Notifications::subscribe(url, topics, polling_interval)
+        })
+        .transpose()?;
+
+      let mut blocks = notifications
+        .as_ref()
+        .map(|notifications| notifications.listener(zmq::Topic::Block));
+
+      let current_index = Arc::new(RwLock::new(index.clone()));
+
+      let index_thread = if self.read_only {
+        let current_index = current_index.clone();
+        let settings = settings.clone();
+        let readers = Readers::new(settings.index());
+        let mut opened = None;
+
+        thread::spawn(move || loop {
+          if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+            break;
+          }
+
+          match &mut blocks {
+            Some(blocks) => blocks.wait(polling_interval),
+            None => thread::sleep(polling_interval),
+          }
+
+          // the daemon advances the generation after closing the index, and
+          // waits for readers to register it before writing again, so reopen
+          // when it changes even if the index file has not
+          let state = (
+            fs::metadata(settings.index())
+              .and_then(|metadata| metadata.modified())
+              .ok(),
+            readers.generation().ok(),
+          );
+
+          if state.0.is_some() && Some(state) == opened {
+            continue;
+          }
+
+          match Index::open_read_only(&settings) {
+            Ok(index) => {
+              if self.mempool {
+                if let Err(error) = index.update_mempool() {
+                  log::warn!("Updating mempool: {error}");
+                }
+              }
+
+              *current_index.write().unwrap() = Arc::new(index);
+              opened = Some(state);
+            }
+            Err(error) => log::info!("Reopening index: {error}"),
+          }
+        })
+      } else {
+        let index = index.clone();
+
+        thread::spawn(move || loop {
+          if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+            break;
+          }
+
+          if !self.no_sync {
+            if let Err(error) = index.update() {
+              log::warn!("Updating index: {error}");
+            }
+          }
+
+          match &mut blocks {
+            Some(blocks) => blocks.wait(polling_interval),
+            None => thread::sleep(polling_interval),
+          }
+        })
+      };
 
       INDEXER.lock().unwrap().replace(index_thread);
 
+      if self.mempool {
+        let current_index = current_index.clone();
+
+        let mut transactions = notifications
+          .as_ref()
+          .map(|notifications| notifications.listener(zmq::Topic::Transaction));
+
+        thread::spawn(move || loop {
+          if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+            break;
+          }
+
+          let index = current_index.read().unwrap().clone();
+
+          if let Err(error) = index.update_mempool() {
+            log::warn!("Updating mempool: {error}");
+          }
+
+          match &mut transactions {
+            Some(transactions) => transactions.wait(polling_interval),
+            None => thread::sleep(polling_interval),
+          }
+        });
+      }
+
       let settings = Arc::new(settings);
       let acme_domains = self.acme_domains()?;
 
@@ -174,12 +459,17 @@ impl Server {
         domain: acme_domains.first().cloned(),
         index_sats: index.has_sat_index(),
         json_api_enabled: !self.disable_json_api,
+        mempool: self.mempool,
         proxy: self.proxy.clone(),
       });
 
+      let http_metrics = Arc::new(HttpMetrics::default());
+
       let router = Router::new()
         .route("/", get(Self::home))
         .route("/address/:address", get(Self::address))
+        .route("/address/:address/history", get(Self::address_history))
+        .route("/address/:address/runes", get(Self::address_runes))
         .route("/block/:query", get(Self::block))
         .route("/blockcount", get(Self::block_count))
         .route("/blockhash", get(Self::block_hash))
@@ -194,7 +484,17 @@ impl Server {
           get(Self::children_paginated),
         )
         .route("/clock", get(Self::clock))
+        .route("/collection/:parent/holders", get(Self::collection_holders))
+        .route(
+          "/collection/:parent/holders/:page",
+          get(Self::collection_holders_paginated),
+        )
         .route("/collections", get(Self::collections))
+        .route("/content-hash/:hash", get(Self::content_hash))
+        .route(
+          "/content-hash/:hash/:page",
+          get(Self::content_hash_paginated),
+        )
         .route("/collections/:page", get(Self::collections_paginated))
         .route("/content/:inscription_id", get(Self::content))
         .route("/faq", get(Self::faq))
@@ -206,6 +506,18 @@ impl Server {
           "/inscription/:inscription_query/:child",
           get(Self::inscription_child),
         )
+        .route(
+          "/inscription/:inscription_query/delegators",
+          get(Self::delegators),
+        )
+        .route(
+          "/inscription/:inscription_query/delegators/:page",
+          get(Self::delegators_paginated),
+        )
+        .route(
+          "/inscription/:inscription_query/history",
+          get(Self::inscription_history),
+        )
         .route("/inscriptions", get(Self::inscriptions))
         .route("/inscriptions", post(Self::inscriptions_json))
         .route("/inscriptions/:page", get(Self::inscriptions_paginated))
@@ -218,6 +530,15 @@ impl Server {
           get(Self::inscriptions_in_block_paginated),
         )
         .route("/install.sh", get(Self::install_script))
+        .route("/mempool/inscriptions", get(Self::mempool_inscriptions))
+        .route("/mempool/runes", get(Self::mempool_runes))
+        .route("/metrics", get(Self::metrics))
+        .route("/metaprotocol/:metaprotocol", get(Self::metaprotocol))
+        .route(
+          "/metaprotocol/:metaprotocol/:page",
+          get(Self::metaprotocol_paginated),
+        )
+        .route("/metaprotocols", get(Self::metaprotocols))
         .route("/ordinal/:sat", get(Self::ordinal))
         .route("/output/:output", get(Self::output))
         .route("/outputs", post(Self::outputs))
@@ -240,6 +561,10 @@ impl Server {
           "/r/inscription/:inscription_id",
           get(Self::inscription_recursive),
         )
+        .route(
+          "/r/inscription/:inscription_id/history",
+          get(Self::inscription_history_recursive),
+        )
         .route("/r/children/:inscription_id", get(Self::children_recursive))
         .route(
           "/r/children/:inscription_id/:page",
@@ -253,6 +578,19 @@ impl Server {
           "/r/children/:inscription_id/inscriptions/:page",
           get(Self::child_inscriptions_recursive_paginated),
         )
+        .route("/r/content-hash/:hash", get(Self::content_hash_recursive))
+        .route(
+          "/r/content-hash/:hash/:page",
+          get(Self::content_hash_recursive_paginated),
+        )
+        .route(
+          "/r/delegators/:inscription_id",
+          get(Self::delegators_recursive),
+        )
+        .route(
+          "/r/delegators/:inscription_id/:page",
+          get(Self::delegators_recursive_paginated),
+        )
         .route(
           "/r/undelegated-content/:inscription_id",
           get(Self::undelegated_content),
@@ -274,6 +612,17 @@ impl Server {
         )
         .route("/rare.txt", get(Self::rare_txt))
         .route("/rune/:rune", get(Self::rune))
+        .route("/rune/:rune/activity", get(Self::rune_activity))
+        .route(
+          "/rune/:rune/activity/:page",
+          get(Self::rune_activity_paginated),
+        )
+        .route("/rune/:rune/holders", get(Self::rune_holders))
+        .route(
+          "/rune/:rune/holders/:page",
+          get(Self::rune_holders_paginated),
+        )
+        .route("/rune/:rune/snapshot/:height", get(Self::rune_snapshot))
         .route("/runes", get(Self::runes))
         .route("/runes/:page", get(Self::runes_paginated))
         .route("/sat/:sat", get(Self::sat))
@@ -284,11 +633,26 @@ impl Server {
         .route("/status", get(Self::status))
         .route("/tx/:txid", get(Self::transaction))
         .route("/decode/:txid", get(Self::decode))
+        .route("/events", get(Self::events))
+        .route("/events/block/:height", get(Self::events_in_block))
         .route("/update", get(Self::update))
         .fallback(Self::fallback)
-        .layer(Extension(index))
+        .layer(middleware::from_fn(
+          move |mut request: http::Request<body::Body>, next: Next<body::Body>| {
+            request
+              .extensions_mut()
+              .insert(current_index.read().unwrap().clone());
+            next.run(request)
+          },
+        ))
+        .layer(middleware::from_fn({
+          let http_metrics = http_metrics.clone();
+          move |request, next| http_metrics.clone().record(request, next)
+        }))
+        .layer(Extension(http_metrics))
         .layer(Extension(server_config.clone()))
         .layer(Extension(settings.clone()))
+        .layer(Extension(events))
         .layer(SetResponseHeaderLayer::if_not_present(
           header::CONTENT_SECURITY_POLICY,
           HeaderValue::from_static("default-src 'self'"),
@@ -302,7 +666,9 @@ impl Server {
             .allow_methods([http::Method::GET])
             .allow_origin(Any),
         )
-        .layer(CompressionLayer::new())
+        .layer(CompressionLayer::new().compress_when(
+          DefaultPredicate::new().and(NotForContentType::const_new("text/event-stream")),
+        ))
         .with_state(server_config.clone());
 
       let router = if server_config.json_api_enabled {
@@ -506,6 +872,146 @@ impl Server {
     index.block_height()?.ok_or_not_found(|| "genesis block")
   }
 
+  fn event_stream(
+    index: Arc<Index>,
+    receiver: broadcast::Receiver<Event>,
+    filter: EventFilter,
+  ) -> impl Stream<Item = Event> {
+    futures::stream::unfold(
+      (index, receiver, filter),
+      |(index, mut receiver, filter)| async move {
+        loop {
+          let event = match receiver.recv().await {
+            Ok(event) => event,
+            Err(broadcast::error::RecvError::Lagged(skipped)) => {
+              log::warn!("Event stream subscriber lagged, skipped {skipped} events");
+              continue;
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+          };
+
+          match task::block_in_place(|| filter.matches(&index, &event)) {
+            Ok(true) => return Some((event, (index, receiver, filter))),
+            Ok(false) => {}
+            Err(err) => log::warn!("Filtering event: {err}"),
+          }
+        }
+      },
+    )
+  }
+
+  async fn events(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Extension(events): Extension<broadcast::Sender<Event>>,
+    Query(query): Query<EventsQuery>,
+    websocket: Option<WebSocketUpgrade>,
+  ) -> ServerResult {
+    if !server_config.json_api_enabled {
+      return Ok((StatusCode::NOT_ACCEPTABLE, "JSON API disabled").into_response());
+    }
+
+    let from = query.from;
+
+    let filter = EventFilter::new(query, server_config.chain)?;
+
+    if !index.has_event_index() && filter.kinds.iter().flatten().any(|kind| kind == "undo") {
+      return Err(ServerError::NotFound(
+        "this server has no event index".to_string(),
+      ));
+    }
+
+    if let Some(from) = from {
+      return task::block_in_place(|| {
+        if !index.has_event_index() {
+          return Err(ServerError::NotFound(
+            "this server has no event index".to_string(),
+          ));
+        }
+
+        let (events, next) = index.get_events_paginated(from, EVENTS_PAGE_SIZE)?;
+
+        let more = next.is_some();
+
+        // the cursor advances past every event read, including those that
+        // don't match the filter
+        let next = next
+          .or(events.last().map(|(id, _event)| id + 1))
+          .unwrap_or(from);
+
+        let mut entries = Vec::new();
+
+        for (id, event) in events {
+          if filter.matches(&index, &event)? {
+            entries.push(api::EventEntry { id, event });
+          }
+        }
+
+        Ok(
+          Json(api::Events {
+            events: entries,
+            more,
+            next,
+          })
+          .into_response(),
+        )
+      });
+    }
+
+    let stream = Self::event_stream(index, events.subscribe(), filter);
+
+    Ok(match websocket {
+      Some(websocket) => websocket
+        .on_upgrade(|mut socket| async move {
+          let mut stream = Box::pin(stream);
+          while let Some(event) = stream.next().await {
+            let Ok(json) = serde_json::to_string(&event) else {
+              break;
+            };
+
+            if socket.send(Message::Text(json)).await.is_err() {
+              break;
+            }
+          }
+        })
+        .into_response(),
+      None => {
+        Sse::new(stream.map(|event| sse::Event::default().event(event.kind()).json_data(event)))
+          .keep_alive(KeepAlive::default())
+          .into_response()
+      }
+    })
+  }
+
+  async fn events_in_block(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(height): Path<u32>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !server_config.json_api_enabled {
+        return Ok((StatusCode::NOT_ACCEPTABLE, "JSON API disabled").into_response());
+      }
+
+      if !index.has_event_index() {
+        return Err(ServerError::NotFound(
+          "this server has no event index".to_string(),
+        ));
+      }
+
+      Ok(
+        Json(
+          index
+            .get_events_in_block(height)?
+            .into_iter()
+            .map(|(id, event)| api::EventEntry { id, event })
+            .collect::<Vec<api::EventEntry>>(),
+        )
+        .into_response(),
+      )
+    })
+  }
+
   async fn clock(Extension(index): Extension<Arc<Index>>) -> ServerResult {
     task::block_in_place(|| {
       Ok(
@@ -613,10 +1119,14 @@ impl Server {
     AcceptJson(accept_json): AcceptJson,
   ) -> ServerResult {
     task::block_in_place(|| {
-      let (output_info, txout) = index
+      let (mut output_info, txout) = index
         .get_output_info(outpoint)?
         .ok_or_not_found(|| format!("output {outpoint}"))?;
 
+      if server_config.mempool {
+        output_info.pending = index.mempool().output(&index, outpoint)?;
+      }
+
       Ok(if accept_json {
         Json(output_info).into_response()
       } else {
@@ -773,15 +1283,7 @@ impl Server {
         ));
       }
 
-      let rune = match rune_query {
-        query::Rune::Spaced(spaced_rune) => spaced_rune.rune,
-        query::Rune::Id(rune_id) => index
-          .get_rune_by_id(rune_id)?
-          .ok_or_not_found(|| format!("rune {rune_id}"))?,
-        query::Rune::Number(number) => index
-          .get_rune_by_number(usize::try_from(number).unwrap())?
-          .ok_or_not_found(|| format!("rune number {number}"))?,
-      };
+      let rune = Self::rune_from_query(&index, rune_query)?;
 
       let (id, entry, parent) = index
         .rune(rune)?
@@ -791,9 +1293,12 @@ impl Server {
 
       let mintable = entry.mintable((block_height.n() + 1).into()).is_ok();
 
+      let holders = index.get_rune_holder_count(id)?;
+
       Ok(if accept_json {
         Json(api::Rune {
           entry,
+          holders,
           id,
           mintable,
           parent,
@@ -802,6 +1307,7 @@ impl Server {
       } else {
         RuneHtml {
           entry,
+          holders,
           id,
           mintable,
           parent,
@@ -812,49 +1318,225 @@ impl Server {
     })
   }
 
-  async fn runes(
+  async fn rune_activity(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
-    accept_json: AcceptJson,
-  ) -> ServerResult<Response> {
-    Self::runes_paginated(
-      Extension(server_config),
-      Extension(index),
-      Path(0),
-      accept_json,
-    )
-    .await
+    Path(DeserializeFromStr(rune_query)): Path<DeserializeFromStr<query::Rune>>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    Self::rune_activity_inner(server_config, &index, rune_query, 0, accept_json)
   }
 
-  async fn runes_paginated(
+  async fn rune_activity_paginated(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
-    Path(page_index): Path<usize>,
+    Path((DeserializeFromStr(rune_query), page_index)): Path<(
+      DeserializeFromStr<query::Rune>,
+      usize,
+    )>,
     AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    Self::rune_activity_inner(server_config, &index, rune_query, page_index, accept_json)
+  }
+
+  fn rune_activity_inner(
+    server_config: Arc<ServerConfig>,
+    index: &Index,
+    rune_query: query::Rune,
+    page_index: usize,
+    accept_json: bool,
   ) -> ServerResult {
     task::block_in_place(|| {
-      let (entries, more) = index.runes_paginated(50, page_index)?;
+      if !index.has_rune_activity_index() {
+        return Err(ServerError::NotFound(
+          "this server has no rune activity index".to_string(),
+        ));
+      }
 
-      let prev = page_index.checked_sub(1);
+      let rune = Self::rune_from_query(index, rune_query)?;
 
-      let next = more.then_some(page_index + 1);
+      let (id, entry, _parent) = index
+        .rune(rune)?
+        .ok_or_not_found(|| format!("rune {rune}"))?;
+
+      let (entries, more) = index.get_rune_activity_paginated(id, 100, page_index)?;
+
+      let activity = RuneActivityHtml {
+        divisibility: entry.divisibility,
+        entries,
+        id,
+        more,
+        next: more.then_some(page_index + 1),
+        prev: page_index.checked_sub(1),
+        rune: entry.spaced_rune,
+        symbol: entry.symbol,
+      };
 
       Ok(if accept_json {
-        Json(RunesHtml {
-          entries,
-          more,
-          prev,
-          next,
-        })
-        .into_response()
+        Json(activity).into_response()
       } else {
-        RunesHtml {
-          entries,
-          more,
-          prev,
-          next,
-        }
-        .page(server_config)
+        activity.page(server_config).into_response()
+      })
+    })
+  }
+
+  async fn rune_holders(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(rune_query)): Path<DeserializeFromStr<query::Rune>>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    Self::rune_holders_inner(server_config, &index, rune_query, 0, accept_json)
+  }
+
+  async fn rune_holders_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path((DeserializeFromStr(rune_query), page_index)): Path<(
+      DeserializeFromStr<query::Rune>,
+      usize,
+    )>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    Self::rune_holders_inner(server_config, &index, rune_query, page_index, accept_json)
+  }
+
+  fn rune_holders_inner(
+    server_config: Arc<ServerConfig>,
+    index: &Index,
+    rune_query: query::Rune,
+    page_index: usize,
+    accept_json: bool,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_rune_index() {
+        return Err(ServerError::NotFound(
+          "this server has no rune index".to_string(),
+        ));
+      }
+
+      let rune = Self::rune_from_query(index, rune_query)?;
+
+      let (id, entry, _parent) = index
+        .rune(rune)?
+        .ok_or_not_found(|| format!("rune {rune}"))?;
+
+      let (holders, more) = index.get_rune_holders_paginated(id, 100, page_index)?;
+
+      let holders = RuneHoldersHtml {
+        divisibility: entry.divisibility,
+        holders,
+        id,
+        more,
+        next: more.then_some(page_index + 1),
+        prev: page_index.checked_sub(1),
+        rune: entry.spaced_rune,
+        symbol: entry.symbol,
+      };
+
+      Ok(if accept_json {
+        Json(holders).into_response()
+      } else {
+        holders.page(server_config).into_response()
+      })
+    })
+  }
+
+  async fn rune_snapshot(
+    Extension(index): Extension<Arc<Index>>,
+    Path((DeserializeFromStr(rune_query), height)): Path<(DeserializeFromStr<query::Rune>, u32)>,
+    Query(query): Query<RuneSnapshotQuery>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_rune_activity_index() {
+        return Err(ServerError::NotFound(
+          "this server has no rune activity index".to_string(),
+        ));
+      }
+
+      let rune = Self::rune_from_query(&index, rune_query)?;
+
+      let (id, entry, _parent) = index
+        .rune(rune)?
+        .ok_or_not_found(|| format!("rune {rune}"))?;
+
+      let block_height = index.block_height()?.unwrap_or(Height(0)).n();
+
+      if height > block_height {
+        return Err(ServerError::BadRequest(format!(
+          "height {height} is above index height {block_height}"
+        )));
+      }
+
+      Ok(
+        Json(api::RuneSnapshot {
+          divisibility: entry.divisibility,
+          height,
+          holders: index.get_rune_snapshot(id, height, query.by_address)?,
+          id,
+          rune: entry.spaced_rune,
+          symbol: entry.symbol,
+        })
+        .into_response(),
+      )
+    })
+  }
+
+  fn rune_from_query(index: &Index, rune_query: query::Rune) -> ServerResult<Rune> {
+    Ok(match rune_query {
+      query::Rune::Spaced(spaced_rune) => spaced_rune.rune,
+      query::Rune::Id(rune_id) => index
+        .get_rune_by_id(rune_id)?
+        .ok_or_not_found(|| format!("rune {rune_id}"))?,
+      query::Rune::Number(number) => index
+        .get_rune_by_number(usize::try_from(number).unwrap())?
+        .ok_or_not_found(|| format!("rune number {number}"))?,
+    })
+  }
+
+  async fn runes(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    accept_json: AcceptJson,
+  ) -> ServerResult<Response> {
+    Self::runes_paginated(
+      Extension(server_config),
+      Extension(index),
+      Path(0),
+      accept_json,
+    )
+    .await
+  }
+
+  async fn runes_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(page_index): Path<usize>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let (entries, more) = index.runes_paginated(50, page_index)?;
+
+      let prev = page_index.checked_sub(1);
+
+      let next = more.then_some(page_index + 1);
+
+      Ok(if accept_json {
+        Json(RunesHtml {
+          entries,
+          more,
+          prev,
+          next,
+        })
+        .into_response()
+      } else {
+        RunesHtml {
+          entries,
+          more,
+          prev,
+          next,
+        }
+        .page(server_config)
         .into_response()
       })
     })
@@ -928,7 +1610,19 @@ impl Server {
 
       let inscriptions = index.get_inscriptions_for_outputs(&outputs)?;
 
-      let runes_balances = index.get_aggregated_rune_balances_for_outputs(&outputs)?;
+      let runes_balances = if index.has_rune_index() {
+        index.get_rune_balances_for_script_pubkey(&address.script_pubkey())?
+      } else {
+        Vec::new()
+      };
+
+      let pending = if server_config.mempool {
+        index
+          .mempool()
+          .address(&index, &address.script_pubkey(), &outputs)?
+      } else {
+        None
+      };
 
       Ok(if accept_json {
         Json(api::AddressInfo {
@@ -936,6 +1630,7 @@ impl Server {
           outputs,
           inscriptions,
           runes_balances,
+          pending,
         })
         .into_response()
       } else {
@@ -952,6 +1647,78 @@ impl Server {
     })
   }
 
+  async fn address_history(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(address): Path<Address<NetworkUnchecked>>,
+    Query(query): Query<AddressHistoryQuery>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_address_history_index() {
+        return Err(ServerError::NotFound(
+          "this server has no address history index".to_string(),
+        ));
+      }
+
+      let address = address
+        .require_network(server_config.chain.network())
+        .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+      let before = query
+        .cursor
+        .map(|cursor| {
+          cursor
+            .split_once(':')
+            .and_then(|(height, tx_index)| Some((height.parse().ok()?, tx_index.parse().ok()?)))
+            .ok_or_else(|| ServerError::BadRequest(format!("invalid cursor `{cursor}`")))
+        })
+        .transpose()?;
+
+      let (entries, next) = index.get_address_history(&address.script_pubkey(), before, 100)?;
+
+      let next = next.map(|(height, tx_index)| format!("{height}:{tx_index}"));
+
+      Ok(if accept_json {
+        Json(api::AddressHistory { entries, next }).into_response()
+      } else {
+        AddressHistoryHtml {
+          address,
+          entries,
+          next,
+        }
+        .page(server_config)
+        .into_response()
+      })
+    })
+  }
+
+  async fn address_runes(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(address): Path<Address<NetworkUnchecked>>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_address_index() {
+        return Err(ServerError::NotFound(
+          "this server has no address index".to_string(),
+        ));
+      }
+
+      if !index.has_rune_index() {
+        return Err(ServerError::NotFound(
+          "this server has no rune index".to_string(),
+        ));
+      }
+
+      let address = address
+        .require_network(server_config.chain.network())
+        .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+      Ok(Json(index.get_rune_balances_for_script_pubkey(&address.script_pubkey())?).into_response())
+    })
+  }
+
   async fn block(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
@@ -968,15 +1735,15 @@ impl Server {
           (block, height)
         }
         query::Block::Hash(hash) => {
-          let info = index
-            .block_header_info(hash)?
+          let height = index
+            .get_block_height_by_hash(hash)?
             .ok_or_not_found(|| format!("block {hash}"))?;
 
           let block = index
             .get_block_by_hash(hash)?
             .ok_or_not_found(|| format!("block {hash}"))?;
 
-          (block, u32::try_from(info.height).unwrap())
+          (block, height)
         }
       };
 
@@ -1108,6 +1875,113 @@ impl Server {
     })
   }
 
+  async fn mempool_inscriptions(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !server_config.mempool {
+        return Err(ServerError::NotFound(
+          "this server is not watching the mempool".to_string(),
+        ));
+      }
+
+      Ok(Json(index.mempool().inscriptions()).into_response())
+    })
+  }
+
+  async fn mempool_runes(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !server_config.mempool {
+        return Err(ServerError::NotFound(
+          "this server is not watching the mempool".to_string(),
+        ));
+      }
+
+      if !index.has_rune_index() {
+        return Err(ServerError::NotFound(
+          "this server has no rune index".to_string(),
+        ));
+      }
+
+      Ok(Json(index.mempool().runes(&index)?).into_response())
+    })
+  }
+
+  async fn metaprotocol(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(metaprotocol): Path<String>,
+    accept_json: AcceptJson,
+  ) -> ServerResult {
+    Self::metaprotocol_paginated(
+      Extension(server_config),
+      Extension(index),
+      Path((metaprotocol, 0)),
+      accept_json,
+    )
+    .await
+  }
+
+  async fn metaprotocol_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path((metaprotocol, page)): Path<(String, u32)>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_metaprotocol_index() {
+        return Err(ServerError::NotFound(
+          "this server has no metaprotocol index".to_string(),
+        ));
+      }
+
+      let (inscriptions, more) =
+        index.get_metaprotocol_inscriptions_paginated(&metaprotocol, 100, page)?;
+
+      let metaprotocol = MetaprotocolHtml {
+        inscriptions,
+        metaprotocol,
+        more,
+        next: more.then_some(page + 1),
+        prev: page.checked_sub(1),
+      };
+
+      Ok(if accept_json {
+        Json(metaprotocol).into_response()
+      } else {
+        metaprotocol.page(server_config).into_response()
+      })
+    })
+  }
+
+  async fn metaprotocols(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_metaprotocol_index() {
+        return Err(ServerError::NotFound(
+          "this server has no metaprotocol index".to_string(),
+        ));
+      }
+
+      let metaprotocols = MetaprotocolsHtml {
+        metaprotocols: index.get_metaprotocols()?,
+      };
+
+      Ok(if accept_json {
+        Json(metaprotocols).into_response()
+      } else {
+        metaprotocols.page(server_config).into_response()
+      })
+    })
+  }
+
   async fn inscription_recursive(
     Extension(index): Extension<Arc<Index>>,
     Extension(server_config): Extension<Arc<ServerConfig>>,
@@ -1182,28 +2056,129 @@ impl Server {
     })
   }
 
-  async fn status(
-    Extension(server_config): Extension<Arc<ServerConfig>>,
+  async fn metrics(
+    Extension(http_metrics): Extension<Arc<HttpMetrics>>,
     Extension(index): Extension<Arc<Index>>,
-    AcceptJson(accept_json): AcceptJson,
   ) -> ServerResult {
     task::block_in_place(|| {
-      Ok(if accept_json {
-        Json(index.status(server_config.json_api_enabled)?).into_response()
-      } else {
-        index
-          .status(server_config.json_api_enabled)?
-          .page(server_config)
-          .into_response()
+      let mut metrics = Metrics::default();
+
+      index.metrics(&mut metrics)?;
+
+      http_metrics.write(&mut metrics);
+
+      Ok(
+        (
+          [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+          metrics.to_string(),
+        )
+          .into_response(),
+      )
+    })
+  }
+
+  async fn status(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      Ok(if accept_json {
+        Json(index.status(server_config.json_api_enabled)?).into_response()
+      } else {
+        index
+          .status(server_config.json_api_enabled)?
+          .page(server_config)
+          .into_response()
       })
     })
   }
 
   async fn search_by_query(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
-    Query(search): Query<Search>,
-  ) -> ServerResult<Redirect> {
-    Self::search(index, search.query).await
+    Query(search): Query<SearchQuery>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    let query = search
+      .q
+      .or(search.query)
+      .ok_or_else(|| ServerError::BadRequest("missing search query".into()))?;
+
+    match search.kind.as_deref() {
+      None => Ok(Self::search(index, query).await?.into_response()),
+      Some("text") => Self::text_search(
+        server_config,
+        &index,
+        &query,
+        search.page.unwrap_or_default(),
+        accept_json,
+      ),
+      Some(kind) => Err(ServerError::BadRequest(format!(
+        "unknown search type `{kind}`, expected `text`"
+      ))),
+    }
+  }
+
+  fn text_search(
+    server_config: Arc<ServerConfig>,
+    index: &Index,
+    query: &str,
+    page: usize,
+    accept_json: bool,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_full_text_index() {
+        return Err(ServerError::NotFound(
+          "this server has no full-text index".into(),
+        ));
+      }
+
+      let terms = full_text::terms(query);
+
+      if terms.is_empty() {
+        return Err(ServerError::BadRequest(
+          "search query contains no searchable terms".into(),
+        ));
+      }
+
+      if terms.len() > full_text::MAX_QUERY_TERMS {
+        return Err(ServerError::BadRequest(format!(
+          "search query may contain at most {} terms",
+          full_text::MAX_QUERY_TERMS
+        )));
+      }
+
+      let (matches, more) = index.search_full_text(&terms, 20, page)?;
+
+      let results = matches
+        .into_iter()
+        .map(|(id, score)| {
+          let snippet = index
+            .get_inscription_by_id(id)?
+            .as_ref()
+            .and_then(full_text::text)
+            .map(|text| full_text::snippet(&text, &terms))
+            .unwrap_or_default();
+
+          Ok(api::TextSearchResult { id, score, snippet })
+        })
+        .collect::<ServerResult<Vec<api::TextSearchResult>>>()?;
+
+      let search = TextSearchHtml {
+        more,
+        next: more.then_some(page + 1),
+        prev: page.checked_sub(1),
+        query: query.into(),
+        results,
+      };
+
+      Ok(if accept_json {
+        Json(search).into_response()
+      } else {
+        search.page(server_config).into_response()
+      })
+    })
   }
 
   async fn search_by_path(
@@ -1759,6 +2734,104 @@ impl Server {
     Self::inscription_inner(server_config, &index, accept_json, query, Some(child)).await
   }
 
+  async fn inscription_history(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    AcceptJson(accept_json): AcceptJson,
+    Path(DeserializeFromStr(query)): Path<DeserializeFromStr<query::Inscription>>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_inscription_history_index() {
+        return Err(ServerError::NotFound(
+          "this server has no inscription history index".into(),
+        ));
+      }
+
+      if let query::Inscription::Sat(_) = query {
+        if !index.has_sat_index() {
+          return Err(ServerError::NotFound("sat index required".into()));
+        }
+      }
+
+      let history = Self::inscription_history_inner(&server_config, &index, query)?
+        .ok_or_not_found(|| format!("inscription {query}"))?;
+
+      Ok(if accept_json {
+        Json(history).into_response()
+      } else {
+        InscriptionHistoryHtml {
+          id: history.id,
+          number: history.number,
+          transfers: history.transfers,
+        }
+        .page(server_config)
+        .into_response()
+      })
+    })
+  }
+
+  async fn inscription_history_recursive(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(inscription_id): Path<InscriptionId>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_inscription_history_index() {
+        return Err(ServerError::NotFound(
+          "this server has no inscription history index".into(),
+        ));
+      }
+
+      let Some(history) = Self::inscription_history_inner(
+        &server_config,
+        &index,
+        query::Inscription::Id(inscription_id),
+      )?
+      else {
+        return if let Some(proxy) = server_config.proxy.as_ref() {
+          Self::proxy(proxy, &format!("r/inscription/{inscription_id}/history"))
+        } else {
+          Err(ServerError::NotFound(format!(
+            "inscription {inscription_id} not found"
+          )))
+        };
+      };
+
+      Ok(Json(history).into_response())
+    })
+  }
+
+  fn inscription_history_inner(
+    server_config: &ServerConfig,
+    index: &Index,
+    query: query::Inscription,
+  ) -> ServerResult<Option<api::InscriptionHistory>> {
+    let Some((entry, transfers)) = index.get_inscription_history(query)? else {
+      return Ok(None);
+    };
+
+    Ok(Some(api::InscriptionHistory {
+      id: entry.id,
+      number: entry.inscription_number,
+      transfers: transfers
+        .into_iter()
+        .map(|transfer| api::InscriptionTransfer {
+          address: transfer.script_pubkey.and_then(|script_pubkey| {
+            server_config
+              .chain
+              .address_from_script(&script_pubkey)
+              .ok()
+              .map(|address| address.to_string())
+          }),
+          height: transfer.height,
+          new_satpoint: transfer.new_satpoint,
+          old_satpoint: transfer.old_satpoint,
+          txid: transfer.txid,
+        })
+        .collect(),
+    }))
+  }
+
   async fn inscription_inner(
     server_config: Arc<ServerConfig>,
     index: &Index,
@@ -1788,6 +2861,7 @@ impl Server {
           })),
           child_count: info.child_count,
           children: info.children,
+          duplicate_of: info.duplicate_of,
           fee: info.fee,
           height: info.height,
           inscription,
@@ -1832,89 +2906,192 @@ impl Server {
     })
   }
 
-  async fn collections(
+  async fn collection_holders(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
+    Path(parent): Path<InscriptionId>,
+    AcceptJson(accept_json): AcceptJson,
   ) -> ServerResult {
-    Self::collections_paginated(Extension(server_config), Extension(index), Path(0)).await
+    Self::collection_holders_paginated(
+      Extension(server_config),
+      Extension(index),
+      Path((parent, 0)),
+      AcceptJson(accept_json),
+    )
+    .await
   }
 
-  async fn collections_paginated(
+  async fn collection_holders_paginated(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
-    Path(page_index): Path<usize>,
+    Path((parent, page)): Path<(InscriptionId, usize)>,
+    AcceptJson(accept_json): AcceptJson,
   ) -> ServerResult {
     task::block_in_place(|| {
-      let (collections, more_collections) = index.get_collections_paginated(100, page_index)?;
+      if !index.has_address_index() {
+        return Err(ServerError::NotFound(
+          "this server has no address index".to_string(),
+        ));
+      }
 
-      let prev = page_index.checked_sub(1);
+      let entry = index
+        .get_inscription_entry(parent)?
+        .ok_or_not_found(|| format!("inscription {parent}"))?;
 
-      let next = more_collections.then_some(page_index + 1);
+      let (holders, more) = index
+        .get_collection_holders_paginated(parent, None, COLLECTION_HOLDERS_PAGE_SIZE, page)?
+        .ok_or_not_found(|| format!("inscription {parent}"))?;
 
-      Ok(
-        CollectionsHtml {
-          inscriptions: collections,
-          prev,
-          next,
-        }
-        .page(server_config)
-        .into_response(),
-      )
+      let holders = CollectionHoldersHtml {
+        holders,
+        more,
+        next: more.then_some(page + 1),
+        parent,
+        parent_number: entry.inscription_number,
+        prev: page.checked_sub(1),
+      };
+
+      Ok(if accept_json {
+        Json(holders).into_response()
+      } else {
+        holders.page(server_config).into_response()
+      })
     })
   }
 
-  async fn children(
+  async fn content_hash(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
-    Path(inscription_id): Path<InscriptionId>,
+    Path(hash): Path<sha256::Hash>,
+    accept_json: AcceptJson,
   ) -> ServerResult {
-    Self::children_paginated(
+    Self::content_hash_paginated(
       Extension(server_config),
       Extension(index),
-      Path((inscription_id, 0)),
+      Path((hash, 0)),
+      accept_json,
     )
     .await
   }
 
-  async fn children_paginated(
+  async fn content_hash_paginated(
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
-    Path((parent, page)): Path<(InscriptionId, usize)>,
+    Path((hash, page)): Path<(sha256::Hash, usize)>,
+    AcceptJson(accept_json): AcceptJson,
   ) -> ServerResult {
     task::block_in_place(|| {
-      let entry = index
-        .get_inscription_entry(parent)?
-        .ok_or_not_found(|| format!("inscription {parent}"))?;
+      if !index.has_content_hash_index() {
+        return Err(ServerError::NotFound(
+          "this server has no content hash index".to_string(),
+        ));
+      }
 
-      let parent_number = entry.inscription_number;
+      let (inscriptions, more) =
+        index.get_inscriptions_by_content_hash_paginated(hash, 100, page)?;
 
-      let (children, more_children) =
-        index.get_children_by_sequence_number_paginated(entry.sequence_number, 100, page)?;
+      let content_hash = ContentHashHtml {
+        hash,
+        inscriptions,
+        more,
+        next: more.then_some(page + 1),
+        prev: page.checked_sub(1),
+      };
 
-      let prev_page = page.checked_sub(1);
+      Ok(if accept_json {
+        Json(content_hash).into_response()
+      } else {
+        content_hash.page(server_config).into_response()
+      })
+    })
+  }
 
-      let next_page = more_children.then_some(page + 1);
+  async fn content_hash_recursive(
+    Extension(index): Extension<Arc<Index>>,
+    Path(hash): Path<sha256::Hash>,
+  ) -> ServerResult {
+    Self::content_hash_recursive_paginated(Extension(index), Path((hash, 0))).await
+  }
+
+  async fn content_hash_recursive_paginated(
+    Extension(index): Extension<Arc<Index>>,
+    Path((hash, page_index)): Path<(sha256::Hash, u32)>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      if !index.has_content_hash_index() {
+        return Err(ServerError::NotFound(
+          "this server has no content hash index".to_string(),
+        ));
+      }
+
+      let (ids, more) = index.get_inscriptions_by_content_hash_paginated(
+        hash,
+        100,
+        usize::try_from(page_index).unwrap(),
+      )?;
 
       Ok(
-        ChildrenHtml {
-          parent,
-          parent_number,
-          children,
-          prev_page,
-          next_page,
-        }
-        .page(server_config)
+        Json(api::Inscriptions {
+          ids,
+          more,
+          page_index,
+        })
         .into_response(),
       )
     })
   }
 
-  async fn children_recursive(
+  async fn delegators(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(inscription_id): Path<InscriptionId>,
+    accept_json: AcceptJson,
+  ) -> ServerResult {
+    Self::delegators_paginated(
+      Extension(server_config),
+      Extension(index),
+      Path((inscription_id, 0)),
+      accept_json,
+    )
+    .await
+  }
+
+  async fn delegators_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path((delegate, page)): Path<(InscriptionId, usize)>,
+    AcceptJson(accept_json): AcceptJson,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let entry = index
+        .get_inscription_entry(delegate)?
+        .ok_or_not_found(|| format!("inscription {delegate}"))?;
+
+      let (delegators, more) = index.get_delegators_paginated(delegate, 100, page)?;
+
+      let delegators = DelegatorsHtml {
+        delegate,
+        delegate_number: entry.inscription_number,
+        delegators,
+        more,
+        next: more.then_some(page + 1),
+        prev: page.checked_sub(1),
+      };
+
+      Ok(if accept_json {
+        Json(delegators).into_response()
+      } else {
+        delegators.page(server_config).into_response()
+      })
+    })
+  }
+
+  async fn delegators_recursive(
     Extension(index): Extension<Arc<Index>>,
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Path(inscription_id): Path<InscriptionId>,
   ) -> ServerResult {
-    Self::children_recursive_paginated(
+    Self::delegators_recursive_paginated(
       Extension(index),
       Extension(server_config),
       Path((inscription_id, 0)),
@@ -1922,33 +3099,153 @@ impl Server {
     .await
   }
 
-  async fn children_recursive_paginated(
+  async fn delegators_recursive_paginated(
     Extension(index): Extension<Arc<Index>>,
     Extension(server_config): Extension<Arc<ServerConfig>>,
-    Path((parent, page)): Path<(InscriptionId, usize)>,
+    Path((delegate, page_index)): Path<(InscriptionId, u32)>,
   ) -> ServerResult {
     task::block_in_place(|| {
-      let Some(parent) = index.get_inscription_entry(parent)? else {
+      if index.get_inscription_entry(delegate)?.is_none() {
         return if let Some(proxy) = server_config.proxy.as_ref() {
-          Self::proxy(proxy, &format!("r/children/{}/{}", parent, page))
+          Self::proxy(proxy, &format!("r/delegators/{delegate}/{page_index}"))
         } else {
           Err(ServerError::NotFound(format!(
-            "inscription {} not found",
-            parent
+            "inscription {delegate} not found"
           )))
         };
-      };
-
-      let parent_sequence_number = parent.sequence_number;
+      }
 
       let (ids, more) =
-        index.get_children_by_sequence_number_paginated(parent_sequence_number, 100, page)?;
+        index.get_delegators_paginated(delegate, 100, usize::try_from(page_index).unwrap())?;
 
-      Ok(Json(api::Children { ids, more, page }).into_response())
+      Ok(
+        Json(api::Inscriptions {
+          ids,
+          more,
+          page_index,
+        })
+        .into_response(),
+      )
     })
   }
 
-  async fn child_inscriptions_recursive(
+  async fn collections(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+  ) -> ServerResult {
+    Self::collections_paginated(Extension(server_config), Extension(index), Path(0)).await
+  }
+
+  async fn collections_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(page_index): Path<usize>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let (collections, more_collections) = index.get_collections_paginated(100, page_index)?;
+
+      let prev = page_index.checked_sub(1);
+
+      let next = more_collections.then_some(page_index + 1);
+
+      Ok(
+        CollectionsHtml {
+          inscriptions: collections,
+          prev,
+          next,
+        }
+        .page(server_config)
+        .into_response(),
+      )
+    })
+  }
+
+  async fn children(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(inscription_id): Path<InscriptionId>,
+  ) -> ServerResult {
+    Self::children_paginated(
+      Extension(server_config),
+      Extension(index),
+      Path((inscription_id, 0)),
+    )
+    .await
+  }
+
+  async fn children_paginated(
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path((parent, page)): Path<(InscriptionId, usize)>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let entry = index
+        .get_inscription_entry(parent)?
+        .ok_or_not_found(|| format!("inscription {parent}"))?;
+
+      let parent_number = entry.inscription_number;
+
+      let (children, more_children) =
+        index.get_children_by_sequence_number_paginated(entry.sequence_number, 100, page)?;
+
+      let prev_page = page.checked_sub(1);
+
+      let next_page = more_children.then_some(page + 1);
+
+      Ok(
+        ChildrenHtml {
+          parent,
+          parent_number,
+          children,
+          prev_page,
+          next_page,
+        }
+        .page(server_config)
+        .into_response(),
+      )
+    })
+  }
+
+  async fn children_recursive(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Path(inscription_id): Path<InscriptionId>,
+  ) -> ServerResult {
+    Self::children_recursive_paginated(
+      Extension(index),
+      Extension(server_config),
+      Path((inscription_id, 0)),
+    )
+    .await
+  }
+
+  async fn children_recursive_paginated(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(server_config): Extension<Arc<ServerConfig>>,
+    Path((parent, page)): Path<(InscriptionId, usize)>,
+  ) -> ServerResult {
+    task::block_in_place(|| {
+      let Some(parent) = index.get_inscription_entry(parent)? else {
+        return if let Some(proxy) = server_config.proxy.as_ref() {
+          Self::proxy(proxy, &format!("r/children/{}/{}", parent, page))
+        } else {
+          Err(ServerError::NotFound(format!(
+            "inscription {} not found",
+            parent
+          )))
+        };
+      };
+
+      let parent_sequence_number = parent.sequence_number;
+
+      let (ids, more) =
+        index.get_children_by_sequence_number_paginated(parent_sequence_number, 100, page)?;
+
+      Ok(Json(api::Children { ids, more, page }).into_response())
+    })
+  }
+
+  async fn child_inscriptions_recursive(
     Extension(index): Extension<Arc<Index>>,
     Path(inscription_id): Path<InscriptionId>,
   ) -> ServerResult {
@@ -2011,12 +3308,14 @@ impl Server {
     Extension(server_config): Extension<Arc<ServerConfig>>,
     Extension(index): Extension<Arc<Index>>,
     accept_json: AcceptJson,
+    Query(query): Query<InscriptionsQuery>,
   ) -> ServerResult {
     Self::inscriptions_paginated(
       Extension(server_config),
       Extension(index),
-      Path(0),
+      Path(query.page.unwrap_or_default()),
       accept_json,
+      Query(query),
     )
     .await
   }
@@ -2026,9 +3325,15 @@ impl Server {
     Extension(index): Extension<Arc<Index>>,
     Path(page_index): Path<u32>,
     AcceptJson(accept_json): AcceptJson,
+    Query(query): Query<InscriptionsQuery>,
   ) -> ServerResult {
     task::block_in_place(|| {
-      let (inscriptions, more) = index.get_inscriptions_paginated(100, page_index)?;
+      let filter = query.filter(&index)?;
+
+      let (inscriptions, more) = match &filter {
+        Some(filter) => index.get_filtered_inscriptions_paginated(filter, None, 100, page_index)?,
+        None => index.get_inscriptions_paginated(100, page_index)?,
+      };
 
       let prev = page_index.checked_sub(1);
 
@@ -2043,6 +3348,7 @@ impl Server {
         .into_response()
       } else {
         InscriptionsHtml {
+          filter,
           inscriptions,
           next,
           prev,
@@ -2058,12 +3364,14 @@ impl Server {
     Extension(index): Extension<Arc<Index>>,
     Path(block_height): Path<u32>,
     AcceptJson(accept_json): AcceptJson,
+    Query(query): Query<InscriptionsQuery>,
   ) -> ServerResult {
     Self::inscriptions_in_block_paginated(
       Extension(server_config),
       Extension(index),
-      Path((block_height, 0)),
+      Path((block_height, query.page.unwrap_or_default())),
       AcceptJson(accept_json),
+      Query(query),
     )
     .await
   }
@@ -2073,25 +3381,40 @@ impl Server {
     Extension(index): Extension<Arc<Index>>,
     Path((block_height, page_index)): Path<(u32, u32)>,
     AcceptJson(accept_json): AcceptJson,
+    Query(query): Query<InscriptionsQuery>,
   ) -> ServerResult {
     task::block_in_place(|| {
       let page_size = 100;
 
-      let page_index_usize = usize::try_from(page_index).unwrap_or(usize::MAX);
-      let page_size_usize = usize::try_from(page_size).unwrap_or(usize::MAX);
+      let filter = query.filter(&index)?;
 
-      let mut inscriptions = index
-        .get_inscriptions_in_block(block_height)?
-        .into_iter()
-        .skip(page_index_usize.saturating_mul(page_size_usize))
-        .take(page_size_usize.saturating_add(1))
-        .collect::<Vec<InscriptionId>>();
+      let (inscriptions, more) = match &filter {
+        Some(filter) => index.get_filtered_inscriptions_paginated(
+          filter,
+          Some(block_height),
+          page_size,
+          page_index,
+        )?,
+        None => {
+          let page_index_usize = usize::try_from(page_index).unwrap_or(usize::MAX);
+          let page_size_usize = usize::try_from(page_size).unwrap_or(usize::MAX);
 
-      let more = inscriptions.len() > page_size_usize;
+          let mut inscriptions = index
+            .get_inscriptions_in_block(block_height)?
+            .into_iter()
+            .skip(page_index_usize.saturating_mul(page_size_usize))
+            .take(page_size_usize.saturating_add(1))
+            .collect::<Vec<InscriptionId>>();
 
-      if more {
-        inscriptions.pop();
-      }
+          let more = inscriptions.len() > page_size_usize;
+
+          if more {
+            inscriptions.pop();
+          }
+
+          (inscriptions, more)
+        }
+      };
 
       Ok(if accept_json {
         Json(api::Inscriptions {
@@ -2104,6 +3427,7 @@ impl Server {
         InscriptionsBlockHtml::new(
           block_height,
           index.block_height()?.unwrap_or(Height(0)).n(),
+          filter,
           inscriptions,
           more,
           page_index,
@@ -2355,8 +3679,10 @@ mod tests {
       args.push("--http-port".into());
       args.push(port.to_string());
 
-      args.push("--polling-interval".into());
-      args.push("100ms".into());
+      if !self.server_args.contains_key("--polling-interval") {
+        args.push("--polling-interval".into());
+        args.push("100ms".into());
+      }
 
       for (arg, value) in self.server_args {
         args.push(arg);
@@ -2377,13 +3703,22 @@ mod tests {
         .or_defaults()
         .unwrap();
 
-      let index = Arc::new(Index::open(&settings).unwrap());
+      let (event_sender, event_receiver) = mpsc::channel(1024);
+      let index = Arc::new(if server.read_only {
+        Index::open_read_only(&settings).unwrap()
+      } else {
+        Index::open_with_event_sender(&settings, Some(event_sender)).unwrap()
+      });
       let ord_server_handle = Handle::new();
 
       {
         let index = index.clone();
         let ord_server_handle = ord_server_handle.clone();
-        thread::spawn(|| server.run(settings, index, ord_server_handle).unwrap());
+        thread::spawn(|| {
+          server
+            .run(settings, index, ord_server_handle, event_receiver)
+            .unwrap()
+        });
       }
 
       while index.statistic(crate::index::Statistic::Commits) == 0 {
@@ -2881,6 +4216,115 @@ mod tests {
     TestServer::new().assert_redirect("/search/abc", "/sat/abc");
   }
 
+  #[test]
+  fn search_by_q_returns_sat() {
+    TestServer::new().assert_redirect("/search?q=abc", "/sat/abc");
+  }
+
+  #[test]
+  fn search_with_unknown_type_is_bad_request() {
+    TestServer::new().assert_response(
+      "/search?q=abc&type=foo",
+      StatusCode::BAD_REQUEST,
+      "unknown search type `foo`, expected `text`",
+    );
+  }
+
+  #[test]
+  fn text_search() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-full-text")
+      .build();
+
+    server.mine_blocks(4);
+
+    let mut ids = Vec::new();
+
+    for (i, (content_type, body)) in [
+      ("text/plain;charset=utf-8", "Hello, World!"),
+      ("text/markdown", "# hello\n\nthere"),
+      ("text/html", "<p class=world>world</p>"),
+      ("image/png", "hello"),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+      ids.push(InscriptionId {
+        txid: server.core.broadcast_tx(TransactionTemplate {
+          inputs: &[(i + 1, 0, 0, inscription(content_type, body).to_witness())],
+          ..default()
+        }),
+        index: 0,
+      });
+
+      server.mine_blocks(1);
+    }
+
+    let search = server.get_json::<api::TextSearch>("/search?q=hello%20world&type=text");
+
+    assert_eq!(search.query, "hello world");
+    assert!(!search.more);
+    assert_eq!(search.next, None);
+    assert_eq!(search.prev, None);
+    assert_eq!(
+      search
+        .results
+        .iter()
+        .map(|result| result.id)
+        .collect::<Vec<InscriptionId>>(),
+      [ids[0], ids[2], ids[1]],
+    );
+    assert!(search.results[0].score > search.results[1].score);
+    assert_eq!(search.results[0].snippet, "Hello, World!");
+    assert_eq!(search.results[1].snippet, "world");
+    assert_eq!(search.results[2].snippet, "# hello there");
+
+    assert_eq!(
+      server
+        .get_json::<api::TextSearch>("/search?q=PNG&type=text")
+        .results,
+      [],
+    );
+
+    assert_eq!(
+      server
+        .get_json::<api::TextSearch>("/search?q=hello&type=text&page=1")
+        .results,
+      [],
+    );
+
+    server.assert_response_regex(
+      "/search?q=there&type=text",
+      StatusCode::OK,
+      format!(
+        ".*<h1>Search Results</h1>.*<a class=collapse href=/inscription/{0}>{0}</a>\\s*<p># hello there</p>.*",
+        ids[1]
+      ),
+    );
+
+    server.assert_response(
+      "/search?q=%20!!&type=text",
+      StatusCode::BAD_REQUEST,
+      "search query contains no searchable terms",
+    );
+
+    server.assert_response(
+      "/search?q=a+b+c+d+e+f+g+h+i&type=text",
+      StatusCode::BAD_REQUEST,
+      "search query may contain at most 8 terms",
+    );
+  }
+
+  #[test]
+  fn text_search_requires_full_text_index() {
+    TestServer::new().assert_response(
+      "/search?q=abc&type=text",
+      StatusCode::NOT_FOUND,
+      "this server has no full-text index",
+    );
+  }
+
   #[test]
   fn search_for_blockhash_returns_block() {
     TestServer::new().assert_redirect(
@@ -3333,6 +4777,7 @@ mod tests {
       RuneHtml {
         id,
         entry,
+        holders: 1,
         mintable: false,
         parent: Some(parent),
       },
@@ -3562,29 +5007,23 @@ mod tests {
   }
 
   #[test]
-  fn runes_are_displayed_on_output_page() {
+  fn rune_activity() {
     let server = TestServer::builder()
       .chain(Chain::Regtest)
       .index_runes()
+      .ord_flag("--index-rune-activity")
       .build();
 
     server.mine_blocks(1);
 
     let rune = Rune(RUNE);
 
-    server.assert_response_regex(format!("/rune/{rune}"), StatusCode::NOT_FOUND, ".*");
-
-    let (txid, id) = server.etch(
+    let (etching, id) = server.etch(
       Runestone {
-        edicts: vec![Edict {
-          id: RuneId::default(),
-          amount: u128::MAX,
-          output: 0,
-        }],
         etching: Some(Etching {
-          divisibility: Some(1),
           rune: Some(rune),
-          premine: Some(u128::MAX),
+          premine: Some(1000),
+          symbol: Some('%'),
           ..default()
         }),
         ..default()
@@ -3593,36 +5032,1182 @@ mod tests {
       None,
     );
 
-    pretty_assert_eq!(
-      server.index.runes().unwrap(),
-      [(
-        id,
-        RuneEntry {
-          block: id.block,
-          divisibility: 1,
-          etching: txid,
-          spaced_rune: SpacedRune { rune, spacers: 0 },
-          premine: u128::MAX,
-          timestamp: id.block,
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 0, Witness::new())],
+      op_return: Some(
+        Runestone {
+          edicts: vec![Edict {
+            id,
+            amount: 100,
+            output: 1,
+          }],
           ..default()
         }
-      )]
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let height = u32::try_from(id.block).unwrap();
+
+    let entries = vec![
+      RuneActivityEntry {
+        amount: 100,
+        height: height + 1,
+        inputs: vec![OutPoint {
+          txid: etching,
+          vout: 0,
+        }],
+        kind: RuneActivityKind::Burn,
+        output: Some(OutPoint { txid, vout: 1 }),
+        txid,
+      },
+      RuneActivityEntry {
+        amount: 900,
+        height: height + 1,
+        inputs: vec![OutPoint {
+          txid: etching,
+          vout: 0,
+        }],
+        kind: RuneActivityKind::Transfer,
+        output: Some(OutPoint { txid, vout: 0 }),
+        txid,
+      },
+      RuneActivityEntry {
+        amount: 1000,
+        height,
+        inputs: Vec::new(),
+        kind: RuneActivityKind::Transfer,
+        output: Some(OutPoint {
+          txid: etching,
+          vout: 0,
+        }),
+        txid: etching,
+      },
+      RuneActivityEntry {
+        amount: 1000,
+        height,
+        inputs: Vec::new(),
+        kind: RuneActivityKind::Mint,
+        output: None,
+        txid: etching,
+      },
+    ];
+
+    pretty_assert_eq!(
+      server.get_json::<api::RuneActivity>(format!("/rune/{rune}/activity")),
+      api::RuneActivity {
+        divisibility: 0,
+        entries: entries.clone(),
+        id,
+        more: false,
+        next: None,
+        prev: None,
+        rune: SpacedRune { rune, spacers: 0 },
+        symbol: Some('%'),
+      }
     );
 
-    let output = OutPoint { txid, vout: 0 };
+    pretty_assert_eq!(
+      server.index.get_rune_activity_paginated(id, 2, 0).unwrap(),
+      (entries[..2].to_vec(), true)
+    );
 
-    assert_eq!(
-      server.index.get_rune_balances().unwrap(),
-      [(output, vec![(id, u128::MAX)])]
+    pretty_assert_eq!(
+      server.index.get_rune_activity_paginated(id, 2, 1).unwrap(),
+      (entries[2..].to_vec(), false)
     );
 
     server.assert_response_regex(
-      format!("/output/{output}"),
+      format!("/rune/{id}/activity"),
       StatusCode::OK,
       format!(
-        ".*<title>Output {output}</title>.*<h1>Output <span class=monospace>{output}</span></h1>.*
-  <dt>runes</dt>
-  <dd>
+        ".*<title>Rune {rune} Activity</title>.*
+<h1><a href=/rune/{rune}>{rune}</a> Activity</h1>
+<ul>
+  <li>
+    <dl>
+      <dt>kind</dt>
+      <dd>burn</dd>
+      <dt>amount</dt>
+      <dd>100\u{A0}%</dd>
+.*
+      <dt>to</dt>
+      <dd><a class=collapse href=/output/{txid}:1>{txid}:1</a></dd>
+    </dl>
+  </li>
+.*"
+      ),
+    );
+
+    server.assert_response_regex(
+      format!("/rune/{rune}/activity/1"),
+      StatusCode::OK,
+      ".*<h3>No activity</h3>.*",
+    );
+  }
+
+  #[test]
+  fn rune_activity_requires_index() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.mine_blocks(1);
+
+    server.assert_response(
+      format!("/rune/{}/activity", Rune(RUNE)),
+      StatusCode::NOT_FOUND,
+      "this server has no rune activity index",
+    );
+  }
+
+  #[test]
+  fn rune_holders() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.mine_blocks(1);
+
+    let rune = Rune(RUNE);
+
+    let (etching, id) = server.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(rune),
+          premine: Some(1000),
+          symbol: Some('%'),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    pretty_assert_eq!(
+      server
+        .get_json::<api::RuneHolders>(format!("/rune/{rune}/holders"))
+        .holders,
+      vec![api::RuneHolder {
+        address: None,
+        amount: 1000,
+        outpoint: Some(OutPoint {
+          txid: etching,
+          vout: 0,
+        }),
+        script_pubkey: None,
+      }]
+    );
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 0, Witness::new())],
+      outputs: 2,
+      op_return: Some(
+        Runestone {
+          edicts: vec![Edict {
+            id,
+            amount: 100,
+            output: 1,
+          }],
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let holders = vec![
+      api::RuneHolder {
+        address: None,
+        amount: 900,
+        outpoint: Some(OutPoint { txid, vout: 0 }),
+        script_pubkey: None,
+      },
+      api::RuneHolder {
+        address: None,
+        amount: 100,
+        outpoint: Some(OutPoint { txid, vout: 1 }),
+        script_pubkey: None,
+      },
+    ];
+
+    pretty_assert_eq!(
+      server.get_json::<api::RuneHolders>(format!("/rune/{rune}/holders")),
+      api::RuneHolders {
+        divisibility: 0,
+        holders: holders.clone(),
+        id,
+        more: false,
+        next: None,
+        prev: None,
+        rune: SpacedRune { rune, spacers: 0 },
+        symbol: Some('%'),
+      }
+    );
+
+    pretty_assert_eq!(
+      server.index.get_rune_holders_paginated(id, 1, 1).unwrap(),
+      (holders[1..].to_vec(), false)
+    );
+
+    assert_eq!(
+      server.get_json::<api::Rune>(format!("/rune/{id}")).holders,
+      2
+    );
+
+    server.assert_response_regex(
+      format!("/rune/{id}/holders"),
+      StatusCode::OK,
+      format!(
+        ".*<title>Rune {rune} Holders</title>.*
+<h1><a href=/rune/{rune}>{rune}</a> Holders</h1>
+<ul>
+  <li>
+    <dl>
+      <dt>output</dt>
+      <dd><a class=collapse href=/output/{txid}:0>{txid}:0</a></dd>
+      <dt>balance</dt>
+      <dd>900\u{A0}%</dd>
+    </dl>
+  </li>
+.*"
+      ),
+    );
+
+    server.assert_response_regex(
+      format!("/rune/{rune}"),
+      StatusCode::OK,
+      format!(".*<dt>holders</dt>\n  <dd><a href=/rune/{rune}/holders>2</a></dd>.*"),
+    );
+  }
+
+  #[test]
+  fn rune_holders_are_aggregated_by_address() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .ord_flag("--index-addresses")
+      .build();
+
+    server.mine_blocks(1);
+
+    let rune = Rune(RUNE);
+
+    let (_etching, id) = server.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(rune),
+          premine: Some(1000),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 0, Witness::new())],
+      outputs: 2,
+      op_return: Some(
+        Runestone {
+          edicts: vec![Edict {
+            id,
+            amount: 100,
+            output: 1,
+          }],
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let script_pubkey = ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros());
+
+    let holder = |script_pubkey: &ScriptBuf, amount| api::RuneHolder {
+      address: Some(
+        Chain::Regtest
+          .address_from_script(script_pubkey)
+          .unwrap()
+          .to_string(),
+      ),
+      amount,
+      outpoint: None,
+      script_pubkey: Some(script_pubkey.clone()),
+    };
+
+    pretty_assert_eq!(
+      server
+        .get_json::<api::RuneHolders>(format!("/rune/{rune}/holders"))
+        .holders,
+      vec![holder(&script_pubkey, 1000)]
+    );
+
+    let block = usize::try_from(id.block).unwrap();
+
+    let recipient = address(0);
+
+    server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(block + 1, 1, 1, Witness::new())],
+      recipient: Some(recipient.clone()),
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    pretty_assert_eq!(
+      server
+        .get_json::<api::RuneHolders>(format!("/rune/{rune}/holders"))
+        .holders,
+      vec![
+        holder(&script_pubkey, 900),
+        holder(&recipient.script_pubkey(), 100)
+      ]
+    );
+
+    assert_eq!(
+      server
+        .get_json::<api::Rune>(format!("/rune/{rune}"))
+        .holders,
+      2
+    );
+
+    server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[
+        (block + 1, 1, 0, Witness::new()),
+        (block + 2, 1, 0, Witness::new()),
+      ],
+      recipient: Some(recipient.clone()),
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    pretty_assert_eq!(
+      server
+        .get_json::<api::RuneHolders>(format!("/rune/{rune}/holders"))
+        .holders,
+      vec![holder(&recipient.script_pubkey(), 1000)]
+    );
+  }
+
+  #[test]
+  fn address_history() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-addresses")
+      .ord_flag("--index-address-history")
+      .build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
+      ..default()
+    });
+
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    server.mine_blocks(1);
+
+    let recipient = Chain::Regtest
+      .address_from_script(&address(0).script_pubkey())
+      .unwrap();
+
+    let send = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Default::default())],
+      recipient: Some(recipient.clone()),
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let value = server.core.tx_by_id(send).output[0].value.to_sat();
+
+    pretty_assert_eq!(
+      server.get_json::<api::AddressHistory>(format!("/address/{recipient}/history")),
+      api::AddressHistory {
+        entries: vec![api::AddressHistoryEntry {
+          height: 3,
+          inscriptions_received: vec![inscription_id],
+          inscriptions_sent: Vec::new(),
+          received: value,
+          runes_received: Vec::new(),
+          runes_sent: Vec::new(),
+          sent: 0,
+          txid: send,
+        }],
+        next: None,
+      }
+    );
+
+    let sender = Chain::Regtest
+      .address_from_script(&ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()))
+      .unwrap();
+
+    let history = server.get_json::<api::AddressHistory>(format!("/address/{sender}/history"));
+
+    pretty_assert_eq!(
+      history.entries[0],
+      api::AddressHistoryEntry {
+        height: 3,
+        inscriptions_received: Vec::new(),
+        inscriptions_sent: vec![inscription_id],
+        received: 0,
+        runes_received: Vec::new(),
+        runes_sent: Vec::new(),
+        sent: value,
+        txid: send,
+      }
+    );
+
+    pretty_assert_eq!(
+      history.entries[1],
+      api::AddressHistoryEntry {
+        height: 2,
+        inscriptions_received: vec![inscription_id],
+        inscriptions_sent: Vec::new(),
+        received: value,
+        runes_received: Vec::new(),
+        runes_sent: Vec::new(),
+        sent: 0,
+        txid,
+      }
+    );
+
+    pretty_assert_eq!(
+      server
+        .get_json::<api::AddressHistory>(format!("/address/{sender}/history?cursor=3:0"))
+        .entries,
+      history.entries[1..],
+    );
+
+    server.assert_response_regex(
+      format!("/address/{recipient}/history"),
+      StatusCode::OK,
+      format!(
+        ".*<dd><a class=collapse href=/tx/{send}>{send}</a></dd>.*<dt>received</dt>\\s*<dd>{value} sat</dd>.*"
+      ),
+    );
+
+    server.assert_response(
+      format!("/address/{recipient}/history?cursor=foo"),
+      StatusCode::BAD_REQUEST,
+      "invalid cursor `foo`",
+    );
+  }
+
+  #[test]
+  fn address_history_tracks_runes() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .ord_flag("--index-addresses")
+      .ord_flag("--index-address-history")
+      .build();
+
+    server.mine_blocks(1);
+
+    let rune = Rune(RUNE);
+
+    let (_etching, id) = server.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(rune),
+          premine: Some(1000),
+          symbol: Some('$'),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    let recipient = Chain::Regtest
+      .address_from_script(&address(0).script_pubkey())
+      .unwrap();
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 0, Witness::new())],
+      recipient: Some(recipient.clone()),
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let amount = |value| {
+      vec![(
+        SpacedRune { rune, spacers: 0 },
+        Decimal { value, scale: 0 },
+        Some('$'),
+      )]
+    };
+
+    let entry = server
+      .get_json::<api::AddressHistory>(format!("/address/{recipient}/history"))
+      .entries
+      .remove(0);
+
+    assert_eq!(entry.txid, txid);
+    assert_eq!(entry.runes_received, amount(1000));
+    assert_eq!(entry.runes_sent, Vec::new());
+
+    let sender = Chain::Regtest
+      .address_from_script(&ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()))
+      .unwrap();
+
+    let entry = server
+      .get_json::<api::AddressHistory>(format!("/address/{sender}/history"))
+      .entries
+      .remove(0);
+
+    assert_eq!(entry.txid, txid);
+    assert_eq!(entry.runes_received, Vec::new());
+    assert_eq!(entry.runes_sent, amount(1000));
+  }
+
+  #[test]
+  fn address_runes() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .ord_flag("--index-addresses")
+      .build();
+
+    server.mine_blocks(1);
+
+    let rune = Rune(RUNE);
+
+    let (_etching, id) = server.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(rune),
+          premine: Some(1000),
+          symbol: Some('$'),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    let sender = Chain::Regtest
+      .address_from_script(&ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()))
+      .unwrap();
+
+    let balance = vec![(
+      SpacedRune { rune, spacers: 0 },
+      Decimal {
+        value: 1000,
+        scale: 0,
+      },
+      Some('$'),
+    )];
+
+    pretty_assert_eq!(
+      server
+        .get_json::<Vec<(SpacedRune, Decimal, Option<char>)>>(format!("/address/{sender}/runes")),
+      balance,
+    );
+
+    let recipient = Chain::Regtest
+      .address_from_script(&address(0).script_pubkey())
+      .unwrap();
+
+    server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 0, Witness::new())],
+      recipient: Some(recipient.clone()),
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    pretty_assert_eq!(
+      server
+        .get_json::<Vec<(SpacedRune, Decimal, Option<char>)>>(format!("/address/{sender}/runes")),
+      Vec::new(),
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<Vec<(SpacedRune, Decimal, Option<char>)>>(format!(
+        "/address/{recipient}/runes"
+      )),
+      balance,
+    );
+
+    pretty_assert_eq!(
+      server
+        .get_json::<api::AddressInfo>(format!("/address/{recipient}"))
+        .runes_balances,
+      balance,
+    );
+  }
+
+  #[test]
+  fn address_runes_requires_address_and_rune_indices() {
+    let address = Chain::Regtest
+      .address_from_script(&address(0).script_pubkey())
+      .unwrap();
+
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.assert_response(
+      format!("/address/{address}/runes"),
+      StatusCode::NOT_FOUND,
+      "this server has no address index",
+    );
+
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-addresses")
+      .build();
+
+    server.assert_response(
+      format!("/address/{address}/runes"),
+      StatusCode::NOT_FOUND,
+      "this server has no rune index",
+    );
+  }
+
+  #[test]
+  fn mempool_inscriptions() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .server_flag("--mempool")
+      .build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
+      ..default()
+    });
+
+    server.index.update_mempool().unwrap();
+
+    let id = InscriptionId { txid, index: 0 };
+
+    pretty_assert_eq!(
+      server.get_json::<Vec<api::MempoolInscription>>("/mempool/inscriptions"),
+      vec![api::MempoolInscription {
+        id,
+        new: true,
+        satpoint: Some(SatPoint {
+          outpoint: OutPoint { txid, vout: 0 },
+          offset: 0,
+        }),
+        txid,
+      }],
+    );
+
+    pretty_assert_eq!(
+      server
+        .get_json::<api::Output>(format!("/output/{txid}:0"))
+        .pending,
+      Some(api::PendingOutput {
+        inscriptions: vec![id],
+        runes: BTreeMap::new(),
+        spent_by: None,
+      }),
+    );
+
+    let coinbase = server.core.tx(1, 0).compute_txid();
+
+    pretty_assert_eq!(
+      server
+        .get_json::<api::Output>(format!("/output/{coinbase}:0"))
+        .pending,
+      Some(api::PendingOutput {
+        inscriptions: Vec::new(),
+        runes: BTreeMap::new(),
+        spent_by: Some(txid),
+      }),
+    );
+
+    server.mine_blocks(1);
+
+    server.index.update_mempool().unwrap();
+
+    pretty_assert_eq!(
+      server.get_json::<Vec<api::MempoolInscription>>("/mempool/inscriptions"),
+      Vec::new(),
+    );
+
+    pretty_assert_eq!(
+      server
+        .get_json::<api::Output>(format!("/output/{txid}:0"))
+        .pending,
+      None,
+    );
+
+    let transfer = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Default::default())],
+      fee: 50 * COIN_VALUE,
+      ..default()
+    });
+
+    server.index.update_mempool().unwrap();
+
+    pretty_assert_eq!(
+      server.get_json::<Vec<api::MempoolInscription>>("/mempool/inscriptions"),
+      vec![api::MempoolInscription {
+        id,
+        new: false,
+        satpoint: None,
+        txid: transfer,
+      }],
+    );
+  }
+
+  #[test]
+  fn mempool_runes() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .ord_flag("--index-addresses")
+      .server_flag("--mempool")
+      .build();
+
+    server.mine_blocks(1);
+
+    let rune = Rune(RUNE);
+
+    let (etching, id) = server.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(rune),
+          premine: Some(1000),
+          symbol: Some('$'),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    let recipient = Chain::Regtest
+      .address_from_script(&address(0).script_pubkey())
+      .unwrap();
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(id.block.try_into().unwrap(), 1, 0, Witness::new())],
+      recipient: Some(recipient.clone()),
+      ..default()
+    });
+
+    server.index.update_mempool().unwrap();
+
+    let spaced_rune = SpacedRune { rune, spacers: 0 };
+
+    pretty_assert_eq!(
+      server.get_json::<Vec<api::MempoolRuneOutput>>("/mempool/runes"),
+      vec![api::MempoolRuneOutput {
+        address: Some(recipient.as_unchecked().clone()),
+        outpoint: OutPoint { txid, vout: 0 },
+        runes: [(
+          spaced_rune,
+          Pile {
+            amount: 1000,
+            divisibility: 0,
+            symbol: Some('$'),
+          },
+        )]
+        .into(),
+      }],
+    );
+
+    pretty_assert_eq!(
+      server
+        .get_json::<api::AddressInfo>(format!("/address/{recipient}"))
+        .pending,
+      Some(api::PendingAddress {
+        inscriptions: Vec::new(),
+        outputs: vec![OutPoint { txid, vout: 0 }],
+        runes_balances: vec![(
+          spaced_rune,
+          Decimal {
+            value: 1000,
+            scale: 0,
+          },
+          Some('$'),
+        )],
+        spent: Vec::new(),
+      }),
+    );
+
+    let sender = Chain::Regtest
+      .address_from_script(&ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros()))
+      .unwrap();
+
+    assert!(server
+      .get_json::<api::AddressInfo>(format!("/address/{sender}"))
+      .pending
+      .unwrap()
+      .spent
+      .contains(&OutPoint {
+        txid: etching,
+        vout: 0
+      }));
+  }
+
+  #[test]
+  fn mempool_endpoints_require_mempool_flag() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.assert_response(
+      "/mempool/inscriptions",
+      StatusCode::NOT_FOUND,
+      "this server is not watching the mempool",
+    );
+
+    server.assert_response(
+      "/mempool/runes",
+      StatusCode::NOT_FOUND,
+      "this server is not watching the mempool",
+    );
+  }
+
+  #[test]
+  fn zmq_block_notifications_trigger_index_update() {
+    let core = mockcore::builder().zmq(true).build();
+
+    let server = TestServer::builder()
+      .ord_option("--bitcoin-zmq-url", &core.zmq_url())
+      .server_option("--polling-interval", "1h")
+      .core(core)
+      .build();
+
+    for _ in 0..3 {
+      server.core.mine_blocks(1);
+
+      let height = server.core.height();
+
+      for attempt in 0.. {
+        if server.index.block_height().unwrap() == Some(Height(height.try_into().unwrap())) {
+          break;
+        }
+
+        assert!(
+          attempt < 100,
+          "index did not update after block notification"
+        );
+
+        thread::sleep(Duration::from_millis(50));
+      }
+    }
+  }
+
+  #[test]
+  fn zmq_transaction_notifications_trigger_mempool_update() {
+    let core = mockcore::builder()
+      .network(Network::Regtest)
+      .zmq(true)
+      .build();
+
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_option("--bitcoin-zmq-url", &core.zmq_url())
+      .server_flag("--mempool")
+      .server_option("--polling-interval", "1h")
+      .core(core)
+      .build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
+      ..default()
+    });
+
+    for attempt in 0.. {
+      if server
+        .get_json::<Vec<api::MempoolInscription>>("/mempool/inscriptions")
+        .iter()
+        .any(|inscription| inscription.txid == txid)
+      {
+        break;
+      }
+
+      assert!(
+        attempt < 100,
+        "mempool did not update after transaction notification"
+      );
+
+      thread::sleep(Duration::from_millis(50));
+    }
+  }
+
+  #[test]
+  fn address_history_requires_address_history_index() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-addresses")
+      .build();
+
+    server.mine_blocks(1);
+
+    let address = Chain::Regtest
+      .address_from_script(&address(0).script_pubkey())
+      .unwrap();
+
+    server.assert_response(
+      format!("/address/{address}/history"),
+      StatusCode::NOT_FOUND,
+      "this server has no address history index",
+    );
+  }
+
+  #[test]
+  fn rune_snapshot() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .ord_flag("--index-rune-activity")
+      .build();
+
+    server.mine_blocks(1);
+
+    let rune = Rune(RUNE);
+
+    let (etching, id) = server.etch(
+      Runestone {
+        etching: Some(Etching {
+          rune: Some(rune),
+          premine: Some(1000),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    let block = usize::try_from(id.block).unwrap();
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(block, 1, 0, Witness::new())],
+      outputs: 2,
+      op_return: Some(
+        Runestone {
+          edicts: vec![Edict {
+            id,
+            amount: 100,
+            output: 1,
+          }],
+          ..default()
+        }
+        .encipher(),
+      ),
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let recipient = address(0);
+
+    let transfer = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(block + 1, 1, 1, Witness::new())],
+      recipient: Some(recipient.clone()),
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let height = u32::try_from(id.block).unwrap();
+
+    let holders = |height: u32, query: &str| {
+      server
+        .get_json::<api::RuneSnapshot>(format!("/rune/{rune}/snapshot/{height}{query}"))
+        .holders
+    };
+
+    let output = |outpoint: OutPoint, amount: u128| api::RuneHolder {
+      address: None,
+      amount,
+      outpoint: Some(outpoint),
+      script_pubkey: None,
+    };
+
+    pretty_assert_eq!(holders(height - 1, ""), Vec::new());
+
+    pretty_assert_eq!(
+      holders(height, ""),
+      vec![output(
+        OutPoint {
+          txid: etching,
+          vout: 0
+        },
+        1000
+      )]
+    );
+
+    pretty_assert_eq!(
+      holders(height + 1, ""),
+      vec![
+        output(OutPoint { txid, vout: 0 }, 900),
+        output(OutPoint { txid, vout: 1 }, 100),
+      ]
+    );
+
+    pretty_assert_eq!(
+      holders(height + 2, ""),
+      vec![
+        output(OutPoint { txid, vout: 0 }, 900),
+        output(
+          OutPoint {
+            txid: transfer,
+            vout: 0
+          },
+          100
+        ),
+      ]
+    );
+
+    let script_pubkey = ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros());
+
+    pretty_assert_eq!(
+      server.get_json::<api::RuneSnapshot>(format!(
+        "/rune/{id}/snapshot/{}?by_address=true",
+        height + 2
+      )),
+      api::RuneSnapshot {
+        divisibility: 0,
+        height: height + 2,
+        holders: vec![
+          api::RuneHolder {
+            address: Some(
+              Chain::Regtest
+                .address_from_script(&script_pubkey)
+                .unwrap()
+                .to_string()
+            ),
+            amount: 900,
+            outpoint: None,
+            script_pubkey: Some(script_pubkey),
+          },
+          api::RuneHolder {
+            address: Some(
+              Chain::Regtest
+                .address_from_script(&recipient.script_pubkey())
+                .unwrap()
+                .to_string()
+            ),
+            amount: 100,
+            outpoint: None,
+            script_pubkey: Some(recipient.script_pubkey()),
+          },
+        ],
+        id,
+        rune: SpacedRune { rune, spacers: 0 },
+        symbol: None,
+      }
+    );
+
+    server.assert_response(
+      format!("/rune/{rune}/snapshot/{}", height + 3),
+      StatusCode::BAD_REQUEST,
+      &format!("height {} is above index height {}", height + 3, height + 2),
+    );
+  }
+
+  #[test]
+  fn rune_snapshot_requires_rune_activity_index() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.mine_blocks(1);
+
+    server.assert_response(
+      format!("/rune/{}/snapshot/0", Rune(RUNE)),
+      StatusCode::NOT_FOUND,
+      "this server has no rune activity index",
+    );
+  }
+
+  #[test]
+  fn runes_are_displayed_on_output_page() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_runes()
+      .build();
+
+    server.mine_blocks(1);
+
+    let rune = Rune(RUNE);
+
+    server.assert_response_regex(format!("/rune/{rune}"), StatusCode::NOT_FOUND, ".*");
+
+    let (txid, id) = server.etch(
+      Runestone {
+        edicts: vec![Edict {
+          id: RuneId::default(),
+          amount: u128::MAX,
+          output: 0,
+        }],
+        etching: Some(Etching {
+          divisibility: Some(1),
+          rune: Some(rune),
+          premine: Some(u128::MAX),
+          ..default()
+        }),
+        ..default()
+      },
+      1,
+      None,
+    );
+
+    pretty_assert_eq!(
+      server.index.runes().unwrap(),
+      [(
+        id,
+        RuneEntry {
+          block: id.block,
+          divisibility: 1,
+          etching: txid,
+          spaced_rune: SpacedRune { rune, spacers: 0 },
+          premine: u128::MAX,
+          timestamp: id.block,
+          ..default()
+        }
+      )]
+    );
+
+    let output = OutPoint { txid, vout: 0 };
+
+    assert_eq!(
+      server.index.get_rune_balances().unwrap(),
+      [(output, vec![(id, u128::MAX)])]
+    );
+
+    server.assert_response_regex(
+      format!("/output/{output}"),
+      StatusCode::OK,
+      format!(
+        ".*<title>Output {output}</title>.*<h1>Output <span class=monospace>{output}</span></h1>.*
+  <dt>runes</dt>
+  <dd>
     <table>
       <tr>
         <th>rune</th>
@@ -3651,6 +6236,7 @@ mod tests {
         indexed: true,
         inscriptions: Vec::new(),
         outpoint: output,
+        pending: None,
         runes: vec![(
           SpacedRune {
             rune: Rune(RUNE),
@@ -3670,24 +6256,185 @@ mod tests {
   }
 
   #[test]
-  fn http_to_https_redirect_with_path() {
+  fn http_to_https_redirect_with_path() {
+    TestServer::builder()
+      .redirect_http_to_https()
+      .https()
+      .build()
+      .assert_redirect(
+        "/sat/0",
+        &format!("https://{}/sat/0", System::host_name().unwrap()),
+      );
+  }
+
+  #[test]
+  fn http_to_https_redirect_with_empty() {
+    TestServer::builder()
+      .redirect_http_to_https()
+      .https()
+      .build()
+      .assert_redirect("/", &format!("https://{}/", System::host_name().unwrap()));
+  }
+
+  #[test]
+  fn events_are_streamed_as_server_sent_events() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    let response = reqwest::blocking::Client::builder()
+      .timeout(Duration::from_secs(30))
+      .build()
+      .unwrap()
+      .get(server.join_url("/events?kind=inscription_created"))
+      .send()
+      .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CONTENT_TYPE).unwrap(),
+      "text/event-stream"
+    );
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let mut lines = io::BufRead::lines(BufReader::new(response));
+
+    assert_eq!(lines.next().unwrap().unwrap(), "event:inscription_created");
+
+    let event = serde_json::from_str::<Event>(
+      lines
+        .next()
+        .unwrap()
+        .unwrap()
+        .strip_prefix("data:")
+        .unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(event.kind(), "inscription_created");
+    assert_eq!(
+      event.inscription_id(),
+      Some(InscriptionId { txid, index: 0 })
+    );
+  }
+
+  #[test]
+  fn event_log_endpoints() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-events")
+      .build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let events = server.get_json::<Vec<api::EventEntry>>("/events/block/2");
+
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].id, 0);
+    assert_eq!(
+      events[0].event.inscription_id(),
+      Some(InscriptionId { txid, index: 0 })
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<api::Events>("/events?from=0"),
+      api::Events {
+        events,
+        more: false,
+        next: 1,
+      }
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<api::Events>("/events?from=1"),
+      api::Events {
+        events: Vec::new(),
+        more: false,
+        next: 1,
+      }
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<api::Events>("/events?from=0&kind=inscription_created"),
+      server.get_json::<api::Events>("/events?from=0"),
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<api::Events>("/events?from=0&kind=rune_minted"),
+      api::Events {
+        events: Vec::new(),
+        more: false,
+        next: 1,
+      }
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<api::Events>(format!(
+        "/events?from=0&inscription_id={}",
+        InscriptionId { txid, index: 1 }
+      )),
+      api::Events {
+        events: Vec::new(),
+        more: false,
+        next: 1,
+      }
+    );
+  }
+
+  #[test]
+  fn event_log_endpoints_require_event_index() {
+    let server = TestServer::new();
+
+    server.assert_response(
+      "/events/block/0",
+      StatusCode::NOT_FOUND,
+      "this server has no event index",
+    );
+
+    server.assert_response(
+      "/events?from=0",
+      StatusCode::NOT_FOUND,
+      "this server has no event index",
+    );
+
+    server.assert_response(
+      "/events?kind=inscription_created,undo",
+      StatusCode::NOT_FOUND,
+      "this server has no event index",
+    );
+  }
+
+  #[test]
+  fn events_address_filter_requires_matching_network() {
     TestServer::builder()
-      .redirect_http_to_https()
-      .https()
+      .chain(Chain::Regtest)
       .build()
-      .assert_redirect(
-        "/sat/0",
-        &format!("https://{}/sat/0", System::host_name().unwrap()),
+      .assert_response(
+        "/events?address=bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+        StatusCode::BAD_REQUEST,
+        "validation error",
       );
   }
 
   #[test]
-  fn http_to_https_redirect_with_empty() {
+  fn events_with_json_api_disabled() {
     TestServer::builder()
-      .redirect_http_to_https()
-      .https()
+      .server_flag("--disable-json-api")
       .build()
-      .assert_redirect("/", &format!("https://{}/", System::host_name().unwrap()));
+      .assert_response("/events", StatusCode::NOT_ACCEPTABLE, "JSON API disabled");
   }
 
   #[test]
@@ -3787,6 +6534,67 @@ mod tests {
     );
   }
 
+  #[test]
+  fn metrics() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let inscription_id = InscriptionId { txid, index: 0 };
+
+    assert_eq!(
+      server
+        .get(format!("/inscription/{inscription_id}"))
+        .status(),
+      StatusCode::OK
+    );
+    assert_eq!(server.get("/blockcount").status(), StatusCode::OK);
+    assert_eq!(server.get("/blockcount").status(), StatusCode::OK);
+    assert_eq!(server.get("/foo").status(), StatusCode::NOT_FOUND);
+
+    let response = server.get("/metrics");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CONTENT_TYPE).unwrap(),
+      "text/plain; version=0.0.4"
+    );
+
+    let metrics = response.text().unwrap();
+
+    for line in [
+      "# TYPE ord_blessed_inscriptions gauge",
+      "ord_blessed_inscriptions 1",
+      "ord_cursed_inscriptions 0",
+      "ord_runes 0",
+      "ord_blocks_indexed 3",
+      "ord_chain_blocks 3",
+      "ord_reorgs_total 0",
+      "ord_updater_block_duration_seconds_count 3",
+      "ord_http_requests_total{route=\"/inscription/:inscription_query\",status=\"200\"} 1",
+      "ord_http_requests_total{route=\"/blockcount\",status=\"200\"} 2",
+      "ord_http_requests_total{route=\"fallback\",status=\"404\"} 1",
+      "ord_http_request_duration_seconds_count{route=\"/blockcount\"} 2",
+      "ord_http_request_duration_seconds_bucket{route=\"/blockcount\",le=\"+Inf\"} 2",
+    ] {
+      assert!(
+        metrics.lines().any(|l| l == line),
+        "missing `{line}` in metrics:\n{metrics}"
+      );
+    }
+
+    assert!(metrics
+      .lines()
+      .any(|line| line.starts_with("ord_table_stored_bytes{table=\"HEIGHT_TO_BLOCK_HEADER\"} ")));
+  }
+
   #[test]
   fn block_count_endpoint() {
     let test_server = TestServer::new();
@@ -5075,101 +7883,367 @@ mod tests {
 
     server.mine_blocks(1);
 
-    server.assert_response(
-      format!("/preview/{inscription_id}"),
-      StatusCode::OK,
-      &fs::read_to_string("templates/preview-unknown.html").unwrap(),
-    );
-  }
+    server.assert_response(
+      format!("/preview/{inscription_id}"),
+      StatusCode::OK,
+      &fs::read_to_string("templates/preview-unknown.html").unwrap(),
+    );
+  }
+
+  #[test]
+  fn content_responses_have_cache_control_headers() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/foo", "hello").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let response = server.get(format!("/content/{}", InscriptionId { txid, index: 0 }));
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CACHE_CONTROL).unwrap(),
+      "public, max-age=1209600, immutable"
+    );
+  }
+
+  #[test]
+  fn error_content_responses_have_max_age_zero_cache_control_headers() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+    let response =
+      server.get("/content/6ac5cacb768794f4fd7a78bf00f2074891fce68bd65c4ff36e77177237aacacai0");
+
+    assert_eq!(response.status(), 404);
+    assert_eq!(
+      response.headers().get(header::CACHE_CONTROL).unwrap(),
+      "no-store"
+    );
+  }
+
+  #[test]
+  fn inscriptions_page_with_no_prev_or_next() {
+    TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_sats()
+      .build()
+      .assert_response_regex("/inscriptions", StatusCode::OK, ".*prev\nnext.*");
+  }
+
+  #[test]
+  fn inscriptions_page_with_no_next() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_sats()
+      .build();
+
+    for i in 0..101 {
+      server.mine_blocks(1);
+      server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(i + 1, 0, 0, inscription("text/foo", "hello").to_witness())],
+        ..default()
+      });
+    }
+
+    server.mine_blocks(1);
+
+    server.assert_response_regex(
+      "/inscriptions/1",
+      StatusCode::OK,
+      ".*<a class=prev href=/inscriptions/0>prev</a>\nnext.*",
+    );
+  }
+
+  #[test]
+  fn inscriptions_page_with_no_prev() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_sats()
+      .build();
+
+    for i in 0..101 {
+      server.mine_blocks(1);
+      server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(i + 1, 0, 0, inscription("text/foo", "hello").to_witness())],
+        ..default()
+      });
+    }
+
+    server.mine_blocks(1);
+
+    server.assert_response_regex(
+      "/inscriptions/0",
+      StatusCode::OK,
+      ".*prev\n<a class=next href=/inscriptions/1>next</a>.*",
+    );
+  }
+
+  #[test]
+  fn collection_holders() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-addresses")
+      .ord_flag("--index-inscription-history")
+      .build();
+
+    server.mine_blocks(1);
+
+    let parent = InscriptionId {
+      txid: server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, inscription("text/plain", "parent").to_witness())],
+        ..default()
+      }),
+      index: 0,
+    };
+
+    server.mine_blocks(1);
+
+    let mut children = Vec::new();
+
+    for block in 2..5 {
+      children.push(InscriptionId {
+        txid: server.core.broadcast_tx(TransactionTemplate {
+          inputs: &[
+            (block, 1, 0, Default::default()),
+            (
+              block,
+              0,
+              0,
+              Inscription {
+                content_type: Some("text/plain".into()),
+                body: Some("child".into()),
+                parents: vec![parent.value()],
+                ..default()
+              }
+              .to_witness(),
+            ),
+          ],
+          outputs: 2,
+          output_values: &[50 * COIN_VALUE, 50 * COIN_VALUE],
+          ..default()
+        }),
+        index: 0,
+      });
+
+      server.mine_blocks(1);
+    }
+
+    let recipient = address(0);
+
+    server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(3, 1, 1, Default::default())],
+      recipient: Some(recipient.clone()),
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let holder = |script_pubkey: ScriptBuf, inscriptions: &[InscriptionId]| api::CollectionHolder {
+      address: Some(
+        Chain::Regtest
+          .address_from_script(&script_pubkey)
+          .unwrap()
+          .to_string(),
+      ),
+      inscriptions: inscriptions.to_vec(),
+      script_pubkey,
+    };
+
+    let script_pubkey = ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros());
+
+    pretty_assert_eq!(
+      server.get_json::<api::CollectionHolders>(format!("/collection/{parent}/holders")),
+      api::CollectionHolders {
+        holders: vec![
+          holder(script_pubkey.clone(), &children[1..]),
+          holder(recipient.script_pubkey(), &children[..1]),
+        ],
+        more: false,
+        next: None,
+        parent,
+        parent_number: 0,
+        prev: None,
+      }
+    );
+
+    pretty_assert_eq!(
+      server
+        .index
+        .get_collection_holders_paginated(parent, None, 1, 0)
+        .unwrap(),
+      Some((vec![holder(script_pubkey.clone(), &children[1..])], true))
+    );
+
+    pretty_assert_eq!(
+      server
+        .index
+        .get_collection_holders_paginated(parent, None, 1, 1)
+        .unwrap(),
+      Some((
+        vec![holder(recipient.script_pubkey(), &children[..1])],
+        false
+      ))
+    );
+
+    pretty_assert_eq!(
+      server
+        .index
+        .get_collection_holders_paginated(parent, None, 1, 2)
+        .unwrap(),
+      Some((Vec::new(), false))
+    );
+
+    pretty_assert_eq!(
+      server
+        .index
+        .get_collection_holders(parent, Some(5))
+        .unwrap(),
+      Some(vec![holder(script_pubkey.clone(), &children)])
+    );
+
+    pretty_assert_eq!(
+      server
+        .index
+        .get_collection_holders(parent, Some(4))
+        .unwrap(),
+      Some(vec![holder(script_pubkey, &children[..2])])
+    );
+
+    pretty_assert_eq!(
+      server
+        .index
+        .get_collection_holders(parent, Some(2))
+        .unwrap(),
+      Some(Vec::new())
+    );
+
+    server.assert_response_regex(
+      format!("/collection/{parent}/holders"),
+      StatusCode::OK,
+      format!(
+        ".*<title>Inscription 0 Holders</title>.*
+<h1><a href=/inscription/{parent}>Inscription 0</a> Holders</h1>
+<ul>
+  <li>
+    <dl>
+      <dt>address</dt>
+      <dd><a class=collapse href=/address/bcrt1q.*>bcrt1q.*</a></dd>
+      <dt>inscriptions</dt>
+      <dd>2</dd>
+    </dl>
+.*"
+      ),
+    );
+  }
+
+  #[test]
+  fn collection_holders_exclude_burned_children() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-addresses")
+      .ord_flag("--index-inscription-history")
+      .build();
+
+    server.mine_blocks(1);
+
+    let parent = InscriptionId {
+      txid: server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0, inscription("text/plain", "parent").to_witness())],
+        ..default()
+      }),
+      index: 0,
+    };
+
+    server.mine_blocks(1);
+
+    let child = InscriptionId {
+      txid: server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[
+          (2, 1, 0, Default::default()),
+          (
+            2,
+            0,
+            0,
+            Inscription {
+              content_type: Some("text/plain".into()),
+              body: Some("child".into()),
+              parents: vec![parent.value()],
+              ..default()
+            }
+            .to_witness(),
+          ),
+        ],
+        outputs: 2,
+        output_values: &[50 * COIN_VALUE, 50 * COIN_VALUE],
+        ..default()
+      }),
+      index: 0,
+    };
 
-  #[test]
-  fn content_responses_have_cache_control_headers() {
-    let server = TestServer::builder().chain(Chain::Regtest).build();
     server.mine_blocks(1);
 
-    let txid = server.core.broadcast_tx(TransactionTemplate {
-      inputs: &[(1, 0, 0, inscription("text/foo", "hello").to_witness())],
+    server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(3, 1, 1, Default::default())],
+      fee: 0,
+      outputs: 0,
+      op_return_index: Some(0),
+      op_return_value: Some(50 * COIN_VALUE),
+      op_return: Some(
+        script::Builder::new()
+          .push_opcode(opcodes::all::OP_RETURN)
+          .into_script(),
+      ),
       ..default()
     });
 
     server.mine_blocks(1);
 
-    let response = server.get(format!("/content/{}", InscriptionId { txid, index: 0 }));
+    let script_pubkey = ScriptBuf::new_p2wpkh(&WPubkeyHash::all_zeros());
 
-    assert_eq!(response.status(), StatusCode::OK);
-    assert_eq!(
-      response.headers().get(header::CACHE_CONTROL).unwrap(),
-      "public, max-age=1209600, immutable"
+    pretty_assert_eq!(
+      server
+        .index
+        .get_collection_holders(parent, Some(3))
+        .unwrap(),
+      Some(vec![api::CollectionHolder {
+        address: Some(
+          Chain::Regtest
+            .address_from_script(&script_pubkey)
+            .unwrap()
+            .to_string(),
+        ),
+        inscriptions: vec![child],
+        script_pubkey,
+      }])
     );
-  }
-
-  #[test]
-  fn error_content_responses_have_max_age_zero_cache_control_headers() {
-    let server = TestServer::builder().chain(Chain::Regtest).build();
-    let response =
-      server.get("/content/6ac5cacb768794f4fd7a78bf00f2074891fce68bd65c4ff36e77177237aacacai0");
 
-    assert_eq!(response.status(), 404);
-    assert_eq!(
-      response.headers().get(header::CACHE_CONTROL).unwrap(),
-      "no-store"
+    pretty_assert_eq!(
+      server
+        .index
+        .get_collection_holders(parent, Some(4))
+        .unwrap(),
+      Some(Vec::new())
     );
-  }
-
-  #[test]
-  fn inscriptions_page_with_no_prev_or_next() {
-    TestServer::builder()
-      .chain(Chain::Regtest)
-      .index_sats()
-      .build()
-      .assert_response_regex("/inscriptions", StatusCode::OK, ".*prev\nnext.*");
-  }
-
-  #[test]
-  fn inscriptions_page_with_no_next() {
-    let server = TestServer::builder()
-      .chain(Chain::Regtest)
-      .index_sats()
-      .build();
-
-    for i in 0..101 {
-      server.mine_blocks(1);
-      server.core.broadcast_tx(TransactionTemplate {
-        inputs: &[(i + 1, 0, 0, inscription("text/foo", "hello").to_witness())],
-        ..default()
-      });
-    }
-
-    server.mine_blocks(1);
 
-    server.assert_response_regex(
-      "/inscriptions/1",
-      StatusCode::OK,
-      ".*<a class=prev href=/inscriptions/0>prev</a>\nnext.*",
+    pretty_assert_eq!(
+      server.index.get_collection_holders(parent, None).unwrap(),
+      Some(Vec::new())
     );
   }
 
   #[test]
-  fn inscriptions_page_with_no_prev() {
-    let server = TestServer::builder()
-      .chain(Chain::Regtest)
-      .index_sats()
-      .build();
-
-    for i in 0..101 {
-      server.mine_blocks(1);
-      server.core.broadcast_tx(TransactionTemplate {
-        inputs: &[(i + 1, 0, 0, inscription("text/foo", "hello").to_witness())],
-        ..default()
-      });
-    }
+  fn collection_holders_requires_address_index() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
 
     server.mine_blocks(1);
 
-    server.assert_response_regex(
-      "/inscriptions/0",
-      StatusCode::OK,
-      ".*prev\n<a class=next href=/inscriptions/1>next</a>.*",
+    server.assert_response(
+      format!("/collection/{}/holders", inscription_id(1)),
+      StatusCode::NOT_FOUND,
+      "this server has no address index",
     );
   }
 
@@ -6043,16 +9117,163 @@ next
       .push_opcode(opcodes::all::OP_ENDIF)
       .into_script();
 
-    let witness = Witness::from_slice(&[script.into_bytes(), Vec::new()]);
+    let witness = Witness::from_slice(&[script.into_bytes(), Vec::new()]);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, witness)],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let id = InscriptionId { txid, index: 0 };
+    server.assert_response_regex(
+      format!("/inscription/{id}"),
+      StatusCode::OK,
+      format!(
+        ".*<h1>Inscription 0</h1>.*
+<dl>
+  <dt>id</dt>
+  <dd class=collapse>{id}</dd>
+  .*
+  <dt>value</dt>
+  .*
+</dl>
+.*
+"
+      ),
+    );
+
+    let id = InscriptionId { txid, index: 1 };
+    server.assert_response_regex(
+      format!("/inscription/{id}"),
+      StatusCode::OK,
+      ".*
+    <span title=reinscription>♻️</span>
+    <span title=cursed>👹</span>.*",
+    );
+
+    let id = InscriptionId { txid, index: 2 };
+    server.assert_response_regex(
+      format!("/inscription/{id}"),
+      StatusCode::OK,
+      ".*
+    <span title=reinscription>♻️</span>
+    <span title=cursed>👹</span>.*",
+    );
+  }
+
+  #[test]
+  fn charm_reinscription_in_same_tx_with_pointer() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.mine_blocks(3);
+
+    let cursed_inscription = inscription("text/plain", "bar");
+    let reinscription: Inscription = InscriptionTemplate {
+      pointer: Some(0),
+      ..default()
+    }
+    .into();
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[
+        (1, 0, 0, inscription("text/plain", "foo").to_witness()),
+        (2, 0, 0, cursed_inscription.to_witness()),
+        (3, 0, 0, reinscription.to_witness()),
+      ],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let id = InscriptionId { txid, index: 0 };
+    server.assert_response_regex(
+      format!("/inscription/{id}"),
+      StatusCode::OK,
+      format!(
+        ".*<h1>Inscription 0</h1>.*
+<dl>
+  <dt>id</dt>
+  <dd class=collapse>{id}</dd>
+  .*
+  <dt>value</dt>
+  .*
+</dl>
+.*
+"
+      ),
+    );
+
+    let id = InscriptionId { txid, index: 1 };
+    server.assert_response_regex(
+      format!("/inscription/{id}"),
+      StatusCode::OK,
+      ".*
+    <span title=cursed>👹</span>.*",
+    );
+
+    let id = InscriptionId { txid, index: 2 };
+    server.assert_response_regex(
+      format!("/inscription/{id}"),
+      StatusCode::OK,
+      ".*
+    <span title=reinscription>♻️</span>
+    <span title=cursed>👹</span>.*",
+    );
+  }
+
+  #[test]
+  fn charm_unbound() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, envelope(&[b"ord", &[128], &[0]]))],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let id = InscriptionId { txid, index: 0 };
+
+    server.assert_response_regex(
+      format!("/inscription/{id}"),
+      StatusCode::OK,
+      format!(
+        ".*<h1>Inscription -1</h1>.*
+<dl>
+  <dt>id</dt>
+  <dd class=collapse>{id}</dd>
+  <dt>charms</dt>
+  <dd>
+    <span title=cursed>👹</span>
+    <span title=unbound>🔓</span>
+  </dd>
+  .*
+</dl>
+.*
+"
+      ),
+    );
+  }
+
+  #[test]
+  fn charm_lost() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.mine_blocks(1);
 
     let txid = server.core.broadcast_tx(TransactionTemplate {
-      inputs: &[(1, 0, 0, witness)],
+      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
       ..default()
     });
 
+    let id = InscriptionId { txid, index: 0 };
+
     server.mine_blocks(1);
 
-    let id = InscriptionId { txid, index: 0 };
     server.assert_response_regex(
       format!("/inscription/{id}"),
       StatusCode::OK,
@@ -6063,6 +9284,7 @@ next
   <dd class=collapse>{id}</dd>
   .*
   <dt>value</dt>
+  <dd>5000000000</dd>
   .*
 </dl>
 .*
@@ -6070,284 +9292,490 @@ next
       ),
     );
 
-    let id = InscriptionId { txid, index: 1 };
+    server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Default::default())],
+      fee: 50 * COIN_VALUE,
+      ..default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 0);
+
     server.assert_response_regex(
       format!("/inscription/{id}"),
       StatusCode::OK,
-      ".*
-    <span title=reinscription>♻️</span>
-    <span title=cursed>👹</span>.*",
+      format!(
+        ".*<h1>Inscription 0</h1>.*
+<dl>
+  <dt>id</dt>
+  <dd class=collapse>{id}</dd>
+  <dt>charms</dt>
+  <dd>
+    <span title=lost>🤔</span>
+  </dd>
+  .*
+</dl>
+.*
+"
+      ),
     );
+  }
 
-    let id = InscriptionId { txid, index: 2 };
-    server.assert_response_regex(
-      format!("/inscription/{id}"),
-      StatusCode::OK,
-      ".*
-    <span title=reinscription>♻️</span>
-    <span title=cursed>👹</span>.*",
+  #[test]
+  fn sat_recursive_endpoints() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_sats()
+      .build();
+
+    assert_eq!(
+      server.get_json::<api::SatInscriptions>("/r/sat/5000000000"),
+      api::SatInscriptions {
+        ids: Vec::new(),
+        page: 0,
+        more: false
+      }
+    );
+
+    assert_eq!(
+      server.get_json::<api::SatInscription>("/r/sat/5000000000/at/0"),
+      api::SatInscription { id: None }
+    );
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let mut ids = Vec::new();
+    ids.push(InscriptionId { txid, index: 0 });
+
+    for i in 1..111 {
+      let txid = server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(i + 1, 1, 0, inscription("text/plain", "foo").to_witness())],
+        ..default()
+      });
+
+      server.mine_blocks(1);
+
+      ids.push(InscriptionId { txid, index: 0 });
+    }
+
+    let paginated_response = server.get_json::<api::SatInscriptions>("/r/sat/5000000000");
+
+    let equivalent_paginated_response =
+      server.get_json::<api::SatInscriptions>("/r/sat/5000000000/0");
+
+    assert_eq!(paginated_response.ids.len(), 100);
+    assert!(paginated_response.more);
+    assert_eq!(paginated_response.page, 0);
+
+    assert_eq!(
+      paginated_response.ids.len(),
+      equivalent_paginated_response.ids.len()
+    );
+    assert_eq!(paginated_response.more, equivalent_paginated_response.more);
+    assert_eq!(paginated_response.page, equivalent_paginated_response.page);
+
+    let paginated_response = server.get_json::<api::SatInscriptions>("/r/sat/5000000000/1");
+
+    assert_eq!(paginated_response.ids.len(), 11);
+    assert!(!paginated_response.more);
+    assert_eq!(paginated_response.page, 1);
+
+    assert_eq!(
+      server
+        .get_json::<api::SatInscription>("/r/sat/5000000000/at/0")
+        .id,
+      Some(ids[0])
+    );
+
+    assert_eq!(
+      server
+        .get_json::<api::SatInscription>("/r/sat/5000000000/at/-111")
+        .id,
+      Some(ids[0])
+    );
+
+    assert_eq!(
+      server
+        .get_json::<api::SatInscription>("/r/sat/5000000000/at/110")
+        .id,
+      Some(ids[110])
+    );
+
+    assert_eq!(
+      server
+        .get_json::<api::SatInscription>("/r/sat/5000000000/at/-1")
+        .id,
+      Some(ids[110])
     );
+
+    assert!(server
+      .get_json::<api::SatInscription>("/r/sat/5000000000/at/111")
+      .id
+      .is_none());
   }
 
   #[test]
-  fn charm_reinscription_in_same_tx_with_pointer() {
+  fn children_recursive_endpoint() {
     let server = TestServer::builder().chain(Chain::Regtest).build();
+    server.mine_blocks(1);
 
-    server.mine_blocks(3);
-
-    let cursed_inscription = inscription("text/plain", "bar");
-    let reinscription: Inscription = InscriptionTemplate {
-      pointer: Some(0),
+    let parent_txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
       ..default()
+    });
+
+    let parent_inscription_id = InscriptionId {
+      txid: parent_txid,
+      index: 0,
+    };
+
+    server.assert_response(
+      format!("/r/children/{parent_inscription_id}"),
+      StatusCode::NOT_FOUND,
+      &format!("inscription {parent_inscription_id} not found"),
+    );
+
+    server.mine_blocks(1);
+
+    let children_json =
+      server.get_json::<api::Children>(format!("/r/children/{parent_inscription_id}"));
+    assert_eq!(children_json.ids.len(), 0);
+
+    let mut builder = script::Builder::new();
+    for _ in 0..111 {
+      builder = Inscription {
+        content_type: Some("text/plain".into()),
+        body: Some("hello".into()),
+        parents: vec![parent_inscription_id.value()],
+        unrecognized_even_field: false,
+        ..default()
+      }
+      .append_reveal_script_to_builder(builder);
     }
-    .into();
+
+    let witness = Witness::from_slice(&[builder.into_bytes(), Vec::new()]);
 
     let txid = server.core.broadcast_tx(TransactionTemplate {
-      inputs: &[
-        (1, 0, 0, inscription("text/plain", "foo").to_witness()),
-        (2, 0, 0, cursed_inscription.to_witness()),
-        (3, 0, 0, reinscription.to_witness()),
-      ],
+      inputs: &[(2, 0, 0, witness), (2, 1, 0, Default::default())],
       ..default()
     });
 
     server.mine_blocks(1);
 
-    let id = InscriptionId { txid, index: 0 };
-    server.assert_response_regex(
-      format!("/inscription/{id}"),
-      StatusCode::OK,
-      format!(
-        ".*<h1>Inscription 0</h1>.*
-<dl>
-  <dt>id</dt>
-  <dd class=collapse>{id}</dd>
-  .*
-  <dt>value</dt>
-  .*
-</dl>
-.*
-"
-      ),
-    );
+    let first_child_inscription_id = InscriptionId { txid, index: 0 };
+    let hundredth_child_inscription_id = InscriptionId { txid, index: 99 };
+    let hundred_first_child_inscription_id = InscriptionId { txid, index: 100 };
+    let hundred_eleventh_child_inscription_id = InscriptionId { txid, index: 110 };
 
-    let id = InscriptionId { txid, index: 1 };
-    server.assert_response_regex(
-      format!("/inscription/{id}"),
-      StatusCode::OK,
-      ".*
-    <span title=cursed>👹</span>.*",
-    );
+    let children_json =
+      server.get_json::<api::Children>(format!("/r/children/{parent_inscription_id}"));
+
+    assert_eq!(children_json.ids.len(), 100);
+    assert_eq!(children_json.ids[0], first_child_inscription_id);
+    assert_eq!(children_json.ids[99], hundredth_child_inscription_id);
+    assert!(children_json.more);
+    assert_eq!(children_json.page, 0);
+
+    let children_json =
+      server.get_json::<api::Children>(format!("/r/children/{parent_inscription_id}/1"));
+
+    assert_eq!(children_json.ids.len(), 11);
+    assert_eq!(children_json.ids[0], hundred_first_child_inscription_id);
+    assert_eq!(children_json.ids[10], hundred_eleventh_child_inscription_id);
+    assert!(!children_json.more);
+    assert_eq!(children_json.page, 1);
+  }
+
+  #[test]
+  fn parents_recursive_endpoint() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+    server.mine_blocks(1);
+
+    let mut parent_ids = Vec::new();
+    let mut inputs = Vec::new();
+    for i in 0..111 {
+      parent_ids.push(InscriptionId {
+        txid: server.core.broadcast_tx(TransactionTemplate {
+          inputs: &[(i + 1, 0, 0, inscription("text/plain", "hello").to_witness())],
+          ..default()
+        }),
+        index: 0,
+      });
 
-    let id = InscriptionId { txid, index: 2 };
-    server.assert_response_regex(
-      format!("/inscription/{id}"),
-      StatusCode::OK,
-      ".*
-    <span title=reinscription>♻️</span>
-    <span title=cursed>👹</span>.*",
-    );
-  }
+      inputs.push((i + 2, 1, 0, Witness::default()));
 
-  #[test]
-  fn charm_unbound() {
-    let server = TestServer::builder().chain(Chain::Regtest).build();
+      server.mine_blocks(1);
+    }
 
-    server.mine_blocks(1);
+    inputs.insert(
+      0,
+      (
+        112,
+        0,
+        0,
+        Inscription {
+          content_type: Some("text/plain".into()),
+          body: Some("hello".into()),
+          parents: parent_ids.iter().map(|id| id.value()).collect(),
+          ..default()
+        }
+        .to_witness(),
+      ),
+    );
 
     let txid = server.core.broadcast_tx(TransactionTemplate {
-      inputs: &[(1, 0, 0, envelope(&[b"ord", &[128], &[0]]))],
+      inputs: &inputs,
       ..default()
     });
 
     server.mine_blocks(1);
 
-    let id = InscriptionId { txid, index: 0 };
+    let inscription_id = InscriptionId { txid, index: 0 };
 
-    server.assert_response_regex(
-      format!("/inscription/{id}"),
-      StatusCode::OK,
-      format!(
-        ".*<h1>Inscription -1</h1>.*
-<dl>
-  <dt>id</dt>
-  <dd class=collapse>{id}</dd>
-  <dt>charms</dt>
-  <dd>
-    <span title=cursed>👹</span>
-    <span title=unbound>🔓</span>
-  </dd>
-  .*
-</dl>
-.*
-"
-      ),
-    );
+    let first_parent_inscription_id = parent_ids[0];
+    let hundredth_parent_inscription_id = parent_ids[99];
+    let hundred_first_parent_inscription_id = parent_ids[100];
+    let hundred_eleventh_parent_inscription_id = parent_ids[110];
+
+    let parents_json = server.get_json::<api::Inscriptions>(format!("/r/parents/{inscription_id}"));
+
+    assert_eq!(parents_json.ids.len(), 100);
+    assert_eq!(parents_json.ids[0], first_parent_inscription_id);
+    assert_eq!(parents_json.ids[99], hundredth_parent_inscription_id);
+    assert!(parents_json.more);
+    assert_eq!(parents_json.page_index, 0);
+
+    let parents_json =
+      server.get_json::<api::Inscriptions>(format!("/r/parents/{inscription_id}/1"));
+
+    assert_eq!(parents_json.ids.len(), 11);
+    assert_eq!(parents_json.ids[0], hundred_first_parent_inscription_id);
+    assert_eq!(parents_json.ids[10], hundred_eleventh_parent_inscription_id);
+    assert!(!parents_json.more);
+    assert_eq!(parents_json.page_index, 1);
   }
 
   #[test]
-  fn charm_lost() {
-    let server = TestServer::builder().chain(Chain::Regtest).build();
+  fn inscription_history() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-inscription-history")
+      .build();
 
     server.mine_blocks(1);
 
-    let txid = server.core.broadcast_tx(TransactionTemplate {
-      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
+    let create_txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
       ..default()
     });
 
-    let id = InscriptionId { txid, index: 0 };
-
     server.mine_blocks(1);
 
-    server.assert_response_regex(
-      format!("/inscription/{id}"),
-      StatusCode::OK,
-      format!(
-        ".*<h1>Inscription 0</h1>.*
-<dl>
-  <dt>id</dt>
-  <dd class=collapse>{id}</dd>
-  .*
-  <dt>value</dt>
-  <dd>5000000000</dd>
-  .*
-</dl>
-.*
-"
-      ),
-    );
-
-    server.core.broadcast_tx(TransactionTemplate {
+    let transfer_txid = server.core.broadcast_tx(TransactionTemplate {
       inputs: &[(2, 1, 0, Default::default())],
-      fee: 50 * COIN_VALUE,
       ..default()
     });
 
-    server.mine_blocks_with_subsidy(1, 0);
+    server.mine_blocks(1);
+
+    let id = InscriptionId {
+      txid: create_txid,
+      index: 0,
+    };
+
+    let address = Chain::Regtest
+      .address_from_script(&ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::all_zeros()))
+      .unwrap()
+      .to_string();
+
+    let transfers = vec![
+      api::InscriptionTransfer {
+        address: Some(address.clone()),
+        height: 2,
+        new_satpoint: SatPoint {
+          outpoint: OutPoint {
+            txid: create_txid,
+            vout: 0,
+          },
+          offset: 0,
+        },
+        old_satpoint: None,
+        txid: create_txid,
+      },
+      api::InscriptionTransfer {
+        address: Some(address.clone()),
+        height: 3,
+        new_satpoint: SatPoint {
+          outpoint: OutPoint {
+            txid: transfer_txid,
+            vout: 0,
+          },
+          offset: 0,
+        },
+        old_satpoint: Some(SatPoint {
+          outpoint: OutPoint {
+            txid: create_txid,
+            vout: 0,
+          },
+          offset: 0,
+        }),
+        txid: transfer_txid,
+      },
+    ];
+
+    pretty_assert_eq!(
+      server.get_json::<api::InscriptionHistory>(format!("/inscription/{id}/history")),
+      api::InscriptionHistory {
+        id,
+        number: 0,
+        transfers: transfers.clone(),
+      }
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<api::InscriptionHistory>("/inscription/0/history"),
+      api::InscriptionHistory {
+        id,
+        number: 0,
+        transfers: transfers.clone(),
+      }
+    );
 
     server.assert_response_regex(
-      format!("/inscription/{id}"),
+      format!("/inscription/{id}/history"),
       StatusCode::OK,
       format!(
-        ".*<h1>Inscription 0</h1>.*
-<dl>
-  <dt>id</dt>
-  <dd class=collapse>{id}</dd>
-  <dt>charms</dt>
-  <dd>
-    <span title=lost>🤔</span>
-  </dd>
-  .*
-</dl>
-.*
-"
+        ".*<title>Inscription 0 History</title>.*
+<h1><a href=/inscription/{id}>Inscription 0</a> History</h1>
+<ol>
+  <li>
+    <dl>
+      <dt>height</dt>
+      <dd><a href=/block/2>2</a></dd>
+      <dt>transaction</dt>
+      <dd><a class=collapse href=/tx/{create_txid}>{create_txid}</a></dd>
+      <dt>to</dt>
+      <dd><a class=collapse href=/satpoint/{create_txid}:0:0>{create_txid}:0:0</a></dd>
+      <dt>address</dt>
+      <dd><a class=collapse href=/address/{address}>{address}</a></dd>
+    </dl>
+  </li>
+  <li>
+    <dl>
+      <dt>height</dt>
+      <dd><a href=/block/3>3</a></dd>
+      <dt>transaction</dt>
+      <dd><a class=collapse href=/tx/{transfer_txid}>{transfer_txid}</a></dd>
+      <dt>from</dt>
+      <dd><a class=collapse href=/satpoint/{create_txid}:0:0>{create_txid}:0:0</a></dd>
+      <dt>to</dt>
+      <dd><a class=collapse href=/satpoint/{transfer_txid}:0:0>{transfer_txid}:0:0</a></dd>
+      <dt>address</dt>
+      <dd><a class=collapse href=/address/{address}>{address}</a></dd>
+    </dl>
+  </li>
+</ol>.*"
       ),
     );
+
+    pretty_assert_eq!(
+      server.get_json::<api::InscriptionHistory>(format!("/r/inscription/{id}/history")),
+      api::InscriptionHistory {
+        id,
+        number: 0,
+        transfers,
+      }
+    );
   }
 
   #[test]
-  fn sat_recursive_endpoints() {
+  fn inscription_history_records_transaction_that_sent_inscription_to_fees() {
     let server = TestServer::builder()
       .chain(Chain::Regtest)
-      .index_sats()
+      .ord_flag("--index-inscription-history")
       .build();
 
-    assert_eq!(
-      server.get_json::<api::SatInscriptions>("/r/sat/5000000000"),
-      api::SatInscriptions {
-        ids: Vec::new(),
-        page: 0,
-        more: false
-      }
-    );
-
-    assert_eq!(
-      server.get_json::<api::SatInscription>("/r/sat/5000000000/at/0"),
-      api::SatInscription { id: None }
-    );
-
     server.mine_blocks(1);
 
-    let txid = server.core.broadcast_tx(TransactionTemplate {
-      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
+    let create_txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
       ..default()
     });
 
     server.mine_blocks(1);
 
-    let mut ids = Vec::new();
-    ids.push(InscriptionId { txid, index: 0 });
-
-    for i in 1..111 {
-      let txid = server.core.broadcast_tx(TransactionTemplate {
-        inputs: &[(i + 1, 1, 0, inscription("text/plain", "foo").to_witness())],
-        ..default()
-      });
-
-      server.mine_blocks(1);
-
-      ids.push(InscriptionId { txid, index: 0 });
-    }
-
-    let paginated_response = server.get_json::<api::SatInscriptions>("/r/sat/5000000000");
+    let fee_txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 1, 0, Default::default())],
+      fee: 50 * COIN_VALUE,
+      ..default()
+    });
 
-    let equivalent_paginated_response =
-      server.get_json::<api::SatInscriptions>("/r/sat/5000000000/0");
+    let coinbase_txid = server.mine_blocks(1)[0].txdata[0].compute_txid();
 
-    assert_eq!(paginated_response.ids.len(), 100);
-    assert!(paginated_response.more);
-    assert_eq!(paginated_response.page, 0);
+    let id = InscriptionId {
+      txid: create_txid,
+      index: 0,
+    };
 
-    assert_eq!(
-      paginated_response.ids.len(),
-      equivalent_paginated_response.ids.len()
-    );
-    assert_eq!(paginated_response.more, equivalent_paginated_response.more);
-    assert_eq!(paginated_response.page, equivalent_paginated_response.page);
+    let transfer = server
+      .get_json::<api::InscriptionHistory>(format!("/inscription/{id}/history"))
+      .transfers
+      .pop()
+      .unwrap();
 
-    let paginated_response = server.get_json::<api::SatInscriptions>("/r/sat/5000000000/1");
+    assert_eq!(transfer.new_satpoint.outpoint.txid, coinbase_txid);
+    assert_eq!(transfer.txid, fee_txid);
+  }
 
-    assert_eq!(paginated_response.ids.len(), 11);
-    assert!(!paginated_response.more);
-    assert_eq!(paginated_response.page, 1);
+  #[test]
+  fn inscription_history_requires_index() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
 
-    assert_eq!(
-      server
-        .get_json::<api::SatInscription>("/r/sat/5000000000/at/0")
-        .id,
-      Some(ids[0])
-    );
+    server.mine_blocks(1);
 
-    assert_eq!(
-      server
-        .get_json::<api::SatInscription>("/r/sat/5000000000/at/-111")
-        .id,
-      Some(ids[0])
-    );
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
+      ..default()
+    });
 
-    assert_eq!(
-      server
-        .get_json::<api::SatInscription>("/r/sat/5000000000/at/110")
-        .id,
-      Some(ids[110])
+    server.mine_blocks(1);
+
+    let id = InscriptionId { txid, index: 0 };
+
+    server.assert_response(
+      format!("/inscription/{id}/history"),
+      StatusCode::NOT_FOUND,
+      "this server has no inscription history index",
     );
 
-    assert_eq!(
-      server
-        .get_json::<api::SatInscription>("/r/sat/5000000000/at/-1")
-        .id,
-      Some(ids[110])
+    server.assert_response(
+      format!("/r/inscription/{id}/history"),
+      StatusCode::NOT_FOUND,
+      "this server has no inscription history index",
     );
+  }
 
-    assert!(server
-      .get_json::<api::SatInscription>("/r/sat/5000000000/at/111")
-      .id
-      .is_none());
+  #[test]
+  fn inscription_history_not_found() {
+    TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-inscription-history")
+      .build()
+      .assert_response(
+        format!("/r/inscription/{}/history", inscription_id(1)),
+        StatusCode::NOT_FOUND,
+        &format!("inscription {} not found", inscription_id(1)),
+      );
   }
 
   #[test]
-  fn children_recursive_endpoint() {
+  fn child_inscriptions_recursive_endpoint() {
     let server = TestServer::builder().chain(Chain::Regtest).build();
     server.mine_blocks(1);
 
@@ -6362,16 +9790,17 @@ next
     };
 
     server.assert_response(
-      format!("/r/children/{parent_inscription_id}"),
+      format!("/r/children/{parent_inscription_id}/inscriptions"),
       StatusCode::NOT_FOUND,
       &format!("inscription {parent_inscription_id} not found"),
     );
 
     server.mine_blocks(1);
 
-    let children_json =
-      server.get_json::<api::Children>(format!("/r/children/{parent_inscription_id}"));
-    assert_eq!(children_json.ids.len(), 0);
+    let child_inscriptions_json = server.get_json::<api::ChildInscriptions>(format!(
+      "/r/children/{parent_inscription_id}/inscriptions"
+    ));
+    assert_eq!(child_inscriptions_json.children.len(), 0);
 
     let mut builder = script::Builder::new();
     for _ in 0..111 {
@@ -6399,222 +9828,450 @@ next
     let hundred_first_child_inscription_id = InscriptionId { txid, index: 100 };
     let hundred_eleventh_child_inscription_id = InscriptionId { txid, index: 110 };
 
-    let children_json =
-      server.get_json::<api::Children>(format!("/r/children/{parent_inscription_id}"));
+    let child_inscriptions_json = server.get_json::<api::ChildInscriptions>(format!(
+      "/r/children/{parent_inscription_id}/inscriptions"
+    ));
 
-    assert_eq!(children_json.ids.len(), 100);
-    assert_eq!(children_json.ids[0], first_child_inscription_id);
-    assert_eq!(children_json.ids[99], hundredth_child_inscription_id);
-    assert!(children_json.more);
-    assert_eq!(children_json.page, 0);
+    assert_eq!(child_inscriptions_json.children.len(), 100);
 
-    let children_json =
-      server.get_json::<api::Children>(format!("/r/children/{parent_inscription_id}/1"));
+    assert_eq!(
+      child_inscriptions_json.children[0].id,
+      first_child_inscription_id
+    );
+    assert_eq!(child_inscriptions_json.children[0].number, 1); // parent is #0, 1st child is #1
 
-    assert_eq!(children_json.ids.len(), 11);
-    assert_eq!(children_json.ids[0], hundred_first_child_inscription_id);
-    assert_eq!(children_json.ids[10], hundred_eleventh_child_inscription_id);
-    assert!(!children_json.more);
-    assert_eq!(children_json.page, 1);
+    assert_eq!(
+      child_inscriptions_json.children[99].id,
+      hundredth_child_inscription_id
+    );
+    assert_eq!(child_inscriptions_json.children[99].number, -99); // all but 1st child are cursed
+
+    assert!(child_inscriptions_json.more);
+    assert_eq!(child_inscriptions_json.page, 0);
+
+    let child_inscriptions_json = server.get_json::<api::ChildInscriptions>(format!(
+      "/r/children/{parent_inscription_id}/inscriptions/1"
+    ));
+
+    assert_eq!(child_inscriptions_json.children.len(), 11);
+
+    assert_eq!(
+      child_inscriptions_json.children[0].id,
+      hundred_first_child_inscription_id
+    );
+    assert_eq!(child_inscriptions_json.children[0].number, -100);
+
+    assert_eq!(
+      child_inscriptions_json.children[10].id,
+      hundred_eleventh_child_inscription_id
+    );
+    assert_eq!(child_inscriptions_json.children[10].number, -110);
+
+    assert!(!child_inscriptions_json.more);
+    assert_eq!(child_inscriptions_json.page, 1);
   }
 
   #[test]
-  fn parents_recursive_endpoint() {
-    let server = TestServer::builder().chain(Chain::Regtest).build();
-    server.mine_blocks(1);
+  fn inscriptions_in_block_page() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .index_sats()
+      .build();
 
-    let mut parent_ids = Vec::new();
-    let mut inputs = Vec::new();
-    for i in 0..111 {
-      parent_ids.push(InscriptionId {
-        txid: server.core.broadcast_tx(TransactionTemplate {
-          inputs: &[(i + 1, 0, 0, inscription("text/plain", "hello").to_witness())],
-          ..default()
-        }),
-        index: 0,
+    for _ in 0..101 {
+      server.mine_blocks(1);
+    }
+
+    for i in 0..101 {
+      server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(i + 1, 0, 0, inscription("text/foo", "hello").to_witness())],
+        ..default()
       });
+    }
 
-      inputs.push((i + 2, 1, 0, Witness::default()));
+    server.mine_blocks(1);
 
-      server.mine_blocks(1);
-    }
+    server.assert_response_regex(
+      "/inscriptions/block/102",
+      StatusCode::OK,
+      r".*(<a href=/inscription/[[:xdigit:]]{64}i0>.*</a>.*){100}.*",
+    );
 
-    inputs.insert(
-      0,
-      (
-        112,
-        0,
-        0,
-        Inscription {
-          content_type: Some("text/plain".into()),
-          body: Some("hello".into()),
-          parents: parent_ids.iter().map(|id| id.value()).collect(),
-          ..default()
-        }
-        .to_witness(),
+    server.assert_response_regex(
+      "/inscriptions/block/102/1",
+      StatusCode::OK,
+      r".*<a href=/inscription/[[:xdigit:]]{64}i0>.*</a>.*",
+    );
+  }
+
+  #[test]
+  fn inscriptions_filtered_by_content_type_and_media() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-content-types")
+      .build();
+
+    server.mine_blocks(3);
+
+    let text = InscriptionId {
+      txid: server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(
+          1,
+          0,
+          0,
+          inscription("text/plain;charset=utf-8", "foo").to_witness(),
+        )],
+        ..default()
+      }),
+      index: 0,
+    };
+
+    let image = InscriptionId {
+      txid: server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(2, 0, 0, inscription("image/png", "bar").to_witness())],
+        ..default()
+      }),
+      index: 0,
+    };
+
+    server.mine_blocks(1);
+
+    let shouting = InscriptionId {
+      txid: server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(3, 0, 0, inscription("TEXT/PLAIN", "baz").to_witness())],
+        ..default()
+      }),
+      index: 0,
+    };
+
+    server.mine_blocks(1);
+
+    let ids = |path: &str| server.get_json::<api::Inscriptions>(path).ids;
+
+    assert_eq!(
+      ids("/inscriptions?content_type=text/plain"),
+      [shouting, text]
+    );
+    assert_eq!(ids("/inscriptions?content_type=image%2Fpng"), [image]);
+    assert_eq!(ids("/inscriptions?content_type=image/svg+xml"), []);
+    assert_eq!(ids("/inscriptions?media=text"), [text]);
+    assert_eq!(ids("/inscriptions?media=image"), [image]);
+    assert_eq!(ids("/inscriptions?media=unknown"), [shouting]);
+    assert_eq!(ids("/inscriptions?media=image&page=1"), []);
+    assert_eq!(ids("/inscriptions/block/4?content_type=text/plain"), [text]);
+    assert_eq!(
+      ids("/inscriptions/block/5?content_type=text/plain"),
+      [shouting]
+    );
+    assert_eq!(ids("/inscriptions/block/5?media=image"), []);
+
+    server.assert_response_regex(
+      "/inscriptions/block/4?media=image",
+      StatusCode::OK,
+      format!(
+        ".*<h2>Filtered by media image</h2>.*<a href=/inscription/{image}>.*\
+        <a class=prev href=/inscriptions/block/3\\?media=image>3</a>.*"
       ),
     );
 
-    let txid = server.core.broadcast_tx(TransactionTemplate {
-      inputs: &inputs,
-      ..default()
-    });
+    let status = server.get_json::<api::Status>("/status");
+
+    assert_eq!(
+      status.content_types,
+      Some(
+        [("image/png".into(), 1), ("text/plain".into(), 2)]
+          .into_iter()
+          .collect()
+      )
+    );
+
+    assert_eq!(
+      status.media,
+      Some(
+        [
+          ("image".into(), 1),
+          ("text".into(), 1),
+          ("unknown".into(), 1)
+        ]
+        .into_iter()
+        .collect()
+      )
+    );
+
+    server.assert_response_regex(
+      "/status",
+      StatusCode::OK,
+      ".*<dt>media</dt>.*<dt>image</dt>\\s*<dd><a href=/inscriptions\\?media=image>1</a></dd>.*\
+      <dt>content types</dt>.*<dt>image/png</dt>\\s*\
+      <dd><a href=/inscriptions\\?content_type=image%2Fpng>1</a></dd>.*",
+    );
+  }
+
+  #[test]
+  fn inscriptions_filter_requires_content_type_index() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.mine_blocks(1);
+
+    server.assert_response(
+      "/inscriptions?media=image",
+      StatusCode::NOT_FOUND,
+      "this server has no content type index",
+    );
+
+    server.assert_response(
+      "/inscriptions/block/1?content_type=text/plain",
+      StatusCode::NOT_FOUND,
+      "this server has no content type index",
+    );
+  }
+
+  #[test]
+  fn inscriptions_filter_errors() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-content-types")
+      .build();
+
+    server.mine_blocks(1);
+
+    server.assert_response(
+      "/inscriptions?media=foo",
+      StatusCode::BAD_REQUEST,
+      "unknown media `foo`, expected one of: audio, code, font, iframe, image, markdown, model, \
+      pdf, text, unknown, video",
+    );
+
+    server.assert_response(
+      "/inscriptions?media=image&content_type=image/png",
+      StatusCode::BAD_REQUEST,
+      "only one of content_type and media may be specified",
+    );
+  }
+
+  #[test]
+  fn metaprotocol() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-metaprotocols")
+      .build();
+
+    server.mine_blocks(3);
+
+    let mut ids = Vec::new();
+
+    for (i, metaprotocol) in [Some("brc-20"), None, Some("foo/bar"), Some("brc-20")]
+      .into_iter()
+      .enumerate()
+    {
+      ids.push(InscriptionId {
+        txid: server.core.broadcast_tx(TransactionTemplate {
+          inputs: &[(
+            i + 1,
+            0,
+            0,
+            Inscription {
+              content_type: Some("text/plain".into()),
+              body: Some("hello".into()),
+              metaprotocol: metaprotocol.map(|metaprotocol| metaprotocol.into()),
+              ..default()
+            }
+            .to_witness(),
+          )],
+          ..default()
+        }),
+        index: 0,
+      });
 
-    server.mine_blocks(1);
+      server.mine_blocks(1);
+    }
 
-    let inscription_id = InscriptionId { txid, index: 0 };
+    pretty_assert_eq!(
+      server.get_json::<api::Metaprotocol>("/metaprotocol/brc-20"),
+      api::Metaprotocol {
+        inscriptions: vec![ids[3], ids[0]],
+        metaprotocol: "brc-20".into(),
+        more: false,
+        next: None,
+        prev: None,
+      }
+    );
 
-    let first_parent_inscription_id = parent_ids[0];
-    let hundredth_parent_inscription_id = parent_ids[99];
-    let hundred_first_parent_inscription_id = parent_ids[100];
-    let hundred_eleventh_parent_inscription_id = parent_ids[110];
+    pretty_assert_eq!(
+      server
+        .get_json::<api::Metaprotocol>("/metaprotocol/foo%2Fbar")
+        .inscriptions,
+      [ids[2]],
+    );
 
-    let parents_json = server.get_json::<api::Inscriptions>(format!("/r/parents/{inscription_id}"));
+    pretty_assert_eq!(
+      server
+        .get_json::<api::Metaprotocol>("/metaprotocol/brc-20/1")
+        .inscriptions,
+      [],
+    );
 
-    assert_eq!(parents_json.ids.len(), 100);
-    assert_eq!(parents_json.ids[0], first_parent_inscription_id);
-    assert_eq!(parents_json.ids[99], hundredth_parent_inscription_id);
-    assert!(parents_json.more);
-    assert_eq!(parents_json.page_index, 0);
+    pretty_assert_eq!(
+      server.get_json::<api::Metaprotocols>("/metaprotocols"),
+      api::Metaprotocols {
+        metaprotocols: [("brc-20".into(), 2), ("foo/bar".into(), 1)]
+          .into_iter()
+          .collect(),
+      }
+    );
 
-    let parents_json =
-      server.get_json::<api::Inscriptions>(format!("/r/parents/{inscription_id}/1"));
+    server.assert_response_regex(
+      "/metaprotocol/brc-20",
+      StatusCode::OK,
+      format!(
+        ".*<title>Metaprotocol brc-20</title>.*<a href=/inscription/{}>.*<a href=/inscription/{}>.*",
+        ids[3], ids[0]
+      ),
+    );
 
-    assert_eq!(parents_json.ids.len(), 11);
-    assert_eq!(parents_json.ids[0], hundred_first_parent_inscription_id);
-    assert_eq!(parents_json.ids[10], hundred_eleventh_parent_inscription_id);
-    assert!(!parents_json.more);
-    assert_eq!(parents_json.page_index, 1);
+    server.assert_response_regex(
+      "/metaprotocols",
+      StatusCode::OK,
+      ".*<dt><a href=/metaprotocol/brc-20>brc-20</a></dt>\\s*<dd>2</dd>.*",
+    );
   }
 
   #[test]
-  fn child_inscriptions_recursive_endpoint() {
+  fn metaprotocol_requires_metaprotocol_index() {
     let server = TestServer::builder().chain(Chain::Regtest).build();
-    server.mine_blocks(1);
-
-    let parent_txid = server.core.broadcast_tx(TransactionTemplate {
-      inputs: &[(1, 0, 0, inscription("text/plain", "hello").to_witness())],
-      ..default()
-    });
 
-    let parent_inscription_id = InscriptionId {
-      txid: parent_txid,
-      index: 0,
-    };
+    server.mine_blocks(1);
 
     server.assert_response(
-      format!("/r/children/{parent_inscription_id}/inscriptions"),
+      "/metaprotocol/brc-20",
       StatusCode::NOT_FOUND,
-      &format!("inscription {parent_inscription_id} not found"),
+      "this server has no metaprotocol index",
     );
 
-    server.mine_blocks(1);
-
-    let child_inscriptions_json = server.get_json::<api::ChildInscriptions>(format!(
-      "/r/children/{parent_inscription_id}/inscriptions"
-    ));
-    assert_eq!(child_inscriptions_json.children.len(), 0);
+    server.assert_response(
+      "/metaprotocols",
+      StatusCode::NOT_FOUND,
+      "this server has no metaprotocol index",
+    );
+  }
 
-    let mut builder = script::Builder::new();
-    for _ in 0..111 {
-      builder = Inscription {
-        content_type: Some("text/plain".into()),
-        body: Some("hello".into()),
-        parents: vec![parent_inscription_id.value()],
-        unrecognized_even_field: false,
-        ..default()
-      }
-      .append_reveal_script_to_builder(builder);
-    }
+  #[test]
+  fn content_hash() {
+    let server = TestServer::builder()
+      .chain(Chain::Regtest)
+      .ord_flag("--index-content-hashes")
+      .build();
 
-    let witness = Witness::from_slice(&[builder.into_bytes(), Vec::new()]);
+    server.mine_blocks(3);
 
-    let txid = server.core.broadcast_tx(TransactionTemplate {
-      inputs: &[(2, 0, 0, witness), (2, 1, 0, Default::default())],
-      ..default()
-    });
+    let mut ids = Vec::new();
 
-    server.mine_blocks(1);
+    for (i, body) in ["foo", "bar", "foo"].into_iter().enumerate() {
+      ids.push(InscriptionId {
+        txid: server.core.broadcast_tx(TransactionTemplate {
+          inputs: &[(i + 1, 0, 0, inscription("text/plain", body).to_witness())],
+          ..default()
+        }),
+        index: 0,
+      });
 
-    let first_child_inscription_id = InscriptionId { txid, index: 0 };
-    let hundredth_child_inscription_id = InscriptionId { txid, index: 99 };
-    let hundred_first_child_inscription_id = InscriptionId { txid, index: 100 };
-    let hundred_eleventh_child_inscription_id = InscriptionId { txid, index: 110 };
+      server.mine_blocks(1);
+    }
 
-    let child_inscriptions_json = server.get_json::<api::ChildInscriptions>(format!(
-      "/r/children/{parent_inscription_id}/inscriptions"
-    ));
+    let foo = sha256::Hash::hash(b"foo");
 
-    assert_eq!(child_inscriptions_json.children.len(), 100);
+    pretty_assert_eq!(
+      server.get_json::<api::ContentHash>(format!("/content-hash/{foo}")),
+      api::ContentHash {
+        hash: foo,
+        inscriptions: vec![ids[0], ids[2]],
+        more: false,
+        next: None,
+        prev: None,
+      }
+    );
 
-    assert_eq!(
-      child_inscriptions_json.children[0].id,
-      first_child_inscription_id
+    pretty_assert_eq!(
+      server.get_json::<api::Inscriptions>(format!("/r/content-hash/{foo}")),
+      api::Inscriptions {
+        ids: vec![ids[0], ids[2]],
+        more: false,
+        page_index: 0,
+      }
     );
-    assert_eq!(child_inscriptions_json.children[0].number, 1); // parent is #0, 1st child is #1
 
-    assert_eq!(
-      child_inscriptions_json.children[99].id,
-      hundredth_child_inscription_id
+    pretty_assert_eq!(
+      server
+        .get_json::<api::Inscriptions>(format!("/r/content-hash/{foo}/1"))
+        .ids,
+      [],
     );
-    assert_eq!(child_inscriptions_json.children[99].number, -99); // all but 1st child are cursed
 
-    assert!(child_inscriptions_json.more);
-    assert_eq!(child_inscriptions_json.page, 0);
+    let original = server.get_json::<api::Inscription>(format!("/inscription/{}", ids[0]));
+    assert_eq!(original.original, Some(true));
+    assert_eq!(original.duplicate_of, None);
 
-    let child_inscriptions_json = server.get_json::<api::ChildInscriptions>(format!(
-      "/r/children/{parent_inscription_id}/inscriptions/1"
-    ));
+    let unique = server.get_json::<api::Inscription>(format!("/inscription/{}", ids[1]));
+    assert_eq!(unique.original, Some(true));
+    assert_eq!(unique.duplicate_of, None);
 
-    assert_eq!(child_inscriptions_json.children.len(), 11);
+    let duplicate = server.get_json::<api::Inscription>(format!("/inscription/{}", ids[2]));
+    assert_eq!(duplicate.original, Some(false));
+    assert_eq!(duplicate.duplicate_of, Some(ids[0]));
 
-    assert_eq!(
-      child_inscriptions_json.children[0].id,
-      hundred_first_child_inscription_id
+    server.assert_response_regex(
+      format!("/inscription/{}", ids[2]),
+      StatusCode::OK,
+      format!(
+        ".*<dt>duplicate of</dt>\\s*<dd><a href=/inscription/{0}>{0}</a></dd>.*",
+        ids[0]
+      ),
     );
-    assert_eq!(child_inscriptions_json.children[0].number, -100);
 
-    assert_eq!(
-      child_inscriptions_json.children[10].id,
-      hundred_eleventh_child_inscription_id
+    server.assert_response_regex(
+      format!("/content-hash/{foo}"),
+      StatusCode::OK,
+      format!(
+        ".*<dd class=monospace>{foo}</dd>.*<a href=/inscription/{}>.*<a href=/inscription/{}>.*",
+        ids[0], ids[2]
+      ),
     );
-    assert_eq!(child_inscriptions_json.children[10].number, -110);
-
-    assert!(!child_inscriptions_json.more);
-    assert_eq!(child_inscriptions_json.page, 1);
   }
 
   #[test]
-  fn inscriptions_in_block_page() {
-    let server = TestServer::builder()
-      .chain(Chain::Regtest)
-      .index_sats()
-      .build();
-
-    for _ in 0..101 {
-      server.mine_blocks(1);
-    }
-
-    for i in 0..101 {
-      server.core.broadcast_tx(TransactionTemplate {
-        inputs: &[(i + 1, 0, 0, inscription("text/foo", "hello").to_witness())],
-        ..default()
-      });
-    }
+  fn content_hash_requires_content_hash_index() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
 
     server.mine_blocks(1);
 
-    server.assert_response_regex(
-      "/inscriptions/block/102",
-      StatusCode::OK,
-      r".*(<a href=/inscription/[[:xdigit:]]{64}i0>.*</a>.*){100}.*",
+    let hash = sha256::Hash::hash(b"foo");
+
+    server.assert_response(
+      format!("/content-hash/{hash}"),
+      StatusCode::NOT_FOUND,
+      "this server has no content hash index",
     );
 
-    server.assert_response_regex(
-      "/inscriptions/block/102/1",
-      StatusCode::OK,
-      r".*<a href=/inscription/[[:xdigit:]]{64}i0>.*</a>.*",
+    server.assert_response(
+      format!("/r/content-hash/{hash}"),
+      StatusCode::NOT_FOUND,
+      "this server has no content hash index",
     );
+
+    server.mine_blocks(1);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
+      ..default()
+    });
+
+    server.mine_blocks(1);
+
+    let inscription = server
+      .get_json::<api::Inscription>(format!("/inscription/{}", InscriptionId { txid, index: 0 }));
+
+    assert_eq!(inscription.original, None);
+    assert_eq!(inscription.duplicate_of, None);
   }
 
   #[test]
@@ -6714,6 +10371,99 @@ next
     );
   }
 
+  #[test]
+  fn delegators() {
+    let server = TestServer::builder().chain(Chain::Regtest).build();
+
+    server.mine_blocks(3);
+
+    let txid = server.core.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0, inscription("text/plain", "foo").to_witness())],
+      ..default()
+    });
+
+    let delegate = InscriptionId { txid, index: 0 };
+
+    server.assert_response(
+      format!("/inscription/{delegate}/delegators"),
+      StatusCode::NOT_FOUND,
+      &format!("inscription {delegate} not found"),
+    );
+
+    server.assert_response(
+      format!("/r/delegators/{delegate}"),
+      StatusCode::NOT_FOUND,
+      &format!("inscription {delegate} not found"),
+    );
+
+    server.mine_blocks(1);
+
+    server.assert_response_regex(
+      format!("/inscription/{delegate}/delegators"),
+      StatusCode::OK,
+      ".*<h1><a href=/inscription/.*>Inscription 0</a> Delegators</h1>\\s*<h3>No delegators</h3>.*",
+    );
+
+    let mut delegators = Vec::new();
+
+    for i in 1..3 {
+      let txid = server.core.broadcast_tx(TransactionTemplate {
+        inputs: &[(
+          i + 1,
+          0,
+          0,
+          Inscription {
+            delegate: Some(delegate.value()),
+            ..default()
+          }
+          .to_witness(),
+        )],
+        ..default()
+      });
+
+      delegators.push(InscriptionId { txid, index: 0 });
+
+      server.mine_blocks(1);
+    }
+
+    pretty_assert_eq!(
+      server.get_json::<api::Delegators>(format!("/inscription/{delegate}/delegators")),
+      api::Delegators {
+        delegate,
+        delegate_number: 0,
+        delegators: delegators.clone(),
+        more: false,
+        next: None,
+        prev: None,
+      }
+    );
+
+    pretty_assert_eq!(
+      server.get_json::<api::Inscriptions>(format!("/r/delegators/{delegate}")),
+      api::Inscriptions {
+        ids: delegators.clone(),
+        more: false,
+        page_index: 0,
+      }
+    );
+
+    pretty_assert_eq!(
+      server
+        .get_json::<api::Inscriptions>(format!("/r/delegators/{delegate}/1"))
+        .ids,
+      [],
+    );
+
+    server.assert_response_regex(
+      format!("/inscription/{delegate}/delegators"),
+      StatusCode::OK,
+      format!(
+        ".*<title>Inscription 0 Delegators</title>.*<a href=/inscription/{}>.*<a href=/inscription/{}>.*",
+        delegators[0], delegators[1]
+      ),
+    );
+  }
+
   #[test]
   fn undelegated_content() {
     let server = TestServer::builder().chain(Chain::Regtest).build();
@@ -0,0 +1,79 @@
+use {super::*, crate::metrics::Histogram, axum::extract::MatchedPath};
+
+#[derive(Default)]
+struct RouteMetrics {
+  durations: Histogram,
+  statuses: BTreeMap<u16, u64>,
+}
+
+/// Request counts and latencies for each route served, keyed by the route's
+/// path pattern rather than the requested path to bound cardinality.
+#[derive(Default)]
+pub(crate) struct HttpMetrics {
+  routes: Mutex<BTreeMap<String, RouteMetrics>>,
+}
+
+impl HttpMetrics {
+  pub(crate) async fn record(
+    self: Arc<Self>,
+    request: http::Request<body::Body>,
+    next: Next<body::Body>,
+  ) -> Response {
+    let route = request
+      .extensions()
+      .get::<MatchedPath>()
+      .map(|path| path.as_str().to_string())
+      .unwrap_or_else(|| "fallback".into());
+
+    let start = Instant::now();
+
+    let response = next.run(request).await;
+
+    let mut routes = self.routes.lock().unwrap();
+
+    let metrics = routes.entry(route).or_default();
+
+    metrics.durations.observe(start.elapsed());
+
+    *metrics
+      .statuses
+      .entry(response.status().as_u16())
+      .or_default() += 1;
+
+    response
+  }
+
+  pub(crate) fn write(&self, metrics: &mut Metrics) {
+    let routes = self.routes.lock().unwrap();
+
+    metrics.header(
+      "ord_http_requests_total",
+      "counter",
+      "Number of HTTP requests served.",
+    );
+
+    for (route, route_metrics) in routes.iter() {
+      for (status, count) in &route_metrics.statuses {
+        metrics.sample(
+          "ord_http_requests_total",
+          &[("route", route), ("status", &status.to_string())],
+          count,
+        );
+      }
+    }
+
+    metrics.header(
+      "ord_http_request_duration_seconds",
+      "histogram",
+      "Time spent serving HTTP requests.",
+    );
+
+    for (route, route_metrics) in routes.iter() {
+      metrics.histogram(
+        "ord_http_request_duration_seconds",
+        &[("route", route)],
+        &route_metrics.durations,
+      );
+    }
+  }
+}
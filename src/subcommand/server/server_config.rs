@@ -8,6 +8,7 @@ pub struct ServerConfig {
   pub domain: Option<String>,
   pub index_sats: bool,
   pub json_api_enabled: bool,
+  pub mempool: bool,
   pub proxy: Option<Url>,
 }
 
@@ -3,6 +3,7 @@ use {super::*, boilerplate::Boilerplate};
 pub(crate) use {
   crate::subcommand::server::ServerConfig,
   address::AddressHtml,
+  address_history::AddressHistoryHtml,
   block::BlockHtml,
   children::ChildrenHtml,
   clock::ClockSvg,
@@ -10,6 +11,7 @@ pub(crate) use {
   home::HomeHtml,
   iframe::Iframe,
   input::InputHtml,
+  inscription_history::InscriptionHistoryHtml,
   inscriptions::InscriptionsHtml,
   inscriptions_block::InscriptionsBlockHtml,
   metadata::MetadataHtml,
@@ -24,31 +26,44 @@ pub(crate) use {
 };
 
 pub use {
-  blocks::BlocksHtml, inscription::InscriptionHtml, rune::RuneHtml, runes::RunesHtml,
-  status::StatusHtml, transaction::TransactionHtml,
+  blocks::BlocksHtml, collection_holders::CollectionHoldersHtml, content_hash::ContentHashHtml,
+  delegators::DelegatorsHtml, inscription::InscriptionHtml, metaprotocol::MetaprotocolHtml,
+  metaprotocols::MetaprotocolsHtml, rune::RuneHtml, rune_activity::RuneActivityHtml,
+  rune_holders::RuneHoldersHtml, runes::RunesHtml, status::StatusHtml, text_search::TextSearchHtml,
+  transaction::TransactionHtml,
 };
 
 pub mod address;
+mod address_history;
 pub mod block;
 pub mod blocks;
 mod children;
 mod clock;
+pub mod collection_holders;
 pub mod collections;
+pub mod content_hash;
+pub mod delegators;
 mod home;
 mod iframe;
 mod input;
 pub mod inscription;
+mod inscription_history;
 pub mod inscriptions;
 mod inscriptions_block;
 mod metadata;
+pub mod metaprotocol;
+pub mod metaprotocols;
 pub mod output;
 mod parents;
 mod preview;
 mod rare;
 pub mod rune;
+pub mod rune_activity;
+pub mod rune_holders;
 pub mod runes;
 pub mod sat;
 pub mod status;
+pub mod text_search;
 pub mod transaction;
 
 #[derive(Boilerplate)]
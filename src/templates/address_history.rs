@@ -0,0 +1,97 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct AddressHistoryHtml {
+  pub(crate) address: Address,
+  pub(crate) entries: Vec<api::AddressHistoryEntry>,
+  pub(crate) next: Option<String>,
+}
+
+impl PageContent for AddressHistoryHtml {
+  fn title(&self) -> String {
+    format!("Address {} History", self.address)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn address() -> Address {
+    Address::from_str("bc1phuq0vkls6w926zdaem6x9n02z2gg7j2xfudgwddyey7uyquarlgsh40ev8")
+      .unwrap()
+      .require_network(Network::Bitcoin)
+      .unwrap()
+  }
+
+  #[test]
+  fn without_entries() {
+    assert_regex_match!(
+      AddressHistoryHtml {
+        address: address(),
+        entries: Vec::new(),
+        next: None,
+      },
+      "
+        <h1>Address <a href=/address/bc1p.*>bc1p.*</a> History</h1>
+        <h3>No history</h3>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_entries_and_next() {
+    assert_regex_match!(
+      AddressHistoryHtml {
+        address: address(),
+        entries: vec![api::AddressHistoryEntry {
+          height: 3,
+          inscriptions_received: vec![inscription_id(1)],
+          inscriptions_sent: Vec::new(),
+          received: 5000,
+          runes_received: vec![(
+            SpacedRune {
+              rune: Rune(0),
+              spacers: 0,
+            },
+            Decimal {
+              scale: 1,
+              value: 25,
+            },
+            Some('$'),
+          )],
+          runes_sent: Vec::new(),
+          sent: 0,
+          txid: txid(2),
+        }],
+        next: Some("3:1".into()),
+      },
+      "
+        <h1>Address .* History</h1>
+        <ol>
+          <li>
+            <dl>
+              <dt>transaction</dt>
+              <dd><a class=collapse href=/tx/2{64}>2{64}</a></dd>
+              <dt>height</dt>
+              <dd><a href=/block/3>3</a></dd>
+              <dt>received</dt>
+              <dd>5000 sat</dd>
+              <dt>inscriptions received</dt>
+              <dd class=thumbnails>
+                <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+              </dd>
+              <dt>runes received</dt>
+              <dd><a class=monospace href=/rune/A>A</a>: 2.5\\$</dd>
+            </dl>
+          </li>
+        </ol>
+        <div class=center>
+          <a class=next href=/address/bc1p.*/history\\?cursor=3:1>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+}
@@ -0,0 +1,99 @@
+use super::*;
+
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CollectionHoldersHtml {
+  pub holders: Vec<api::CollectionHolder>,
+  pub more: bool,
+  pub next: Option<usize>,
+  pub parent: InscriptionId,
+  pub parent_number: i32,
+  pub prev: Option<usize>,
+}
+
+impl PageContent for CollectionHoldersHtml {
+  fn title(&self) -> String {
+    format!("Inscription {} Holders", self.parent_number)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn without_holders() {
+    assert_regex_match!(
+      CollectionHoldersHtml {
+        holders: Vec::new(),
+        more: false,
+        next: None,
+        parent: inscription_id(1),
+        parent_number: 0,
+        prev: None,
+      },
+      "
+        <h1><a href=/inscription/1{64}i1>Inscription 0</a> Holders</h1>
+        <h3>No holders</h3>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_holders() {
+    assert_regex_match!(
+      CollectionHoldersHtml {
+        holders: vec![
+          api::CollectionHolder {
+            address: Some("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".into()),
+            inscriptions: vec![inscription_id(2), inscription_id(3)],
+            script_pubkey: address(0).script_pubkey(),
+          },
+          api::CollectionHolder {
+            address: None,
+            inscriptions: vec![inscription_id(4)],
+            script_pubkey: ScriptBuf::from_bytes(vec![0x51]),
+          },
+        ],
+        more: true,
+        next: Some(2),
+        parent: inscription_id(1),
+        parent_number: 7,
+        prev: Some(0),
+      },
+      "
+        <h1><a href=/inscription/1{64}i1>Inscription 7</a> Holders</h1>
+        <ul>
+          <li>
+            <dl>
+              <dt>address</dt>
+              <dd><a class=collapse href=/address/bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4>bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</a></dd>
+              <dt>inscriptions</dt>
+              <dd>2</dd>
+            </dl>
+            <div class=thumbnails>
+              <a href=/inscription/2{64}i2>.*</a>
+              <a href=/inscription/3{64}i3>.*</a>
+            </div>
+          </li>
+          <li>
+            <dl>
+              <dt>script pubkey</dt>
+              <dd class=monospace>OP_PUSHNUM_1</dd>
+              <dt>inscriptions</dt>
+              <dd>1</dd>
+            </dl>
+            <div class=thumbnails>
+              <a href=/inscription/4{64}i4>.*</a>
+            </div>
+          </li>
+        </ul>
+        <div class=center>
+          <a class=prev href=/collection/1{64}i1/holders/0>prev</a>
+          <a class=next href=/collection/1{64}i1/holders/2>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+}
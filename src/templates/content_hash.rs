@@ -0,0 +1,69 @@
+use super::*;
+
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ContentHashHtml {
+  pub hash: sha256::Hash,
+  pub inscriptions: Vec<InscriptionId>,
+  pub more: bool,
+  pub next: Option<usize>,
+  pub prev: Option<usize>,
+}
+
+impl PageContent for ContentHashHtml {
+  fn title(&self) -> String {
+    format!("Content Hash {}", self.hash)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn without_inscriptions() {
+    assert_regex_match!(
+      ContentHashHtml {
+        hash: sha256::Hash::all_zeros(),
+        inscriptions: Vec::new(),
+        more: false,
+        next: None,
+        prev: None,
+      },
+      "
+        <h1>Content Hash</h1>
+        <dl>
+          <dt>sha256</dt>
+          <dd class=monospace>0{64}</dd>
+        </dl>
+        <h3>No inscriptions</h3>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_prev_and_next() {
+    assert_regex_match!(
+      ContentHashHtml {
+        hash: sha256::Hash::all_zeros(),
+        inscriptions: vec![inscription_id(1), inscription_id(2)],
+        more: true,
+        next: Some(2),
+        prev: Some(0),
+      },
+      "
+        <h1>Content Hash</h1>
+        .*
+        <div class=thumbnails>
+          <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+          <a href=/inscription/2{64}i2><iframe .* src=/preview/2{64}i2></iframe></a>
+        </div>
+        <div class=center>
+          <a class=prev href=/content-hash/0{64}/0>prev</a>
+          <a class=next href=/content-hash/0{64}/2>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+}
@@ -0,0 +1,67 @@
+use super::*;
+
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DelegatorsHtml {
+  pub delegate: InscriptionId,
+  pub delegate_number: i32,
+  pub delegators: Vec<InscriptionId>,
+  pub more: bool,
+  pub next: Option<usize>,
+  pub prev: Option<usize>,
+}
+
+impl PageContent for DelegatorsHtml {
+  fn title(&self) -> String {
+    format!("Inscription {} Delegators", self.delegate_number)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn without_delegators() {
+    assert_regex_match!(
+      DelegatorsHtml {
+        delegate: inscription_id(1),
+        delegate_number: 0,
+        delegators: Vec::new(),
+        more: false,
+        next: None,
+        prev: None,
+      },
+      "
+        <h1><a href=/inscription/1{64}i1>Inscription 0</a> Delegators</h1>
+        <h3>No delegators</h3>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_prev_and_next() {
+    assert_regex_match!(
+      DelegatorsHtml {
+        delegate: inscription_id(1),
+        delegate_number: 0,
+        delegators: vec![inscription_id(2), inscription_id(3)],
+        more: true,
+        next: Some(2),
+        prev: Some(0),
+      },
+      "
+        <h1><a href=/inscription/1{64}i1>Inscription 0</a> Delegators</h1>
+        <div class=thumbnails>
+          <a href=/inscription/2{64}i2><iframe .* src=/preview/2{64}i2></iframe></a>
+          <a href=/inscription/3{64}i3><iframe .* src=/preview/3{64}i3></iframe></a>
+        </div>
+        <div class=center>
+          <a class=prev href=/inscription/1{64}i1/delegators/0>prev</a>
+          <a class=next href=/inscription/1{64}i1/delegators/2>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+}
@@ -6,6 +6,7 @@ pub struct InscriptionHtml {
   pub charms: u16,
   pub child_count: u64,
   pub children: Vec<InscriptionId>,
+  pub duplicate_of: Option<InscriptionId>,
   pub fee: u64,
   pub height: u32,
   pub inscription: Inscription,
@@ -503,4 +504,30 @@ mod tests {
       .unindent()
     );
   }
+
+  #[test]
+  fn with_duplicate_of() {
+    assert_regex_match!(
+      InscriptionHtml {
+        duplicate_of: Some(inscription_id(2)),
+        fee: 1,
+        inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
+        id: inscription_id(1),
+        number: 1,
+        satpoint: satpoint(1, 0),
+        ..default()
+      },
+      "
+        <h1>Inscription 1</h1>
+        .*
+          <dt>content</dt>
+          <dd><a href=/content/1{64}i1>link</a></dd>
+          <dt>duplicate of</dt>
+          <dd><a href=/inscription/2{64}i2>2{64}i2</a></dd>
+          <dt>content length</dt>
+        .*
+      "
+      .unindent()
+    );
+  }
 }
@@ -0,0 +1,91 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct InscriptionHistoryHtml {
+  pub(crate) id: InscriptionId,
+  pub(crate) number: i32,
+  pub(crate) transfers: Vec<api::InscriptionTransfer>,
+}
+
+impl PageContent for InscriptionHistoryHtml {
+  fn title(&self) -> String {
+    format!("Inscription {} History", self.number)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn without_transfers() {
+    assert_regex_match!(
+      InscriptionHistoryHtml {
+        id: inscription_id(1),
+        number: 0,
+        transfers: Vec::new(),
+      },
+      "
+        <h1><a href=/inscription/1{64}i1>Inscription 0</a> History</h1>
+        <h3>No history</h3>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_transfers() {
+    assert_regex_match!(
+      InscriptionHistoryHtml {
+        id: inscription_id(1),
+        number: 0,
+        transfers: vec![
+          api::InscriptionTransfer {
+            address: None,
+            height: 1,
+            new_satpoint: satpoint(1, 0),
+            old_satpoint: None,
+            txid: txid(1),
+          },
+          api::InscriptionTransfer {
+            address: Some("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".into()),
+            height: 2,
+            new_satpoint: satpoint(2, 0),
+            old_satpoint: Some(satpoint(1, 0)),
+            txid: txid(2),
+          },
+        ],
+      },
+      "
+        <h1><a href=/inscription/1{64}i1>Inscription 0</a> History</h1>
+        <ol>
+          <li>
+            <dl>
+              <dt>height</dt>
+              <dd><a href=/block/1>1</a></dd>
+              <dt>transaction</dt>
+              <dd><a class=collapse href=/tx/1{64}>1{64}</a></dd>
+              <dt>to</dt>
+              <dd><a class=collapse href=/satpoint/1{64}:1:0>1{64}:1:0</a></dd>
+            </dl>
+          </li>
+          <li>
+            <dl>
+              <dt>height</dt>
+              <dd><a href=/block/2>2</a></dd>
+              <dt>transaction</dt>
+              <dd><a class=collapse href=/tx/2{64}>2{64}</a></dd>
+              <dt>from</dt>
+              <dd><a class=collapse href=/satpoint/1{64}:1:0>1{64}:1:0</a></dd>
+              <dt>to</dt>
+              <dd><a class=collapse href=/satpoint/2{64}:2:0>2{64}:2:0</a></dd>
+              <dt>address</dt>
+              <dd><a class=collapse href=/address/bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4>bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</a></dd>
+            </dl>
+          </li>
+        </ol>
+      "
+      .unindent()
+    );
+  }
+}
@@ -2,11 +2,22 @@ use super::*;
 
 #[derive(Boilerplate)]
 pub(crate) struct InscriptionsHtml {
+  pub(crate) filter: Option<InscriptionFilter>,
   pub(crate) inscriptions: Vec<InscriptionId>,
   pub(crate) prev: Option<u32>,
   pub(crate) next: Option<u32>,
 }
 
+impl InscriptionsHtml {
+  fn query(&self) -> String {
+    self
+      .filter
+      .as_ref()
+      .map(InscriptionFilter::query)
+      .unwrap_or_default()
+  }
+}
+
 impl PageContent for InscriptionsHtml {
   fn title(&self) -> String {
     "Inscriptions".into()
@@ -21,6 +32,7 @@ mod tests {
   fn without_prev_and_next() {
     assert_regex_match!(
       InscriptionsHtml {
+        filter: None,
         inscriptions: vec![inscription_id(1), inscription_id(2)],
         prev: None,
         next: None,
@@ -44,6 +56,7 @@ mod tests {
   fn with_prev_and_next() {
     assert_regex_match!(
       InscriptionsHtml {
+        filter: None,
         inscriptions: vec![inscription_id(1), inscription_id(2)],
         prev: Some(1),
         next: Some(2),
@@ -62,4 +75,28 @@ mod tests {
       .unindent()
     );
   }
+
+  #[test]
+  fn with_filter() {
+    assert_regex_match!(
+      InscriptionsHtml {
+        filter: Some(InscriptionFilter::ContentType("image/svg+xml".into())),
+        inscriptions: vec![inscription_id(1), inscription_id(2)],
+        prev: Some(1),
+        next: Some(3),
+      },
+      "
+        <h1>Inscriptions Filtered by content type image/svg\\+xml</h1>
+        <div class=thumbnails>
+          <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+          <a href=/inscription/2{64}i2><iframe .* src=/preview/2{64}i2></iframe></a>
+        </div>
+        .*
+        <a class=prev href=/inscriptions/1\\?content_type=image%2Fsvg%2Bxml>prev</a>
+        <a class=next href=/inscriptions/3\\?content_type=image%2Fsvg%2Bxml>next</a>
+        .*
+      "
+      .unindent()
+    );
+  }
 }
@@ -3,6 +3,7 @@ use super::*;
 #[derive(Boilerplate)]
 pub(crate) struct InscriptionsBlockHtml {
   pub(crate) block: u32,
+  pub(crate) filter: Option<InscriptionFilter>,
   pub(crate) inscriptions: Vec<InscriptionId>,
   pub(crate) prev_block: Option<u32>,
   pub(crate) next_block: Option<u32>,
@@ -14,16 +15,18 @@ impl InscriptionsBlockHtml {
   pub(crate) fn new(
     block: u32,
     current_blockheight: u32,
+    filter: Option<InscriptionFilter>,
     inscriptions: Vec<InscriptionId>,
     more_inscriptions: bool,
     page_index: u32,
   ) -> Result<Self> {
-    if inscriptions.is_empty() {
+    if inscriptions.is_empty() && (filter.is_none() || page_index > 0) {
       return Err(anyhow!("page index {page_index} exceeds inscription count"));
     }
 
     Ok(Self {
       block,
+      filter,
       inscriptions,
       prev_block: block.checked_sub(1),
       next_block: if current_blockheight > block {
@@ -41,6 +44,16 @@ impl InscriptionsBlockHtml {
   }
 }
 
+impl InscriptionsBlockHtml {
+  fn query(&self) -> String {
+    self
+      .filter
+      .as_ref()
+      .map(InscriptionFilter::query)
+      .unwrap_or_default()
+  }
+}
+
 impl PageContent for InscriptionsBlockHtml {
   fn title(&self) -> String {
     format!("Inscriptions in Block {0}", self.block)
@@ -56,6 +69,7 @@ mod tests {
     assert_regex_match!(
       InscriptionsBlockHtml {
         block: 21,
+        filter: None,
         inscriptions: vec![inscription_id(1), inscription_id(2)],
         prev_block: None,
         next_block: None,
@@ -82,6 +96,7 @@ mod tests {
     assert_regex_match!(
       InscriptionsBlockHtml {
         block: 21,
+        filter: None,
         inscriptions: vec![inscription_id(1), inscription_id(2)],
         prev_block: Some(20),
         next_block: Some(22),
@@ -106,4 +121,35 @@ mod tests {
       .unindent()
     );
   }
+
+  #[test]
+  fn with_filter() {
+    assert_regex_match!(
+      InscriptionsBlockHtml {
+        block: 21,
+        filter: Some(InscriptionFilter::Media("image")),
+        inscriptions: vec![inscription_id(1)],
+        prev_block: Some(20),
+        next_block: Some(22),
+        next_page: Some(3),
+        prev_page: Some(1),
+      },
+      "
+        <h1>Inscriptions in <a href=/block/21>Block 21</a></h1>
+        <h2>Filtered by media image</h2>
+        <div class=thumbnails>
+          <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+        </div>
+        .*
+          <a class=prev href=/inscriptions/block/20\\?media=image>20</a>
+        &bull;
+          <a class=prev href=/inscriptions/block/21/1\\?media=image>prev</a>
+          <a class=next href=/inscriptions/block/21/3\\?media=image>next</a>
+        &bull;
+          <a class=next href=/inscriptions/block/22\\?media=image>22</a>
+        .*
+      "
+      .unindent()
+    );
+  }
 }
@@ -0,0 +1,64 @@
+use super::*;
+
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MetaprotocolHtml {
+  pub inscriptions: Vec<InscriptionId>,
+  pub metaprotocol: String,
+  pub more: bool,
+  pub next: Option<u32>,
+  pub prev: Option<u32>,
+}
+
+impl PageContent for MetaprotocolHtml {
+  fn title(&self) -> String {
+    format!("Metaprotocol {}", self.metaprotocol)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn without_inscriptions() {
+    assert_regex_match!(
+      MetaprotocolHtml {
+        inscriptions: Vec::new(),
+        metaprotocol: "brc-20".into(),
+        more: false,
+        next: None,
+        prev: None,
+      },
+      "
+        <h1>Metaprotocol brc-20</h1>
+        <h3>No inscriptions</h3>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_prev_and_next() {
+    assert_regex_match!(
+      MetaprotocolHtml {
+        inscriptions: vec![inscription_id(1), inscription_id(2)],
+        metaprotocol: "foo bar".into(),
+        more: true,
+        next: Some(2),
+        prev: Some(0),
+      },
+      "
+        <h1>Metaprotocol foo bar</h1>
+        <div class=thumbnails>
+          <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+          <a href=/inscription/2{64}i2><iframe .* src=/preview/2{64}i2></iframe></a>
+        </div>
+        <div class=center>
+          <a class=prev href=/metaprotocol/foo%20bar/0>prev</a>
+          <a class=next href=/metaprotocol/foo%20bar/2>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+}
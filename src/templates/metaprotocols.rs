@@ -0,0 +1,52 @@
+use super::*;
+
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MetaprotocolsHtml {
+  pub metaprotocols: BTreeMap<String, u64>,
+}
+
+impl PageContent for MetaprotocolsHtml {
+  fn title(&self) -> String {
+    "Metaprotocols".into()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn without_metaprotocols() {
+    assert_regex_match!(
+      MetaprotocolsHtml {
+        metaprotocols: BTreeMap::new(),
+      },
+      "
+        <h1>Metaprotocols</h1>
+        <h3>No metaprotocols</h3>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_metaprotocols() {
+    assert_regex_match!(
+      MetaprotocolsHtml {
+        metaprotocols: [("brc-20".into(), 2), ("foo/bar".into(), 1)]
+          .into_iter()
+          .collect(),
+      },
+      "
+        <h1>Metaprotocols</h1>
+        <dl>
+          <dt><a href=/metaprotocol/brc-20>brc-20</a></dt>
+          <dd>2</dd>
+          <dt><a href=/metaprotocol/foo%2Fbar>foo/bar</a></dt>
+          <dd>1</dd>
+        </dl>
+      "
+      .unindent()
+    );
+  }
+}
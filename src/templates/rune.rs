@@ -3,6 +3,7 @@ use super::*;
 #[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
 pub struct RuneHtml {
   pub entry: RuneEntry,
+  pub holders: u64,
   pub id: RuneId,
   pub mintable: bool,
   pub parent: Option<InscriptionId>,
@@ -66,6 +67,7 @@ mod tests {
           timestamp: 0,
           turbo: true,
         },
+        holders: 3,
         id: RuneId { block: 10, tx: 9 },
         mintable: true,
         parent: Some(InscriptionId {
@@ -115,6 +117,8 @@ mod tests {
   <dd>0.12%</dd>
   <dt>burned</dt>
   <dd>123456789.123456789\u{A0}@</dd>
+  <dt>holders</dt>
+  <dd><a href=/rune/B•CGDENLQRQWDSLRUGSNLBTMFIJAV/holders>3</a></dd>
   <dt>divisibility</dt>
   <dd>9</dd>
   <dt>symbol</dt>
@@ -151,6 +155,7 @@ mod tests {
           timestamp: 0,
           turbo: false,
         },
+        holders: 3,
         id: RuneId { block: 10, tx: 9 },
         mintable: false,
         parent: None,
@@ -185,6 +190,7 @@ mod tests {
           timestamp: 0,
           turbo: false,
         },
+        holders: 3,
         id: RuneId { block: 10, tx: 9 },
         mintable: false,
         parent: None,
@@ -224,6 +230,7 @@ mod tests {
           timestamp: 0,
           turbo: false,
         },
+        holders: 3,
         id: RuneId { block: 10, tx: 9 },
         mintable: false,
         parent: None,
@@ -280,6 +287,7 @@ mod tests {
           timestamp: 0,
           turbo: false,
         },
+        holders: 3,
         id: RuneId { block: 0, tx: 0 },
         mintable: false,
         parent: Some(InscriptionId {
@@ -317,6 +325,7 @@ mod tests {
           timestamp: 0,
           turbo: false,
         },
+        holders: 3,
         id: RuneId { block: 0, tx: 0 },
         mintable: true,
         parent: Some(InscriptionId {
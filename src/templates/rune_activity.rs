@@ -0,0 +1,133 @@
+use super::*;
+
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RuneActivityHtml {
+  pub divisibility: u8,
+  pub entries: Vec<RuneActivityEntry>,
+  pub id: RuneId,
+  pub more: bool,
+  pub next: Option<usize>,
+  pub prev: Option<usize>,
+  pub rune: SpacedRune,
+  pub symbol: Option<char>,
+}
+
+impl RuneActivityHtml {
+  fn pile(&self, amount: u128) -> Pile {
+    Pile {
+      amount,
+      divisibility: self.divisibility,
+      symbol: self.symbol,
+    }
+  }
+}
+
+impl PageContent for RuneActivityHtml {
+  fn title(&self) -> String {
+    format!("Rune {} Activity", self.rune)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn without_activity() {
+    assert_regex_match!(
+      RuneActivityHtml {
+        divisibility: 0,
+        entries: Vec::new(),
+        id: RuneId { block: 1, tx: 0 },
+        more: false,
+        next: None,
+        prev: None,
+        rune: SpacedRune {
+          rune: Rune(26),
+          spacers: 1
+        },
+        symbol: None,
+      },
+      "
+        <h1><a href=/rune/A•A>A•A</a> Activity</h1>
+        <h3>No activity</h3>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_activity() {
+    assert_regex_match!(
+      RuneActivityHtml {
+        divisibility: 1,
+        entries: vec![
+          RuneActivityEntry {
+            amount: 100,
+            height: 3,
+            inputs: vec![outpoint(1), outpoint(2)],
+            kind: RuneActivityKind::Transfer,
+            output: Some(outpoint(3)),
+            txid: txid(3),
+          },
+          RuneActivityEntry {
+            amount: 5,
+            height: 2,
+            inputs: Vec::new(),
+            kind: RuneActivityKind::Burn,
+            output: None,
+            txid: txid(2),
+          },
+        ],
+        id: RuneId { block: 1, tx: 0 },
+        more: true,
+        next: Some(2),
+        prev: Some(0),
+        rune: SpacedRune {
+          rune: Rune(26),
+          spacers: 1
+        },
+        symbol: Some('%'),
+      },
+      "
+        <h1><a href=/rune/A•A>A•A</a> Activity</h1>
+        <ul>
+          <li>
+            <dl>
+              <dt>kind</dt>
+              <dd>transfer</dd>
+              <dt>amount</dt>
+              <dd>10\u{A0}%</dd>
+              <dt>height</dt>
+              <dd><a href=/block/3>3</a></dd>
+              <dt>transaction</dt>
+              <dd><a class=collapse href=/tx/3{64}>3{64}</a></dd>
+              <dt>from</dt>
+              <dd><a class=collapse href=/output/1{64}:1>1{64}:1</a></dd>
+              <dd><a class=collapse href=/output/2{64}:2>2{64}:2</a></dd>
+              <dt>to</dt>
+              <dd><a class=collapse href=/output/3{64}:3>3{64}:3</a></dd>
+            </dl>
+          </li>
+          <li>
+            <dl>
+              <dt>kind</dt>
+              <dd>burn</dd>
+              <dt>amount</dt>
+              <dd>0.5\u{A0}%</dd>
+              <dt>height</dt>
+              <dd><a href=/block/2>2</a></dd>
+              <dt>transaction</dt>
+              <dd><a class=collapse href=/tx/2{64}>2{64}</a></dd>
+            </dl>
+          </li>
+        </ul>
+        <div class=center>
+          <a class=prev href=/rune/A•A/activity/0>prev</a>
+          <a class=next href=/rune/A•A/activity/2>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+}
@@ -0,0 +1,116 @@
+use super::*;
+
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RuneHoldersHtml {
+  pub divisibility: u8,
+  pub holders: Vec<api::RuneHolder>,
+  pub id: RuneId,
+  pub more: bool,
+  pub next: Option<usize>,
+  pub prev: Option<usize>,
+  pub rune: SpacedRune,
+  pub symbol: Option<char>,
+}
+
+impl RuneHoldersHtml {
+  fn pile(&self, amount: u128) -> Pile {
+    Pile {
+      amount,
+      divisibility: self.divisibility,
+      symbol: self.symbol,
+    }
+  }
+}
+
+impl PageContent for RuneHoldersHtml {
+  fn title(&self) -> String {
+    format!("Rune {} Holders", self.rune)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn without_holders() {
+    assert_regex_match!(
+      RuneHoldersHtml {
+        divisibility: 0,
+        holders: Vec::new(),
+        id: RuneId { block: 1, tx: 0 },
+        more: false,
+        next: None,
+        prev: None,
+        rune: SpacedRune {
+          rune: Rune(26),
+          spacers: 1
+        },
+        symbol: None,
+      },
+      "
+        <h1><a href=/rune/A•A>A•A</a> Holders</h1>
+        <h3>No holders</h3>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_holders() {
+    assert_regex_match!(
+      RuneHoldersHtml {
+        divisibility: 1,
+        holders: vec![
+          api::RuneHolder {
+            address: Some("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".into()),
+            amount: 100,
+            outpoint: None,
+            script_pubkey: None,
+          },
+          api::RuneHolder {
+            address: None,
+            amount: 5,
+            outpoint: Some(outpoint(1)),
+            script_pubkey: None,
+          },
+        ],
+        id: RuneId { block: 1, tx: 0 },
+        more: true,
+        next: Some(2),
+        prev: Some(0),
+        rune: SpacedRune {
+          rune: Rune(26),
+          spacers: 1
+        },
+        symbol: Some('%'),
+      },
+      "
+        <h1><a href=/rune/A•A>A•A</a> Holders</h1>
+        <ul>
+          <li>
+            <dl>
+              <dt>address</dt>
+              <dd><a class=collapse href=/address/bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4>bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</a></dd>
+              <dt>balance</dt>
+              <dd>10\u{A0}%</dd>
+            </dl>
+          </li>
+          <li>
+            <dl>
+              <dt>output</dt>
+              <dd><a class=collapse href=/output/1{64}:1>1{64}:1</a></dd>
+              <dt>balance</dt>
+              <dd>0.5\u{A0}%</dd>
+            </dl>
+          </li>
+        </ul>
+        <div class=center>
+          <a class=prev href=/rune/A•A/holders/0>prev</a>
+          <a class=next href=/rune/A•A/holders/2>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+}
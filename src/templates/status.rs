@@ -5,6 +5,7 @@ pub struct StatusHtml {
   pub address_index: bool,
   pub blessed_inscriptions: u64,
   pub chain: Chain,
+  pub content_types: Option<BTreeMap<String, u64>>,
   pub cursed_inscriptions: u64,
   pub height: Option<u32>,
   pub initial_sync_time: Duration,
@@ -12,6 +13,7 @@ pub struct StatusHtml {
   pub inscriptions: u64,
   pub json_api: bool,
   pub lost_sats: u64,
+  pub media: Option<BTreeMap<String, u64>>,
   pub minimum_rune_for_next_block: Rune,
   pub rune_index: bool,
   pub runes: u64,
@@ -0,0 +1,81 @@
+use super::*;
+
+#[derive(Boilerplate, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TextSearchHtml {
+  pub more: bool,
+  pub next: Option<usize>,
+  pub prev: Option<usize>,
+  pub query: String,
+  pub results: Vec<api::TextSearchResult>,
+}
+
+impl TextSearchHtml {
+  fn href(&self, page: usize) -> String {
+    format!(
+      "/search?q={}&type=text&page={page}",
+      urlencoding::encode(&self.query)
+    )
+  }
+}
+
+impl PageContent for TextSearchHtml {
+  fn title(&self) -> String {
+    format!("Search: {}", self.query)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn without_results() {
+    assert_regex_match!(
+      TextSearchHtml {
+        more: false,
+        next: None,
+        prev: None,
+        query: "foo".into(),
+        results: Vec::new(),
+      },
+      "
+        <h1>Search Results</h1>
+        <p>Inscriptions containing <code>foo</code></p>
+        <h3>No results</h3>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn with_prev_and_next() {
+    assert_regex_match!(
+      TextSearchHtml {
+        more: true,
+        next: Some(2),
+        prev: Some(0),
+        query: "foo bar".into(),
+        results: vec![api::TextSearchResult {
+          id: inscription_id(1),
+          score: 1.5,
+          snippet: "<b>foo</b> bar".into(),
+        }],
+      },
+      "
+        <h1>Search Results</h1>
+        <p>Inscriptions containing <code>foo bar</code></p>
+        <ol>
+          <li>
+            <a class=collapse href=/inscription/1{64}i1>1{64}i1</a>
+            <p>&lt;b&gt;foo&lt;/b&gt; bar</p>
+          </li>
+        </ol>
+        <div class=center>
+          <a class=prev href=/search\\?q=foo%20bar&amp;type=text&amp;page=0>prev</a>
+          <a class=next href=/search\\?q=foo%20bar&amp;type=text&amp;page=2>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+}
@@ -0,0 +1,391 @@
+//! A minimal ZMTP 3.0 subscriber for Bitcoin Core's ZMQ notifications.
+//!
+//! Only the subset of the protocol used by `zmqpubhashblock` and
+//! `zmqpubrawtx` is implemented: the NULL security mechanism, a single SUB
+//! socket over TCP, and multipart messages of the form
+//! `[topic, body, sequence]`.
+
+use {
+  super::*,
+  socket2::{SockRef, TcpKeepalive},
+  std::{
+    io::Write,
+    net::TcpStream,
+    sync::{Condvar, MutexGuard},
+  },
+};
+
+const COMMAND: u8 = 0x04;
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+// Bitcoin Core sends no heartbeats, and may be silent for as long as it takes
+// to find a block, so half-open connections are detected with TCP keepalive
+// probes instead of a read timeout
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(10);
+const KEEPALIVE_TIME: Duration = Duration::from_secs(60);
+const LONG: u8 = 0x02;
+const MAX_FRAME_SIZE: u64 = 1 << 24;
+const MORE: u8 = 0x01;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Topic {
+  Block,
+  Transaction,
+}
+
+impl Topic {
+  fn name(self) -> &'static [u8] {
+    match self {
+      Self::Block => b"hashblock",
+      Self::Transaction => b"rawtx",
+    }
+  }
+}
+
+#[derive(Default)]
+struct Counts {
+  blocks: u64,
+  transactions: u64,
+}
+
+impl Counts {
+  fn get_mut(&mut self, topic: Topic) -> &mut u64 {
+    match topic {
+      Topic::Block => &mut self.blocks,
+      Topic::Transaction => &mut self.transactions,
+    }
+  }
+}
+
+pub(crate) struct Notifications {
+  condvar: Condvar,
+  counts: Mutex<Counts>,
+}
+
+impl Notifications {
+  /// Subscribes to `topics` at `url` on a background thread. If the
+  /// connection fails or drops, it is re-established every `retry_interval`.
+  pub(crate) fn subscribe(
+    url: &str,
+    topics: Vec<Topic>,
+    retry_interval: Duration,
+  ) -> Result<Arc<Self>> {
+    let address = url
+      .strip_prefix("tcp://")
+      .with_context(|| format!("ZMQ URL `{url}` must start with `tcp://`"))?
+      .to_string();
+
+    let notifications = Arc::new(Self {
+      condvar: Condvar::new(),
+      counts: Mutex::new(Counts::default()),
+    });
+
+    {
+      let notifications = notifications.clone();
+      let url = url.to_string();
+
+      thread::spawn(move || loop {
+        if SHUTTING_DOWN.load(atomic::Ordering::Relaxed) {
+          break;
+        }
+
+        match notifications.receive(&address, &topics) {
+          Ok(()) => log::warn!("ZMQ connection to {url} closed"),
+          Err(error) => log::warn!("ZMQ connection to {url} failed: {error}"),
+        }
+
+        thread::sleep(retry_interval);
+      });
+    }
+
+    Ok(notifications)
+  }
+
+  pub(crate) fn listener(self: &Arc<Self>, topic: Topic) -> Listener {
+    Listener {
+      notifications: self.clone(),
+      seen: *self.counts().get_mut(topic),
+      topic,
+    }
+  }
+
+  fn counts(&self) -> MutexGuard<'_, Counts> {
+    self.counts.lock().unwrap()
+  }
+
+  fn notify(&self, topics: &[Topic]) {
+    let mut counts = self.counts();
+
+    for topic in topics {
+      *counts.get_mut(*topic) += 1;
+    }
+
+    self.condvar.notify_all();
+  }
+
+  fn connect(address: &str) -> Result<TcpStream> {
+    let mut last_error = None;
+
+    for address in address.to_socket_addrs()? {
+      match TcpStream::connect_timeout(&address, CONNECT_TIMEOUT) {
+        Ok(stream) => return Ok(stream),
+        Err(error) => last_error = Some(error),
+      }
+    }
+
+    Err(
+      last_error
+        .map(Into::into)
+        .unwrap_or_else(|| anyhow!("`{address}` did not resolve to any addresses")),
+    )
+  }
+
+  fn receive(&self, address: &str, topics: &[Topic]) -> Result {
+    let mut stream = Self::connect(address)?;
+
+    SockRef::from(&stream).set_tcp_keepalive(
+      &TcpKeepalive::new()
+        .with_time(KEEPALIVE_TIME)
+        .with_interval(KEEPALIVE_INTERVAL),
+    )?;
+
+    stream.write_all(&greeting())?;
+
+    let mut peer = [0; 64];
+    stream.read_exact(&mut peer)?;
+
+    ensure!(
+      peer[0] == 0xFF && peer[9] == 0x7F,
+      "peer sent invalid ZMTP greeting"
+    );
+
+    ensure!(peer[10] >= 3, "peer speaks unsupported ZMTP {}", peer[10]);
+
+    ensure!(
+      &peer[12..16] == b"NULL",
+      "peer requires unsupported security mechanism"
+    );
+
+    let mut ready = vec![5];
+    ready.extend_from_slice(b"READY");
+    ready.push(11);
+    ready.extend_from_slice(b"Socket-Type");
+    ready.extend_from_slice(&3u32.to_be_bytes());
+    ready.extend_from_slice(b"SUB");
+    write_frame(&mut stream, COMMAND, &ready)?;
+
+    let (flags, command) = read_frame(&mut stream)?;
+
+    ensure!(
+      flags & COMMAND != 0 && command.get(1..6) == Some(b"READY"),
+      "peer did not send READY command"
+    );
+
+    for topic in topics {
+      let mut subscription = vec![1];
+      subscription.extend_from_slice(topic.name());
+      write_frame(&mut stream, 0, &subscription)?;
+    }
+
+    // notifications may have been missed while disconnected
+    self.notify(topics);
+
+    loop {
+      let mut message = Vec::new();
+
+      loop {
+        let (flags, frame) = match read_frame(&mut stream) {
+          Ok(frame) => frame,
+          Err(error) => {
+            if error
+              .downcast_ref::<io::Error>()
+              .is_some_and(|error| error.kind() == io::ErrorKind::UnexpectedEof)
+            {
+              return Ok(());
+            }
+
+            return Err(error);
+          }
+        };
+
+        if flags & COMMAND != 0 {
+          continue;
+        }
+
+        message.push(frame);
+
+        if flags & MORE == 0 {
+          break;
+        }
+      }
+
+      if let Some(topic) = topics
+        .iter()
+        .find(|topic| message.first().map(Vec::as_slice) == Some(topic.name()))
+      {
+        self.notify(&[*topic]);
+      }
+    }
+  }
+}
+
+pub(crate) struct Listener {
+  notifications: Arc<Notifications>,
+  seen: u64,
+  topic: Topic,
+}
+
+impl Listener {
+  /// Blocks until a notification arrives or `timeout` elapses, whichever
+  /// comes first.
+  pub(crate) fn wait(&mut self, timeout: Duration) {
+    let deadline = Instant::now() + timeout;
+
+    let mut counts = self.notifications.counts();
+
+    loop {
+      let count = *counts.get_mut(self.topic);
+
+      if count != self.seen {
+        self.seen = count;
+        return;
+      }
+
+      let now = Instant::now();
+
+      if now >= deadline {
+        return;
+      }
+
+      counts = self
+        .notifications
+        .condvar
+        .wait_timeout(counts, deadline - now)
+        .unwrap()
+        .0;
+    }
+  }
+}
+
+fn greeting() -> [u8; 64] {
+  let mut greeting = [0; 64];
+  greeting[0] = 0xFF;
+  greeting[9] = 0x7F;
+  greeting[10] = 3;
+  greeting[12..16].copy_from_slice(b"NULL");
+  greeting
+}
+
+fn read_frame(stream: &mut TcpStream) -> Result<(u8, Vec<u8>)> {
+  let mut flags = [0];
+  stream.read_exact(&mut flags)?;
+  let flags = flags[0];
+
+  let size = if flags & LONG != 0 {
+    let mut size = [0; 8];
+    stream.read_exact(&mut size)?;
+    u64::from_be_bytes(size)
+  } else {
+    let mut size = [0];
+    stream.read_exact(&mut size)?;
+    size[0].into()
+  };
+
+  ensure!(
+    size <= MAX_FRAME_SIZE,
+    "ZMTP frame of {size} bytes exceeds maximum of {MAX_FRAME_SIZE} bytes"
+  );
+
+  let mut frame = vec![0; usize::try_from(size).unwrap()];
+  stream.read_exact(&mut frame)?;
+
+  Ok((flags, frame))
+}
+
+fn write_frame(stream: &mut TcpStream, flags: u8, body: &[u8]) -> io::Result<()> {
+  let mut frame = Vec::new();
+
+  match u8::try_from(body.len()) {
+    Ok(size) => {
+      frame.push(flags);
+      frame.push(size);
+    }
+    Err(_) => {
+      frame.push(flags | LONG);
+      frame.extend_from_slice(&u64::try_from(body.len()).unwrap().to_be_bytes());
+    }
+  }
+
+  frame.extend_from_slice(body);
+
+  stream.write_all(&frame)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn url_must_use_tcp() {
+    assert_eq!(
+      Notifications::subscribe("ipc:///tmp/bitcoind", vec![Topic::Block], Duration::ZERO)
+        .err()
+        .unwrap()
+        .to_string(),
+      "ZMQ URL `ipc:///tmp/bitcoind` must start with `tcp://`",
+    );
+  }
+
+  #[test]
+  fn listener_is_woken_by_block_notifications() {
+    let core = mockcore::builder().zmq(true).build();
+
+    let notifications =
+      Notifications::subscribe(&core.zmq_url(), vec![Topic::Block], Duration::from_secs(1))
+        .unwrap();
+
+    let mut listener = notifications.listener(Topic::Block);
+
+    // the first notification is sent on connection
+    listener.wait(Duration::from_secs(10));
+
+    let seen = listener.seen;
+
+    for _ in 0..100 {
+      core.mine_blocks(1);
+
+      listener.wait(Duration::from_millis(100));
+
+      if listener.seen != seen {
+        return;
+      }
+    }
+
+    panic!("no block notification received");
+  }
+
+  #[test]
+  fn listener_times_out_without_notifications() {
+    let notifications = Arc::new(Notifications {
+      condvar: Condvar::new(),
+      counts: Mutex::new(Counts::default()),
+    });
+
+    let mut listener = notifications.listener(Topic::Block);
+
+    let start = Instant::now();
+    listener.wait(Duration::from_millis(50));
+    assert!(start.elapsed() >= Duration::from_millis(50));
+
+    notifications.notify(&[Topic::Transaction]);
+
+    listener.wait(Duration::ZERO);
+    assert_eq!(listener.seen, 0);
+
+    notifications.notify(&[Topic::Block]);
+
+    let start = Instant::now();
+    listener.wait(Duration::from_secs(60));
+    assert!(start.elapsed() < Duration::from_secs(60));
+    assert_eq!(listener.seen, 1);
+  }
+}
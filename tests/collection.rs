@@ -0,0 +1,71 @@
+use {super::*, ord::subcommand::collection::snapshot::Output};
+
+#[test]
+fn snapshot_requires_address_index() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  CommandBuilder::new(format!(
+    "--regtest collection snapshot --parent {}i0",
+    "0".repeat(64),
+  ))
+  .core(&core)
+  .expected_exit_code(1)
+  .expected_stderr(
+    "error: `ord collection snapshot` requires index created with `--index-addresses` flag\n",
+  )
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn snapshot() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(&core, &["--regtest", "--index-addresses"], &[]);
+
+  create_wallet(&core, &ord);
+
+  core.mine_blocks(1);
+
+  let parent = CommandBuilder::new("--regtest wallet inscribe --fee-rate 1 --file parent.png")
+    .write("parent.png", [1; 520])
+    .core(&core)
+    .ord(&ord)
+    .run_and_deserialize_output::<Batch>()
+    .inscriptions[0]
+    .id;
+
+  core.mine_blocks(1);
+
+  let child = CommandBuilder::new(format!(
+    "--regtest wallet inscribe --fee-rate 1 --parent {parent} --file child.png"
+  ))
+  .write("child.png", [2; 520])
+  .core(&core)
+  .ord(&ord)
+  .run_and_deserialize_output::<Batch>()
+  .inscriptions
+  .remove(0);
+
+  core.mine_blocks(1);
+
+  let output = CommandBuilder::new(format!(
+    "--regtest --index-addresses collection snapshot --parent {parent}"
+  ))
+  .core(&core)
+  .run_and_deserialize_output::<Output>();
+
+  let address = child.destination.assume_checked();
+
+  pretty_assert_eq!(
+    output,
+    Output {
+      height: 3,
+      holders: vec![api::CollectionHolder {
+        address: Some(address.to_string()),
+        inscriptions: vec![child.id],
+        script_pubkey: address.script_pubkey(),
+      }],
+      parent,
+    }
+  );
+}
@@ -90,3 +90,71 @@ fn export_inscription_number_to_id_tsv() {
     &ord::Object::InscriptionId(inscription),
   );
 }
+
+#[test]
+fn rollback_to_earlier_height() {
+  let core = mockcore::spawn();
+  core.mine_blocks(5);
+
+  let tempdir = TempDir::new().unwrap();
+
+  let index_path = tempdir.path().join("foo.redb");
+
+  CommandBuilder::new(format!(
+    "--index {} --index-undo-log index update",
+    index_path.display()
+  ))
+  .core(&core)
+  .run_and_extract_stdout();
+
+  assert_eq!(
+    CommandBuilder::new(format!(
+      "--index {} index rollback --height 2",
+      index_path.display()
+    ))
+    .core(&core)
+    .run_and_deserialize_output::<ord::subcommand::index::rollback::Output>(),
+    ord::subcommand::index::rollback::Output {
+      blocks: 3,
+      height: 2,
+    }
+  );
+
+  CommandBuilder::new(format!(
+    "--index {} index rollback --height 2",
+    index_path.display()
+  ))
+  .core(&core)
+  .expected_stderr(
+    "error: cannot roll back to height 2: index has 3 blocks, so it is already at or below that height\n",
+  )
+  .expected_exit_code(1)
+  .run_and_extract_stdout();
+}
+
+#[test]
+fn check_consistent_index() {
+  let core = mockcore::spawn();
+  core.mine_blocks(2);
+
+  let tempdir = TempDir::new().unwrap();
+
+  let index_path = tempdir.path().join("foo.redb");
+
+  CommandBuilder::new(format!(
+    "--index {} --index-sats index update",
+    index_path.display()
+  ))
+  .core(&core)
+  .run_and_extract_stdout();
+
+  assert_eq!(
+    CommandBuilder::new(format!("--index {} index check", index_path.display()))
+      .core(&core)
+      .run_and_deserialize_output::<ord::subcommand::index::check::Output>(),
+    ord::subcommand::index::check::Output {
+      block_count: 3,
+      violations: Vec::new(),
+    }
+  );
+}
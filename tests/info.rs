@@ -16,9 +16,17 @@ fn json_with_satoshi_index() {
   "metadata_bytes": \d+,
   "outputs_traversed": 1,
   "page_size": \d+,
+  "reorgs": 0,
   "sat_ranges": 1,
   "stored_bytes": \d+,
   "tables": .*,
+  "throughput": \{
+    "blocks": 1,
+    "blocks_per_second": .*,
+    "seconds": .*,
+    "transactions": 1,
+    "transactions_per_second": .*
+  \},
   "total_bytes": \d+,
   "transactions": \[
     \{
@@ -50,9 +58,17 @@ fn json_without_satoshi_index() {
   "metadata_bytes": \d+,
   "outputs_traversed": 0,
   "page_size": \d+,
+  "reorgs": 0,
   "sat_ranges": 0,
   "stored_bytes": \d+,
   "tables": .*,
+  "throughput": \{
+    "blocks": 1,
+    "blocks_per_second": .*,
+    "seconds": .*,
+    "transactions": 1,
+    "transactions_per_second": .*
+  \},
   "total_bytes": \d+,
   "transactions": \[
     \{
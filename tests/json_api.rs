@@ -163,12 +163,14 @@ fn get_inscription() {
       children: Vec::new(),
       content_length: Some(3),
       content_type: Some("text/plain;charset=utf-8".to_string()),
+      duplicate_of: None,
       effective_content_type: Some("text/plain;charset=utf-8".to_string()),
       fee: 138,
       height: 2,
       id: inscription_id,
       number: 0,
       next: None,
+      original: None,
       value: Some(10000),
       parents: Vec::new(),
       previous: None,
@@ -346,6 +348,7 @@ fn get_output() {
         InscriptionId { txid, index: 2 },
       ],
       indexed: true,
+      pending: None,
       runes: BTreeMap::new(),
       sat_ranges: Some(vec![
         (5000000000, 10000000000,),
@@ -500,6 +503,7 @@ fn get_status() {
       address_index: false,
       blessed_inscriptions: 1,
       chain: Chain::Regtest,
+      content_types: None,
       cursed_inscriptions: 0,
       height: Some(3),
       initial_sync_time: dummy_duration,
@@ -507,6 +511,7 @@ fn get_status() {
       inscriptions: 1,
       json_api: true,
       lost_sats: 0,
+      media: None,
       minimum_rune_for_next_block: Rune(99218849511960410),
       rune_index: true,
       runes: 0,
@@ -560,6 +565,7 @@ fn get_runes() {
         timestamp: 10,
         turbo: false,
       },
+      holders: 1,
       id: RuneId { block: 10, tx: 1 },
       mintable: false,
       parent: Some(InscriptionId {
@@ -749,6 +755,7 @@ fn outputs_address() {
         vout: 0
       },
       indexed: true,
+      pending: None,
       runes: BTreeMap::new(),
       sat_ranges: None,
       script_pubkey: ScriptBuf::from(
@@ -793,6 +800,7 @@ fn outputs_address() {
         vout: 0
       },
       indexed: true,
+      pending: None,
       runes: expected_runes,
       sat_ranges: None,
       script_pubkey: ScriptBuf::from(
@@ -827,6 +835,7 @@ fn outputs_address() {
         vout: 0
       },
       indexed: true,
+      pending: None,
       runes: BTreeMap::new(),
       sat_ranges: None,
       script_pubkey: ScriptBuf::from(
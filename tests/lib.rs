@@ -54,6 +54,7 @@ mod expected;
 mod test_server;
 
 mod balances;
+mod collection;
 mod decode;
 mod epochs;
 mod find;
@@ -342,6 +343,8 @@ fn batch(core: &mockcore::Handle, ord: &TestServer, batchfile: batch::File) -> E
 
   let RuneId { block, tx } = id;
 
+  let holders = u8::from(premine.to_integer(divisibility).unwrap() > 0);
+
   ord.assert_response_regex(
     format!("/rune/{rune}"),
     format!(
@@ -361,6 +364,8 @@ fn batch(core: &mockcore::Handle, ord: &TestServer, batchfile: batch::File) -> E
   <dd>.*</dd>
   <dt>burned</dt>
   <dd>0 {symbol}</dd>
+  <dt>holders</dt>
+  <dd><a href=/rune/{rune}/holders>{holders}</a></dd>
   <dt>divisibility</dt>
   <dd>{divisibility}</dd>
   <dt>symbol</dt>
@@ -141,3 +141,88 @@ fn two_runes() {
     }
   );
 }
+
+#[test]
+fn snapshot_requires_rune_activity_index() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  CommandBuilder::new("--regtest --index-runes runes snapshot --rune AAAAAAAAAAAAA --height 0")
+    .core(&core)
+    .expected_exit_code(1)
+    .expected_stderr(
+      "error: `ord runes snapshot` requires index created with `--index-runes` and `--index-rune-activity` flags\n",
+    )
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn snapshot() {
+  let core = mockcore::builder().network(Network::Regtest).build();
+
+  let ord = TestServer::spawn_with_server_args(
+    &core,
+    &["--regtest", "--index-runes", "--index-rune-activity"],
+    &[],
+  );
+
+  create_wallet(&core, &ord);
+
+  let etch = etch(&core, &ord, Rune(RUNE));
+
+  let location = etch.output.rune.unwrap().location.unwrap();
+
+  let snapshot = |height: u32| {
+    CommandBuilder::new(format!(
+      "--regtest --index-runes --index-rune-activity runes snapshot --rune {} --height {height}",
+      Rune(RUNE),
+    ))
+    .core(&core)
+    .run_and_deserialize_output::<ord::subcommand::runes::snapshot::Output>()
+  };
+
+  pretty_assert_eq!(
+    snapshot(6),
+    api::RuneSnapshot {
+      divisibility: 0,
+      height: 6,
+      holders: Vec::new(),
+      id: etch.id,
+      rune: SpacedRune {
+        rune: Rune(RUNE),
+        spacers: 0,
+      },
+      symbol: Some('¢'),
+    }
+  );
+
+  pretty_assert_eq!(
+    snapshot(7).holders,
+    vec![api::RuneHolder {
+      address: None,
+      amount: 1000,
+      outpoint: Some(location),
+      script_pubkey: None,
+    }]
+  );
+
+  let csv = CommandBuilder::new(format!(
+    "--regtest --index-runes --index-rune-activity runes snapshot --rune {} --height 7 --csv snapshot.csv",
+    Rune(RUNE),
+  ))
+  .core(&core)
+  .run_and_extract_file("snapshot.csv");
+
+  assert_eq!(
+    csv,
+    format!("outpoint,amount,balance\n{location},1000,1000\n")
+  );
+
+  CommandBuilder::new(format!(
+    "--regtest --index-runes --index-rune-activity runes snapshot --rune {} --height 100",
+    Rune(RUNE),
+  ))
+  .core(&core)
+  .expected_exit_code(1)
+  .expected_stderr("error: height 100 is above index height 7\n")
+  .run_and_extract_stdout();
+}
@@ -769,6 +769,64 @@ fn run_no_sync() {
   child.kill().unwrap();
 }
 
+#[test]
+fn read_only_server_follows_index_daemon() {
+  let core = mockcore::spawn();
+
+  core.mine_blocks(1);
+
+  let port = TcpListener::bind("127.0.0.1:0")
+    .unwrap()
+    .local_addr()
+    .unwrap()
+    .port();
+
+  let tempdir = Arc::new(TempDir::new().unwrap());
+
+  assert!(CommandBuilder::new("index update")
+    .core(&core)
+    .temp_dir(tempdir.clone())
+    .command()
+    .status()
+    .unwrap()
+    .success());
+
+  let mut server = CommandBuilder::new(format!(
+    "server --read-only --address 127.0.0.1 --http-port {port}",
+  ))
+  .core(&core)
+  .temp_dir(tempdir.clone())
+  .command()
+  .spawn()
+  .unwrap();
+
+  let mut daemon = CommandBuilder::new("index update --daemon --polling-interval 100ms")
+    .core(&core)
+    .temp_dir(tempdir.clone())
+    .command()
+    .spawn()
+    .unwrap();
+
+  core.mine_blocks(2);
+
+  for attempt in 0.. {
+    if let Ok(response) = reqwest::blocking::get(format!("http://localhost:{port}/blockheight")) {
+      if response.status() == 200 && response.text().unwrap() == "3" {
+        break;
+      }
+    }
+
+    if attempt == 200 {
+      panic!("Server did not pick up blocks indexed by daemon");
+    }
+
+    thread::sleep(Duration::from_millis(50));
+  }
+
+  daemon.kill().unwrap();
+  server.kill().unwrap();
+}
+
 #[test]
 fn authentication() {
   let core = mockcore::spawn();
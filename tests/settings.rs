@@ -11,26 +11,43 @@ fn default() {
   "bitcoin_rpc_password": null,
   "bitcoin_rpc_url": "127.0.0.1:8332",
   "bitcoin_rpc_username": null,
+  "bitcoin_zmq_url": null,
+  "block_files": false,
   "chain": "mainnet",
+  "chain_tip_distance": 21,
   "commit_interval": 5000,
   "config": null,
   "config_dir": null,
   "cookie_file": ".*\.cookie",
   "data_dir": ".*",
+  "esplora_url": null,
   "height_limit": null,
   "hidden": \[\],
   "http_port": null,
   "index": ".*index\.redb",
+  "index_address_history": false,
   "index_addresses": false,
   "index_cache_size": \d+,
+  "index_content_hashes": false,
+  "index_content_types": false,
+  "index_events": false,
+  "index_full_text": false,
+  "index_inscription_history": false,
+  "index_metaprotocols": false,
+  "index_rune_activity": false,
   "index_runes": false,
   "index_sats": false,
   "index_transactions": false,
+  "index_undo_log": false,
   "integration_test": false,
+  "max_savepoints": 2,
   "no_index_inscriptions": false,
+  "rollback_unrecoverable_reorgs": false,
+  "savepoint_interval": 10,
   "server_password": null,
   "server_url": null,
-  "server_username": null
+  "server_username": null,
+  "undo_log_depth": null
 \}
 "#,
     )